@@ -65,6 +65,43 @@ impl AudioPacket {
         output.extend(downmixed_iter);
     }
 
+    /// Split interleaved audio into left/right buffers
+    /// Mono input is copied to both sides; channels beyond the first two are ignored
+    pub fn to_stereo_with_buffers(&self, left: &mut Vec<f32>, right: &mut Vec<f32>) {
+        self.channel_pair_with_buffers(0, 1, left, right);
+    }
+
+    /// Extract two channels (0-based) into separate buffers
+    /// Indices past the last channel use the last one, so mono input lands in both
+    pub fn channel_pair_with_buffers(&self, first: usize, second: usize, a: &mut Vec<f32>, b: &mut Vec<f32>) {
+        a.clear();
+        b.clear();
+
+        if self.channels == 0 {
+            return;
+        }
+
+        let channels = self.channels as usize;
+        let (first, second) = (first.min(channels - 1), second.min(channels - 1));
+        for frame in self.samples.chunks_exact(channels) {
+            a.push(frame[first]);
+            b.push(frame[second]);
+        }
+    }
+
+    /// Split into mid (L+R)/2 and side (L-R)/2 buffers
+    /// Side carries everything that differs between channels, so out-of-phase
+    /// content shows up there and cancels in mid
+    pub fn to_mid_side_with_buffers(&self, mid: &mut Vec<f32>, side: &mut Vec<f32>) {
+        self.to_stereo_with_buffers(mid, side);
+
+        for (m, s) in mid.iter_mut().zip(side.iter_mut()) {
+            let (left, right) = (*m, *s);
+            *m = (left + right) * 0.5;
+            *s = (left - right) * 0.5;
+        }
+    }
+
     /// Get the duration of audio in this packet (in seconds)
     #[allow(dead_code)]
     pub fn duration_secs(&self) -> f32 {
@@ -536,6 +573,28 @@ mod tests {
         assert_eq!(mono, samples);
     }
     
+    #[test]
+    fn test_channel_pair_selection() {
+        // 4 channels, two frames: 1 2 3 4 | 5 6 7 8
+        let packet = AudioPacket {
+            samples: vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0],
+            sample_rate: 48000,
+            channels: 4,
+            timestamp: Instant::now(),
+        };
+        let (mut a, mut b) = (Vec::new(), Vec::new());
+
+        packet.channel_pair_with_buffers(3, 1, &mut a, &mut b);
+        assert_eq!((a.as_slice(), b.as_slice()), (&[4.0, 8.0][..], &[2.0, 6.0][..]));
+
+        // Out of range falls back to the last channel
+        packet.channel_pair_with_buffers(0, 9, &mut a, &mut b);
+        assert_eq!(b, vec![4.0, 8.0]);
+
+        packet.to_stereo_with_buffers(&mut a, &mut b);
+        assert_eq!((a.as_slice(), b.as_slice()), (&[1.0, 5.0][..], &[2.0, 6.0][..]));
+    }
+
     #[test]
     fn test_audio_packet_duration() {
         // 2 seconds of stereo audio at 48kHz
@@ -749,4 +808,36 @@ mod tests {
         println!(">>> Speedup Factor:     {:.2}x FASTER", ratio);
         println!("==============================================\n");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_to_stereo_with_buffers() {
+        // 4 channels: only the first two are used
+        let packet = AudioPacket {
+            samples: vec![1.0, -1.0, 0.3, 0.3, 0.5, 0.25, 0.3, 0.3],
+            sample_rate: 48000,
+            channels: 4,
+            timestamp: Instant::now(),
+        };
+
+        let (mut left, mut right) = (Vec::new(), Vec::new());
+        packet.to_stereo_with_buffers(&mut left, &mut right);
+        assert_eq!(left, vec![1.0, 0.5]);
+        assert_eq!(right, vec![-1.0, 0.25]);
+    }
+
+    #[test]
+    fn test_mid_side_detects_phase_cancellation() {
+        // Left and right perfectly out of phase: mid cancels, side keeps it all
+        let packet = AudioPacket {
+            samples: vec![0.8, -0.8, -0.4, 0.4],
+            sample_rate: 48000,
+            channels: 2,
+            timestamp: Instant::now(),
+        };
+
+        let (mut mid, mut side) = (Vec::new(), Vec::new());
+        packet.to_mid_side_with_buffers(&mut mid, &mut side);
+        assert_eq!(mid, vec![0.0, 0.0]);
+        assert_eq!(side, vec![0.8, -0.4]);
+    }
+}
@@ -0,0 +1,173 @@
+//! Auto-ranging for the spectrum display (auto gain)
+//! Slowly moves the displayed dB range to follow the programme, so quiet material fills
+//! the window and loud material keeps some headroom instead of pinning the bars
+
+use crate::ballistics::Smoothing;
+use crate::shared_state::SILENCE_DB;
+
+/// Smallest range the display is squeezed to, so silence between notes isn't blown up
+pub const MIN_SPAN_DB: f32 = 24.0;
+
+/// Space kept above the loudest bar
+const HEADROOM_DB: f32 = 6.0;
+/// Space kept below the floor percentile so the quietest bars still show
+const FLOOR_MARGIN_DB: f32 = 6.0;
+/// Share of the active bars allowed to sit at or below the floor
+const FLOOR_PERCENTILE: f32 = 0.1;
+/// The ceiling rises this much faster than the adaptation speed, so a loud passage
+/// only pins the bars briefly
+const RISE_SPEEDUP: f32 = 8.0;
+
+/// Bars at or below this are treated as silent and ignored
+const ACTIVE_THRESHOLD_DB: f32 = SILENCE_DB + 1.0;
+
+/// dB values drawn at the bottom and the top of the spectrum
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct DbRange {
+    pub floor_db: f32,
+    pub ceiling_db: f32,
+}
+
+impl DbRange {
+    /// Fixed range: the noise floor up to 0 dB
+    pub fn fixed(noise_floor_db: f32) -> Self {
+        Self { floor_db: noise_floor_db, ceiling_db: 0.0 }
+    }
+
+    pub fn span_db(&self) -> f32 {
+        self.ceiling_db - self.floor_db
+    }
+}
+
+/// Adaptation speed and the bounds the range stays within
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct AutoRangeSettings {
+    pub speed_secs: f32,            // Time to close most of the gap to a new level
+    pub lowest_floor_db: f32,       // The floor never drops below this
+    pub highest_ceiling_db: f32,    // The ceiling never rises above this
+}
+
+impl AutoRangeSettings {
+    /// Range that frames `bars`, or None if every bar is silent
+    pub fn target(&self, bars: &[f32]) -> Option<DbRange> {
+        let mut active: Vec<f32> = bars.iter().copied().filter(|&db| db > ACTIVE_THRESHOLD_DB).collect();
+        if active.is_empty() {
+            return None;
+        }
+        active.sort_by(|a, b| a.total_cmp(b));
+
+        let loudest = active[active.len() - 1];
+        let quiet = active[((active.len() - 1) as f32 * FLOOR_PERCENTILE) as usize];
+        Some(self.clamp(DbRange {
+            floor_db: quiet - FLOOR_MARGIN_DB,
+            ceiling_db: loudest + HEADROOM_DB,
+        }))
+    }
+
+    /// Keep `range` within the limits and at least `MIN_SPAN_DB` wide
+    pub fn clamp(&self, range: DbRange) -> DbRange {
+        let highest_ceiling = self.highest_ceiling_db.max(self.lowest_floor_db + MIN_SPAN_DB);
+        let ceiling_db = range.ceiling_db.clamp(self.lowest_floor_db + MIN_SPAN_DB, highest_ceiling);
+        let floor_db = range.floor_db.clamp(self.lowest_floor_db, ceiling_db - MIN_SPAN_DB);
+        DbRange { floor_db, ceiling_db }
+    }
+}
+
+/// Smoothed display range following the published bars
+pub struct AutoRange {
+    range: DbRange,
+}
+
+impl AutoRange {
+    /// Start from `initial` (typically the fixed range) and drift from there
+    pub fn new(initial: DbRange) -> Self {
+        Self { range: initial }
+    }
+
+    /// Move towards the range framing `bars`; silent frames hold the current range
+    pub fn update(&mut self, bars: &[f32], elapsed_ms: f32, settings: &AutoRangeSettings) -> DbRange {
+        let Some(target) = settings.target(bars) else {
+            return self.range;
+        };
+
+        let speed_ms = settings.speed_secs * 1000.0;
+        let ceiling = Smoothing { attack_ms: speed_ms / RISE_SPEEDUP, release_ms: speed_ms };
+        let floor = Smoothing { attack_ms: speed_ms, release_ms: speed_ms };
+        let moved = DbRange {
+            floor_db: floor.step(self.range.floor_db, target.floor_db, elapsed_ms),
+            ceiling_db: ceiling.step(self.range.ceiling_db, target.ceiling_db, elapsed_ms),
+        };
+
+        self.range = settings.clamp(moved);
+        self.range
+    }
+}
+
+// =============== Tests ==================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SETTINGS: AutoRangeSettings = AutoRangeSettings {
+        speed_secs: 2.0,
+        lowest_floor_db: -120.0,
+        highest_ceiling_db: 20.0,
+    };
+
+    /// Run `bars` through the tracker for `secs` in 20 ms frames
+    fn settle(auto: &mut AutoRange, bars: &[f32], secs: f32) -> DbRange {
+        let mut range = auto.range;
+        for _ in 0..(secs * 50.0) as usize {
+            range = auto.update(bars, 20.0, &SETTINGS);
+        }
+        range
+    }
+
+    #[test]
+    fn test_quiet_material_fills_the_window() {
+        // Bars between -90 and -70 dB would barely show on the default -60 dB floor
+        let bars: Vec<f32> = (0..64).map(|i| -90.0 + 20.0 * i as f32 / 63.0).collect();
+        let mut auto = AutoRange::new(DbRange::fixed(-60.0));
+        let range = settle(&mut auto, &bars, 20.0);
+
+        assert!((range.ceiling_db - (-64.0)).abs() < 0.5, "ceiling {}", range.ceiling_db);
+        assert!(range.floor_db < -90.0 && range.floor_db > -100.0, "floor {}", range.floor_db);
+    }
+
+    #[test]
+    fn test_loud_material_gets_headroom() {
+        let bars = vec![-20.0, -10.0, 5.0, -30.0];
+        let mut auto = AutoRange::new(DbRange::fixed(-60.0));
+        let range = settle(&mut auto, &bars, 20.0);
+
+        assert!((range.ceiling_db - 11.0).abs() < 0.5, "ceiling {}", range.ceiling_db);
+        assert!(range.span_db() >= MIN_SPAN_DB);
+    }
+
+    #[test]
+    fn test_ceiling_rises_faster_than_it_falls() {
+        // Share of the gap to the target ceiling closed in one 200 ms frame
+        let mut auto = AutoRange::new(DbRange::fixed(-60.0));
+        let rise = auto.update(&[10.0], 200.0, &SETTINGS).ceiling_db / 16.0;
+
+        let mut auto = AutoRange::new(DbRange { floor_db: -60.0, ceiling_db: 16.0 });
+        let fall = (16.0 - auto.update(&[-70.0], 200.0, &SETTINGS).ceiling_db) / 80.0;
+
+        assert!(rise > fall * 4.0, "rise {} fall {}", rise, fall);
+    }
+
+    #[test]
+    fn test_limits_and_silence() {
+        let mut auto = AutoRange::new(DbRange::fixed(-60.0));
+        let range = settle(&mut auto, &[40.0, 35.0], 20.0);
+        assert!((range.ceiling_db - SETTINGS.highest_ceiling_db).abs() < 0.01, "ceiling {}", range.ceiling_db);
+
+        let range = settle(&mut auto, &[-135.0, -130.0], 20.0);
+        assert!((range.floor_db - SETTINGS.lowest_floor_db).abs() < 0.01, "floor {}", range.floor_db);
+        assert!((range.span_db() - MIN_SPAN_DB).abs() < 0.01, "span {}", range.span_db());
+
+        // Silence holds the last range
+        let held = settle(&mut auto, &[SILENCE_DB; 8], 5.0);
+        assert_eq!(held, range);
+    }
+}
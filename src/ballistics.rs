@@ -0,0 +1,195 @@
+//! Level ballistics shared by the spectrum bars and the level meters
+//! Levels are in dB. Each function advances one channel (or bar) by `delta_ms`,
+//! so callers can run them at whatever rate their data arrives
+
+use serde::{Serialize, Deserialize};
+
+/// VU: 300 ms to reach 99% of a steady tone (about 4.6 time constants)
+const VU_TIME_CONSTANT_MS: f32 = 65.0;
+/// BBC PPM (IEC 60268-10 Type IIa): fast quasi-peak attack, 24 dB fall in 2.8 s
+const PPM_ATTACK_MS: f32 = 2.5;
+const PPM_FALL_DB_PER_SEC: f32 = 24.0 / 2.8;
+/// Digital peak (IEC 60268-18): instant attack, 20 dB fall in 1.7 s
+const DIGITAL_FALL_DB_PER_SEC: f32 = 20.0 / 1.7;
+
+/// Attack/release smoothing: each step closes `delta / time` of the gap to the target
+/// (a time of 0 follows the target instantly)
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Smoothing {
+    pub attack_ms: f32,     // Rise speed
+    pub release_ms: f32,    // Fall speed
+}
+
+impl Smoothing {
+    pub fn step(&self, last: f32, target: f32, delta_ms: f32) -> f32 {
+        // if new value is higher, use attack time
+        // if new value is lower, use release time
+        let time_ms = if target > last { self.attack_ms } else { self.release_ms };
+        last + (target - last) * (delta_ms / time_ms).min(1.0)
+    }
+
+    /// Smooth every level towards its target in place
+    pub fn apply(&self, levels: &mut [f32], targets: &[f32], delta_ms: f32) {
+        for (level, &target) in levels.iter_mut().zip(targets) {
+            *level = self.step(*level, target, delta_ms);
+        }
+    }
+}
+
+/// Peak hold: jumps to a new maximum, holds it, then falls back towards the level
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PeakHold {
+    pub hold_ms: f32,       // Duration of the hold
+    pub release_ms: f32,    // Fall speed once the hold expires
+}
+
+/// Held value and remaining hold time for one channel or bar
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PeakState {
+    pub level: f32,
+    pub timer_ms: f32,
+}
+
+impl PeakState {
+    pub fn new(level: f32) -> Self {
+        Self { level, timer_ms: 0.0 }
+    }
+}
+
+impl PeakHold {
+    pub fn step(&self, peak: &mut PeakState, level: f32, delta_ms: f32) {
+        // if the level exceeds the peak, reset the peak
+        if level > peak.level {
+            peak.level = level;
+            peak.timer_ms = self.hold_ms;
+            return;
+        }
+
+        peak.timer_ms -= delta_ms;
+
+        // if the hold expired, let the peak fall, but never below the level
+        if peak.timer_ms <= 0.0 {
+            let release_factor = (delta_ms / self.release_ms).min(1.0);
+            peak.level -= (peak.level - level) * release_factor;
+            peak.level = peak.level.max(level);
+        }
+    }
+
+    /// Update every peak from its level in place
+    pub fn apply(&self, peaks: &mut [PeakState], levels: &[f32], delta_ms: f32) {
+        for (peak, &level) in peaks.iter_mut().zip(levels) {
+            self.step(peak, level, delta_ms);
+        }
+    }
+}
+
+/// Standard meter characteristics
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub enum MeterBallistics {
+    Vu,         // Averaging, symmetric 300 ms integration of the RMS level
+    Ppm,        // BBC quasi-peak: fast rise, slow constant-rate fall
+    Digital,    // True sample peak: instant rise, constant-rate fall
+}
+
+impl MeterBallistics {
+    /// All meter ballistics, in the order shown in the settings UI
+    pub const ALL: [MeterBallistics; 3] = [
+        MeterBallistics::Vu,
+        MeterBallistics::Ppm,
+        MeterBallistics::Digital,
+    ];
+
+    /// Human readable name for the settings UI
+    pub fn label(&self) -> &'static str {
+        match self {
+            MeterBallistics::Vu => "VU (300 ms)",
+            MeterBallistics::Ppm => "BBC PPM",
+            MeterBallistics::Digital => "Digital Peak",
+        }
+    }
+
+    /// VU meters read the average (RMS) level, the others follow the sample peak
+    pub fn reads_rms(&self) -> bool {
+        matches!(self, MeterBallistics::Vu)
+    }
+
+    /// Advance the meter reading from `last` towards `target` over `delta_ms`
+    pub fn step(&self, last: f32, target: f32, delta_ms: f32) -> f32 {
+        match self {
+            MeterBallistics::Vu => Smoothing { attack_ms: VU_TIME_CONSTANT_MS, release_ms: VU_TIME_CONSTANT_MS }
+                .step(last, target, delta_ms),
+            MeterBallistics::Ppm if target > last => Smoothing { attack_ms: PPM_ATTACK_MS, release_ms: PPM_ATTACK_MS }
+                .step(last, target, delta_ms),
+            MeterBallistics::Ppm => fall(last, target, PPM_FALL_DB_PER_SEC, delta_ms),
+            MeterBallistics::Digital if target > last => target,
+            MeterBallistics::Digital => fall(last, target, DIGITAL_FALL_DB_PER_SEC, delta_ms),
+        }
+    }
+}
+
+/// Constant-rate fall that stops at the target
+fn fall(last: f32, target: f32, db_per_sec: f32, delta_ms: f32) -> f32 {
+    (last - db_per_sec * delta_ms / 1000.0).max(target)
+}
+
+// =============== Tests ==================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_smoothing_uses_attack_and_release() {
+        let smoothing = Smoothing { attack_ms: 100.0, release_ms: 50.0 };
+
+        assert!((smoothing.step(0.0, 10.0, 10.0) - 1.0).abs() < 1e-5);
+        assert!((smoothing.step(10.0, 0.0, 10.0) - 8.0).abs() < 1e-5);
+        assert_eq!(Smoothing { attack_ms: 0.0, release_ms: 0.0 }.step(-60.0, -6.0, 10.0), -6.0);
+    }
+
+    #[test]
+    fn test_peak_holds_then_releases() {
+        let hold = PeakHold { hold_ms: 100.0, release_ms: 100.0 };
+        let mut peak = PeakState::new(-140.0);
+
+        hold.step(&mut peak, -10.0, 10.0);
+        assert_eq!(peak.level, -10.0);
+
+        // Still held after 90 ms
+        for _ in 0..9 {
+            hold.step(&mut peak, -40.0, 10.0);
+        }
+        assert_eq!(peak.level, -10.0);
+
+        // Released afterwards, but never below the level
+        for _ in 0..100 {
+            hold.step(&mut peak, -40.0, 10.0);
+        }
+        assert!(peak.level < -10.0);
+        assert!(peak.level >= -40.0);
+    }
+
+    #[test]
+    fn test_vu_reaches_99_percent_in_300ms() {
+        let mut level = -40.0;
+        for _ in 0..300 {
+            level = MeterBallistics::Vu.step(level, 0.0, 1.0);
+        }
+        // 99% of a 40 dB step
+        assert!(level > -0.6 && level < 0.0, "VU = {}", level);
+    }
+
+    #[test]
+    fn test_peak_meters_fall_at_constant_rate() {
+        // PPM: 24 dB in 2.8 s
+        let mut ppm = 0.0;
+        for _ in 0..28 {
+            ppm = MeterBallistics::Ppm.step(ppm, -100.0, 100.0);
+        }
+        assert!((ppm + 24.0).abs() < 0.01, "PPM = {}", ppm);
+
+        // Digital: instant attack
+        assert_eq!(MeterBallistics::Digital.step(-60.0, -3.0, 1.0), -3.0);
+        let digital = MeterBallistics::Digital.step(0.0, -100.0, 1700.0);
+        assert!((digital + 20.0).abs() < 0.01, "Digital = {}", digital);
+    }
+}
@@ -0,0 +1,247 @@
+//! Pitch-class (chroma) analysis and musical key estimation
+//! The FFT magnitude spectrum is folded onto the 12 semitones of the octave, and a
+//! running average of that chroma is matched against the Krumhansl-Kessler key
+//! profiles (24 major and minor keys)
+
+use crate::pitch::{Note, NOTE_NAMES};
+
+/// Number of pitch classes (C, C#, ... B)
+pub const PITCH_CLASSES: usize = 12;
+
+/// Analysed range: below ~A1 the fundamentals are masked by kick drums and rumble,
+/// above ~5 kHz there is little tonal energy left
+const CHROMA_MIN_HZ: f64 = 55.0;
+const CHROMA_MAX_HZ: f64 = 5000.0;
+/// Bins must be at least this much narrower than a semitone, otherwise one bin
+/// smears across several pitch classes (raises the lower limit for small FFTs)
+const MIN_BINS_PER_SEMITONE: f64 = 1.0;
+/// Frames quieter than this (dB per bin) do not update the chroma
+const CHROMA_SILENCE_DB: f32 = -90.0;
+
+/// Time constant of the running chroma average used for the key
+const KEY_AVERAGE_SECS: f32 = 10.0;
+/// Profile correlation a key needs before it is reported
+const MIN_KEY_CORRELATION: f32 = 0.5;
+
+/// Krumhansl-Kessler probe-tone ratings, tonic first
+const MAJOR_PROFILE: [f32; PITCH_CLASSES] = [6.35, 2.23, 3.48, 2.33, 4.38, 4.09, 2.52, 5.19, 2.39, 3.66, 2.29, 2.88];
+const MINOR_PROFILE: [f32; PITCH_CLASSES] = [6.33, 2.68, 3.52, 5.38, 2.60, 3.53, 2.54, 4.75, 3.98, 2.69, 3.34, 3.17];
+
+/// Pitch-class energy, normalised so the strongest class is 1.0 (C first)
+/// All zero when the spectrum is silent or too coarse to resolve semitones
+pub fn chroma_from_spectrum(spectrum_db: &[f32], freq_resolution: f64, a4_hz: f32) -> [f32; PITCH_CLASSES] {
+    let mut chroma = [0.0f32; PITCH_CLASSES];
+    if freq_resolution <= 0.0 {
+        return chroma;
+    }
+
+    let semitone_ratio = 2f64.powf(1.0 / 12.0) - 1.0;
+    let low_hz = CHROMA_MIN_HZ.max(freq_resolution * MIN_BINS_PER_SEMITONE / semitone_ratio);
+    let first_bin = (low_hz / freq_resolution).ceil() as usize;
+    let last_bin = (CHROMA_MAX_HZ / freq_resolution).floor() as usize;
+
+    let bins = spectrum_db.iter().enumerate().take(last_bin + 1).skip(first_bin);
+    for (bin, &level_db) in bins {
+        if level_db <= CHROMA_SILENCE_DB {
+            continue;
+        }
+        if let Some(note) = Note::from_frequency(bin as f64 * freq_resolution, a4_hz) {
+            chroma[note.midi.rem_euclid(12) as usize] += 10f32.powf(level_db / 10.0);
+        }
+    }
+
+    let strongest = chroma.iter().copied().fold(0.0f32, f32::max);
+    if strongest > 0.0 {
+        for value in &mut chroma {
+            *value /= strongest;
+        }
+    }
+    chroma
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum KeyMode {
+    Major,
+    Minor,
+}
+
+/// A detected key and how well the chroma matched its profile
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct MusicalKey {
+    pub tonic: usize,       // Pitch class, C = 0
+    pub mode: KeyMode,
+    pub correlation: f32,   // Pearson correlation with the key profile (-1 .. 1)
+}
+
+impl MusicalKey {
+    /// "A minor", "F# major"
+    pub fn name(&self) -> String {
+        let mode = match self.mode {
+            KeyMode::Major => "major",
+            KeyMode::Minor => "minor",
+        };
+        format!("{} {}", NOTE_NAMES[self.tonic], mode)
+    }
+
+    /// Camelot wheel code used for harmonic mixing ("8A" = A minor, "8B" = C major)
+    /// Neighbouring numbers and the same number with the other letter mix cleanly
+    pub fn camelot(&self) -> String {
+        let (relative_major, letter) = match self.mode {
+            KeyMode::Major => (self.tonic, 'B'),
+            KeyMode::Minor => ((self.tonic + 3) % PITCH_CLASSES, 'A'),
+        };
+        // Each step round the wheel is a fifth (7 semitones); C major sits at 8
+        let number = (relative_major * 7 + 7) % PITCH_CLASSES + 1;
+        format!("{}{}", number, letter)
+    }
+}
+
+/// Running key estimate from successive chroma frames
+#[derive(Clone, Debug, Default)]
+pub struct KeyEstimator {
+    average: [f32; PITCH_CLASSES],
+}
+
+impl KeyEstimator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget the history (e.g. on a track change)
+    pub fn reset(&mut self) {
+        self.average = [0.0; PITCH_CLASSES];
+    }
+
+    /// Add one chroma frame covering `elapsed_ms` of audio; silent frames are skipped
+    pub fn update(&mut self, chroma: &[f32; PITCH_CLASSES], elapsed_ms: f32) {
+        if chroma.iter().all(|&v| v == 0.0) {
+            return;
+        }
+        let alpha = 1.0 - (-elapsed_ms / (KEY_AVERAGE_SECS * 1000.0)).exp();
+        for (average, &value) in self.average.iter_mut().zip(chroma) {
+            *average += (value - *average) * alpha;
+        }
+    }
+
+    /// Best matching key, None until the chroma is tonal enough
+    pub fn key(&self) -> Option<MusicalKey> {
+        let candidates = (0..PITCH_CLASSES).flat_map(|tonic| {
+            [(KeyMode::Major, &MAJOR_PROFILE), (KeyMode::Minor, &MINOR_PROFILE)]
+                .map(|(mode, profile)| MusicalKey {
+                    tonic,
+                    mode,
+                    correlation: rotated_correlation(&self.average, profile, tonic),
+                })
+        });
+
+        candidates
+            .max_by(|a, b| a.correlation.total_cmp(&b.correlation))
+            .filter(|key| key.correlation >= MIN_KEY_CORRELATION)
+    }
+}
+
+/// Pearson correlation between the chroma and a profile transposed to `tonic`
+fn rotated_correlation(chroma: &[f32; PITCH_CLASSES], profile: &[f32; PITCH_CLASSES], tonic: usize) -> f32 {
+    let n = PITCH_CLASSES as f32;
+    let chroma_mean = chroma.iter().sum::<f32>() / n;
+    let profile_mean = profile.iter().sum::<f32>() / n;
+
+    let (mut covariance, mut chroma_var, mut profile_var) = (0.0f32, 0.0f32, 0.0f32);
+    for (pitch_class, &value) in chroma.iter().enumerate() {
+        let c = value - chroma_mean;
+        let p = profile[(pitch_class + PITCH_CLASSES - tonic) % PITCH_CLASSES] - profile_mean;
+        covariance += c * p;
+        chroma_var += c * c;
+        profile_var += p * p;
+    }
+
+    let denominator = (chroma_var * profile_var).sqrt();
+    if denominator < 1e-9 {
+        return 0.0;
+    }
+    covariance / denominator
+}
+
+// =============== Tests ==================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fft_processor::{FFTConfig, FFTProcessor};
+    use crate::pitch::{midi_frequency, DEFAULT_A4_HZ};
+
+    /// Raw spectrum of a sum of equal-level tones (Hann, 8192 @ 48 kHz)
+    fn chord_spectrum(midi_notes: &[i32]) -> (Vec<f32>, f64) {
+        let config = FFTConfig { sample_rate: 48000, fft_size: 8192, ..FFTConfig::default() };
+        let mut processor = FFTProcessor::new(config);
+        let freqs: Vec<f32> = midi_notes.iter().map(|&m| midi_frequency(m as f64, DEFAULT_A4_HZ) as f32).collect();
+        let samples: Vec<f32> = (0..16384)
+            .map(|i| {
+                let t = i as f32 / 48000.0;
+                freqs.iter().map(|f| 0.1 * (2.0 * std::f32::consts::PI * f * t).sin()).sum()
+            })
+            .collect();
+        processor.process(&samples);
+
+        (processor.spectrum().to_vec(), processor.frequency_resolution())
+    }
+
+    #[test]
+    fn test_chroma_of_single_note() {
+        // A3 = 220 Hz lands in pitch class A
+        let (spectrum, resolution) = chord_spectrum(&[57]);
+        let chroma = chroma_from_spectrum(&spectrum, resolution, DEFAULT_A4_HZ);
+
+        assert_eq!(chroma[9], 1.0);
+        assert!(chroma.iter().enumerate().all(|(pc, &v)| pc == 9 || v < 0.05), "{:?}", chroma);
+    }
+
+    #[test]
+    fn test_silence_and_coarse_spectrum() {
+        assert_eq!(chroma_from_spectrum(&[-120.0; 1025], 23.4, DEFAULT_A4_HZ), [0.0; PITCH_CLASSES]);
+
+        // 1 kHz bins are wider than a semitone everywhere below 5 kHz
+        assert_eq!(chroma_from_spectrum(&[-10.0; 24], 1000.0, DEFAULT_A4_HZ), [0.0; PITCH_CLASSES]);
+    }
+
+    #[test]
+    fn test_key_of_scale() {
+        // C major scale with a weighted tonic triad, the way melodies dwell on it
+        let mut chroma = [0.0f32; PITCH_CLASSES];
+        for (pc, weight) in [(0, 1.0), (2, 0.5), (4, 0.8), (5, 0.5), (7, 0.9), (9, 0.5), (11, 0.4)] {
+            chroma[pc] = weight;
+        }
+        let mut estimator = KeyEstimator::new();
+        estimator.update(&chroma, 60_000.0);
+        let key = estimator.key().unwrap();
+        assert_eq!((key.tonic, key.mode), (0, KeyMode::Major));
+        assert_eq!(key.name(), "C major");
+        assert_eq!(key.camelot(), "8B");
+
+        // The same notes centred on A read as A minor
+        let mut chroma = [0.0f32; PITCH_CLASSES];
+        for (pc, weight) in [(9, 1.0), (11, 0.4), (0, 0.8), (2, 0.5), (4, 0.9), (5, 0.4), (7, 0.4)] {
+            chroma[pc] = weight;
+        }
+        estimator.reset();
+        estimator.update(&chroma, 60_000.0);
+        let key = estimator.key().unwrap();
+        assert_eq!(key.name(), "A minor");
+        assert_eq!(key.camelot(), "8A");
+    }
+
+    #[test]
+    fn test_key_from_chord_spectra() {
+        // Alternating I and V chords in G major (G-B-D, D-F#-A)
+        let mut estimator = KeyEstimator::new();
+        for chord in [[55, 59, 62], [50, 54, 57], [55, 59, 62], [43, 47, 50]] {
+            let (spectrum, resolution) = chord_spectrum(&chord);
+            estimator.update(&chroma_from_spectrum(&spectrum, resolution, DEFAULT_A4_HZ), 2000.0);
+        }
+        let key = estimator.key().unwrap();
+        assert_eq!(key.name(), "G major");
+        assert_eq!(key.camelot(), "9B");
+
+        // Nothing heard yet: no key
+        assert!(KeyEstimator::new().key().is_none());
+    }
+}
@@ -0,0 +1,141 @@
+//! Colour maps for intensity displays (spectrogram)
+//! Maps a normalised level (0.0 = noise floor, 1.0 = full scale) to an RGB colour
+
+use serde::{Serialize, Deserialize};
+
+use crate::shared_state::ColorProfile;
+
+/// Perceptually uniform viridis, sampled at 9 evenly spaced stops
+const VIRIDIS: [[u8; 3]; 9] = [
+    [68, 1, 84],
+    [71, 45, 123],
+    [59, 82, 139],
+    [44, 114, 142],
+    [33, 145, 140],
+    [40, 174, 128],
+    [94, 201, 98],
+    [173, 220, 48],
+    [253, 231, 37],
+];
+
+/// Perceptually uniform magma, sampled at 9 evenly spaced stops
+const MAGMA: [[u8; 3]; 9] = [
+    [0, 0, 4],
+    [28, 16, 68],
+    [79, 18, 123],
+    [129, 37, 129],
+    [181, 54, 122],
+    [229, 80, 100],
+    [251, 135, 97],
+    [254, 194, 135],
+    [252, 253, 191],
+];
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub enum Colormap {
+    Viridis,
+    Magma,
+    Grayscale,
+    Profile,    // Background -> low -> high -> peak of the active colour profile
+}
+
+impl Colormap {
+    /// All colour maps, in the order shown in the settings UI
+    pub const ALL: [Colormap; 4] = [
+        Colormap::Viridis,
+        Colormap::Magma,
+        Colormap::Grayscale,
+        Colormap::Profile,
+    ];
+
+    /// Human readable name for the settings UI
+    pub fn label(&self) -> &'static str {
+        match self {
+            Colormap::Viridis => "Viridis",
+            Colormap::Magma => "Magma",
+            Colormap::Grayscale => "Grayscale",
+            Colormap::Profile => "Colour Profile",
+        }
+    }
+
+    /// Colour at level `t` (clamped to 0.0 - 1.0)
+    pub fn sample(&self, t: f32, colors: &ColorProfile) -> [u8; 3] {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Colormap::Viridis => sample_stops(&VIRIDIS, t),
+            Colormap::Magma => sample_stops(&MAGMA, t),
+            Colormap::Grayscale => {
+                let v = (t * 255.0).round() as u8;
+                [v, v, v]
+            }
+            Colormap::Profile => {
+                let stops = [colors.background, colors.low, colors.high, colors.peak]
+                    .map(|c| [c.r, c.g, c.b]);
+                sample_stops(&stops, t)
+            }
+        }
+    }
+
+    /// Precomputed lookup table with `size` entries (cheap per-pixel colouring)
+    pub fn lut(&self, size: usize, colors: &ColorProfile) -> Vec<[u8; 3]> {
+        let last = size.saturating_sub(1).max(1) as f32;
+        (0..size).map(|i| self.sample(i as f32 / last, colors)).collect()
+    }
+}
+
+/// Linear interpolation between evenly spaced colour stops
+fn sample_stops(stops: &[[u8; 3]], t: f32) -> [u8; 3] {
+    let segments = (stops.len() - 1) as f32;
+    let pos = t * segments;
+    let idx = (pos.floor() as usize).min(stops.len() - 2);
+    let frac = pos - idx as f32;
+
+    let (a, b) = (stops[idx], stops[idx + 1]);
+    [0, 1, 2].map(|c| (a[c] as f32 + (b[c] as f32 - a[c] as f32) * frac).round() as u8)
+}
+
+// =============== Tests ==================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_colormap_endpoints() {
+        let colors = ColorProfile::default();
+
+        assert_eq!(Colormap::Viridis.sample(0.0, &colors), VIRIDIS[0]);
+        assert_eq!(Colormap::Viridis.sample(1.0, &colors), VIRIDIS[8]);
+        assert_eq!(Colormap::Magma.sample(-5.0, &colors), MAGMA[0]);
+        assert_eq!(Colormap::Grayscale.sample(1.0, &colors), [255, 255, 255]);
+    }
+
+    #[test]
+    fn test_colormap_middle_stops() {
+        // viridis(9): the stops must be the evenly spaced samples, not a thinned-out viridis(10)
+        let colors = ColorProfile::default();
+        assert_eq!(Colormap::Viridis.sample(0.5, &colors), [33, 145, 140]);
+        assert_eq!(Colormap::Viridis.sample(0.875, &colors), [173, 220, 48]);
+        assert_eq!(Colormap::Magma.sample(0.5, &colors), [181, 54, 122]);
+    }
+
+    #[test]
+    fn test_profile_map_follows_colour_profile() {
+        let colors = ColorProfile::default();
+        let bg = colors.background;
+        let peak = colors.peak;
+
+        assert_eq!(Colormap::Profile.sample(0.0, &colors), [bg.r, bg.g, bg.b]);
+        assert_eq!(Colormap::Profile.sample(1.0, &colors), [peak.r, peak.g, peak.b]);
+    }
+
+    #[test]
+    fn test_interpolates_between_stops() {
+        // Half way between the first two grayscale-like stops
+        let mid = sample_stops(&[[0, 0, 0], [200, 100, 50]], 0.5);
+        assert_eq!(mid, [100, 50, 25]);
+
+        let lut = Colormap::Magma.lut(256, &ColorProfile::default());
+        assert_eq!(lut.len(), 256);
+        assert_eq!(lut[255], MAGMA[8]);
+    }
+}
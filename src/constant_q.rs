@@ -0,0 +1,317 @@
+//! Constant-Q transform (CQT): bins spaced logarithmically at a fixed number per
+//! octave, each with a bandwidth proportional to its centre frequency
+//!
+//! Uses Brown & Puckette's spectral-kernel method: every bin is a Hann-windowed
+//! complex exponential Q cycles long, pre-transformed into a sparse set of FFT
+//! weights, so one large real FFT per frame serves all bins. The kernels end at the
+//! newest sample, so treble bins (short kernels) react quickly while bass bins get
+//! the long window they need to separate semitones.
+
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use num_complex::{Complex, Complex64};
+use realfft::{RealFftPlanner, RealToComplex};
+use serde::{Serialize, Deserialize};
+
+use crate::fft_config::MAX_FFT_SIZE;
+use crate::frequency_scale::FrequencyRange;
+
+/// Lowest bin: C1
+pub const CQT_MIN_HZ: f64 = 32.703_195_662_574_83;
+/// Highest bin: C10 (or 45% of the sample rate, whichever is lower)
+const CQT_MAX_HZ: f64 = 16_744.036_179_238_312;
+const MAX_NYQUIST_FRACTION: f64 = 0.45;
+/// Kernel weights are kept within this many kernel-resolution bins of the centre
+/// (the Hann main lobe is ±2; the rest of the skirt is below -70 dB)
+const KERNEL_HALF_WIDTH: f64 = 4.0;
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub enum CqtResolution {
+    Semitone,           // 12 bins per octave
+    HalfSemitone,       // 24
+    ThirdSemitone,      // 36
+    QuarterSemitone,    // 48
+}
+
+impl CqtResolution {
+    /// All resolutions, in the order shown in the settings UI
+    pub const ALL: [CqtResolution; 4] = [
+        CqtResolution::Semitone,
+        CqtResolution::HalfSemitone,
+        CqtResolution::ThirdSemitone,
+        CqtResolution::QuarterSemitone,
+    ];
+
+    /// Human readable name for the settings UI
+    pub fn label(&self) -> &'static str {
+        match self {
+            CqtResolution::Semitone => "12 / Octave (Semitones)",
+            CqtResolution::HalfSemitone => "24 / Octave",
+            CqtResolution::ThirdSemitone => "36 / Octave",
+            CqtResolution::QuarterSemitone => "48 / Octave",
+        }
+    }
+
+    pub fn bins_per_octave(&self) -> u32 {
+        match self {
+            CqtResolution::Semitone => 12,
+            CqtResolution::HalfSemitone => 24,
+            CqtResolution::ThirdSemitone => 36,
+            CqtResolution::QuarterSemitone => 48,
+        }
+    }
+
+    /// Quality factor: centre frequency / bandwidth (one bin spacing)
+    pub fn q(&self) -> f64 {
+        1.0 / (2f64.powf(1.0 / self.bins_per_octave() as f64) - 1.0)
+    }
+}
+
+/// Centre frequency of every bin inside `range`, on the grid from C1 upwards
+pub fn bin_frequencies(resolution: CqtResolution, sample_rate: u32, range: FrequencyRange) -> Vec<f64> {
+    let max_hz = CQT_MAX_HZ.min(sample_rate as f64 * MAX_NYQUIST_FRACTION);
+    let bins_per_octave = resolution.bins_per_octave() as f64;
+
+    (0..)
+        .map(|k| CQT_MIN_HZ * 2f64.powf(k as f64 / bins_per_octave))
+        .take_while(|&freq| freq <= max_hz)
+        .filter(|&freq| range.contains(freq))
+        .collect()
+}
+
+/// FFT weights of one bin: conjugated kernel spectrum for bins `first_bin ..`
+struct SpectralKernel {
+    first_bin: usize,
+    weights: Vec<Complex<f32>>,
+}
+
+pub struct ConstantQ {
+    frequencies: Vec<f64>,
+    kernels: Vec<SpectralKernel>,
+
+    // Newest frame of samples (the lowest bin's kernel length rounded up to a
+    // power of two; ring buffer, oldest at `write_pos`)
+    ring: Vec<f32>,
+    write_pos: usize,
+
+    fft: Arc<dyn RealToComplex<f32>>,
+    input: Vec<f32>,
+    spectrum: Vec<Complex<f32>>,
+    scratch: Vec<Complex<f32>>,
+}
+
+impl ConstantQ {
+    /// Transform over the whole grid; displayed ranges pick their bins with `bins_in`,
+    /// so zooming never rebuilds the kernels or empties the sample history
+    pub fn new(sample_rate: u32, resolution: CqtResolution) -> Self {
+        let frequencies = bin_frequencies(resolution, sample_rate, FrequencyRange::full(sample_rate));
+        let q = resolution.q();
+
+        // Long enough for the lowest bin, capped at the largest regular FFT
+        // (bins below the cap's limit fall back to a shorter kernel, i.e. a lower Q)
+        let longest = frequencies.first().map_or(1.0, |&f| q * sample_rate as f64 / f);
+        let frame_size = (longest.ceil() as usize).next_power_of_two().min(MAX_FFT_SIZE);
+
+        let kernels = frequencies
+            .iter()
+            .map(|&freq| {
+                let length = ((q * sample_rate as f64 / freq).round() as usize).clamp(2, frame_size);
+                spectral_kernel(freq / sample_rate as f64, length, frame_size)
+            })
+            .collect();
+
+        let fft = RealFftPlanner::<f32>::new().plan_fft_forward(frame_size);
+        let spectrum = fft.make_output_vec();
+        let scratch = fft.make_scratch_vec();
+
+        Self {
+            frequencies,
+            kernels,
+            ring: vec![0.0; frame_size],
+            write_pos: 0,
+            fft,
+            input: vec![0.0; frame_size],
+            spectrum,
+            scratch,
+        }
+    }
+
+    /// Centre frequency of every bin (Hz)
+    pub fn frequencies(&self) -> &[f64] {
+        &self.frequencies
+    }
+
+    /// Indices of the bins inside `range` (the ones `bin_frequencies` lists for it)
+    pub fn bins_in(&self, range: FrequencyRange) -> std::ops::Range<usize> {
+        let first = self.frequencies.partition_point(|&freq| freq < range.min_hz);
+        let end = self.frequencies.partition_point(|&freq| freq <= range.max_hz);
+        first..end.max(first)
+    }
+
+    /// Append samples (any amount) to the analysis buffer
+    pub fn push(&mut self, samples: &[f32]) {
+        let size = self.ring.len();
+        for &sample in samples {
+            self.ring[self.write_pos] = sample;
+            self.write_pos = (self.write_pos + 1) % size;
+        }
+    }
+
+    /// Amplitude of every bin for the current buffer contents
+    /// Calibrated so a sine of amplitude A reads A in its bin
+    pub fn analyse(&mut self) -> Vec<f32> {
+        let (newest, oldest) = self.ring.split_at(self.write_pos);
+        for (out, &sample) in self.input.iter_mut().zip(oldest.iter().chain(newest)) {
+            *out = sample;
+        }
+
+        self.fft
+            .process_with_scratch(&mut self.input, &mut self.spectrum, &mut self.scratch)
+            .expect("CQT FFT processing failed");
+
+        self.kernels
+            .iter()
+            .map(|kernel| {
+                let bins = &self.spectrum[kernel.first_bin..kernel.first_bin + kernel.weights.len()];
+                bins.iter().zip(&kernel.weights).map(|(x, w)| x * w).sum::<Complex<f32>>().norm()
+            })
+            .collect()
+    }
+}
+
+/// Sparse FFT weights for a Hann-windowed complex exponential at `norm_freq`
+/// (cycles per sample), `length` samples long and ending at the last sample of a
+/// `frame_size` frame
+///
+/// The kernel spectrum is evaluated in closed form (a Hann window is three shifted
+/// Dirichlet kernels), so building hundreds of kernels needs no FFTs. By Parseval,
+/// sum(x * conj(kernel)) = sum(X * conj(K)) / N; the negative-frequency half of K is
+/// negligible for a real input, so only bins 0 ..= N/2 are kept.
+fn spectral_kernel(norm_freq: f64, length: usize, frame_size: usize) -> SpectralKernel {
+    let n = frame_size as f64;
+    let l = length as f64;
+    let start = n - l;
+    // Amplitude calibration: a periodic Hann sums to L/2, and a real sine puts half
+    // its amplitude at the positive frequency
+    let scale = 4.0 / l / n;
+
+    let center = norm_freq * n;
+    let half_width = KERNEL_HALF_WIDTH * n / l;
+    let last_bin = frame_size / 2;
+    let first_bin = ((center - half_width).floor().max(0.0) as usize).min(last_bin);
+    let end_bin = ((center + half_width).ceil() as usize).min(last_bin);
+
+    let omega = 2.0 * PI * norm_freq;
+    let weights = (first_bin..=end_bin)
+        .map(|bin| {
+            let delta = omega - 2.0 * PI * bin as f64 / n;
+            let shift = 2.0 * PI / l;
+            let window = dirichlet(delta, length) * 0.5
+                - dirichlet(delta + shift, length) * 0.25
+                - dirichlet(delta - shift, length) * 0.25;
+            let kernel = Complex64::from_polar(1.0, delta * start) * window;
+            let weight = kernel.conj() * scale;
+            Complex::new(weight.re as f32, weight.im as f32)
+        })
+        .collect();
+
+    SpectralKernel { first_bin, weights }
+}
+
+/// sum(e^(i * delta * m)) for m in 0..length
+fn dirichlet(delta: f64, length: usize) -> Complex64 {
+    let half = delta / 2.0;
+    if half.sin().abs() < 1e-12 {
+        // delta is a multiple of 2π: every term is 1
+        return Complex64::new(length as f64, 0.0);
+    }
+    let ratio = (half * length as f64).sin() / half.sin();
+    Complex64::from_polar(ratio, half * (length as f64 - 1.0))
+}
+
+// =============== Tests ==================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(freq: f64, amplitude: f32, samples: usize) -> Vec<f32> {
+        (0..samples)
+            .map(|i| amplitude * (2.0 * PI * freq * i as f64 / 48000.0).sin() as f32)
+            .collect()
+    }
+
+    fn loudest(levels: &[f32]) -> usize {
+        (0..levels.len()).max_by(|&a, &b| levels[a].total_cmp(&levels[b])).unwrap()
+    }
+
+    #[test]
+    fn test_bins_are_log_spaced() {
+        let freqs = bin_frequencies(CqtResolution::Semitone, 48000, FrequencyRange::AUDIBLE);
+        assert!((freqs[0] - CQT_MIN_HZ).abs() < 1e-9);
+        assert!((freqs[12] / freqs[0] - 2.0).abs() < 1e-9);
+        assert_eq!(freqs.len(), 9 * 12 + 1); // C1 ..= C10
+
+        // Low sample rates stop at 45% of the sample rate
+        let low_rate = bin_frequencies(CqtResolution::HalfSemitone, 16000, FrequencyRange::AUDIBLE);
+        assert!(*low_rate.last().unwrap() <= 16000.0 * MAX_NYQUIST_FRACTION);
+
+        // A narrower range keeps the same grid, just fewer bins (A3 - A5: two octaves)
+        let range = FrequencyRange { min_hz: 219.0, max_hz: 881.0 };
+        let zoomed = bin_frequencies(CqtResolution::Semitone, 48000, range);
+        assert_eq!(zoomed.len(), 25);
+
+        // ...and the full transform selects exactly those bins
+        let cqt = ConstantQ::new(48000, CqtResolution::Semitone);
+        assert_eq!(cqt.frequencies()[cqt.bins_in(range)], zoomed[..]);
+    }
+
+    #[test]
+    fn test_sine_reads_its_amplitude() {
+        let resolution = CqtResolution::HalfSemitone;
+        for bin in [24, 120, 200] {
+            let mut cqt = ConstantQ::new(48000, resolution);
+            let freq = cqt.frequencies()[bin];
+            cqt.push(&sine(freq, 0.5, cqt.ring.len()));
+            let levels = cqt.analyse();
+
+            assert_eq!(loudest(&levels), bin, "{:.1} Hz", freq);
+            assert!((levels[bin] - 0.5).abs() < 0.02, "{:.1} Hz reads {}", freq, levels[bin]);
+            // One bin (a quarter tone) away is well down
+            assert!(levels[bin + 2] < 0.5 * levels[bin], "{:.1} Hz leaks {}", freq, levels[bin + 2]);
+        }
+    }
+
+    #[test]
+    fn test_resolves_semitones_in_the_bass() {
+        // A1 and A#1 (55 / 58.3 Hz) share one 23 Hz bin of a 2048-point FFT, but
+        // are two separate peaks with a dip between them here
+        let mut cqt = ConstantQ::new(48000, CqtResolution::HalfSemitone);
+        let a1 = sine(55.0, 0.3, cqt.ring.len());
+        let a_sharp1 = sine(58.27, 0.3, cqt.ring.len());
+        let mix: Vec<f32> = a1.iter().zip(&a_sharp1).map(|(a, b)| a + b).collect();
+        cqt.push(&mix);
+        let levels = cqt.analyse();
+
+        let a1_bin = 18; // Nine semitones above C1
+        let around = &levels[a1_bin - 2..=a1_bin + 4];
+        assert!(levels[a1_bin] > 0.25 && levels[a1_bin + 2] > 0.25, "{:?}", around);
+        assert!(levels[a1_bin + 1] < 0.9 * levels[a1_bin].min(levels[a1_bin + 2]), "{:?}", around);
+        assert!(levels[a1_bin - 2] < 0.05 && levels[a1_bin + 4] < 0.05, "{:?}", around);
+    }
+
+    #[test]
+    fn test_treble_reacts_before_bass() {
+        // A burst shorter than the bass kernels: treble bins see it in full, bass bins barely
+        let mut cqt = ConstantQ::new(48000, CqtResolution::Semitone);
+        cqt.push(&vec![0.0; cqt.ring.len()]);
+        let treble = cqt.frequencies()[96];
+        let bass = cqt.frequencies()[12];
+        let burst: Vec<f32> = sine(treble, 0.5, 4800).iter().zip(sine(bass, 0.5, 4800)).map(|(a, b)| a + b).collect();
+        cqt.push(&burst);
+        let levels = cqt.analyse();
+
+        assert!(levels[96] > 0.45, "treble {}", levels[96]);
+        assert!(levels[12] < 0.2, "bass {}", levels[12]);
+    }
+}
@@ -1,13 +1,96 @@
 /// FFT configuration adapter for dynamic sample rate handling
 /// Ensures FFT settings are always optimal for the current device's sample rate
 
+use serde::{Serialize, Deserialize};
 
-/// Fixed FFT size for the application
+/// Default FFT size for new profiles
 /// 2048 provides a good balance of frequency resolution and latency:
 /// - At 48kHz: 42.7ms latency, 23.4 Hz/bin resolution
 /// - At 44.1kHz: 46.4ms latency, 21.5 Hz/bin resolution
 /// - At 96kHz: 21.3ms latency, 46.9 Hz/bin resolution
-pub const FIXED_FFT_SIZE: usize = 2048;
+pub const DEFAULT_FFT_SIZE: usize = 2048;
+
+/// Smallest selectable FFT size (low-latency stage use)
+pub const MIN_FFT_SIZE: usize = 512;
+
+/// Largest selectable FFT size (bass detail for mastering work)
+pub const MAX_FFT_SIZE: usize = 32768;
+
+/// FFT sizes offered in the settings UI (powers of two, MIN..=MAX)
+pub const FFT_SIZE_OPTIONS: [usize; 7] = [512, 1024, 2048, 4096, 8192, 16384, 32768];
+
+/// Overlap between consecutive analysis frames
+/// Samples are gathered across audio packets, and a new FFT runs every
+/// `hop_size` samples, so the update rate no longer depends on the driver's buffer size
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub enum AnalysisOverlap {
+    Half,           // 50%: hop = N/2
+    ThreeQuarters,  // 75%: hop = N/4
+    SevenEighths,   // 87.5%: hop = N/8
+}
+
+impl AnalysisOverlap {
+    /// All overlap options, in the order shown in the settings UI
+    pub const ALL: [AnalysisOverlap; 3] = [
+        AnalysisOverlap::Half,
+        AnalysisOverlap::ThreeQuarters,
+        AnalysisOverlap::SevenEighths,
+    ];
+
+    /// Human readable name for the settings UI
+    pub fn label(&self) -> &'static str {
+        match self {
+            AnalysisOverlap::Half => "50%",
+            AnalysisOverlap::ThreeQuarters => "75%",
+            AnalysisOverlap::SevenEighths => "87.5%",
+        }
+    }
+
+    /// Number of new samples between two FFT frames
+    pub fn hop_size(&self, fft_size: usize) -> usize {
+        let divisor = match self {
+            AnalysisOverlap::Half => 2,
+            AnalysisOverlap::ThreeQuarters => 4,
+            AnalysisOverlap::SevenEighths => 8,
+        };
+        (fft_size / divisor).max(1)
+    }
+}
+
+/// How FFT bins are turned into bars
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub enum AnalysisMode {
+    Spectrum,       // `num_bars` bars spread along the selected frequency scale
+    OctaveBands,    // Standard 1/N-octave bands from summed band power (RTA)
+    ConstantQ,      // One bar per constant-Q bin, a fixed number per octave
+    MultiResolution,// Spectrum bars, each read from a bass, mid or treble FFT size
+}
+
+impl AnalysisMode {
+    /// All analysis modes, in the order shown in the settings UI
+    pub const ALL: [AnalysisMode; 4] = [
+        AnalysisMode::Spectrum,
+        AnalysisMode::OctaveBands,
+        AnalysisMode::ConstantQ,
+        AnalysisMode::MultiResolution,
+    ];
+
+    /// Human readable name for the settings UI
+    pub fn label(&self) -> &'static str {
+        match self {
+            AnalysisMode::Spectrum => "Spectrum",
+            AnalysisMode::OctaveBands => "Octave Bands (RTA)",
+            AnalysisMode::ConstantQ => "Constant-Q (CQT)",
+            AnalysisMode::MultiResolution => "Multi-Resolution",
+        }
+    }
+}
+
+/// Coerce a user/profile FFT size into something the processor can use:
+/// clamped to MIN..=MAX and rounded up to the next power of two
+pub fn sanitize_fft_size(size: usize) -> usize {
+    size.clamp(MIN_FFT_SIZE, MAX_FFT_SIZE).next_power_of_two()
+}
 
 /// Public result of FFT configuration
 /// Everything you need to know about current state
@@ -19,19 +102,35 @@ pub struct FFTInfo {
     pub frequency_resolution: f32,
 }
 
+impl FFTInfo {
+    /// Derive latency and resolution for a given rate/size pair.
+    /// Also used by the GUI to preview a size before the FFT thread applies it
+    pub fn new(sample_rate: u32, fft_size: usize) -> Self {
+        Self {
+            sample_rate,
+            fft_size,
+            latency_ms: (fft_size as f32 / sample_rate as f32) * 1000.0,
+            frequency_resolution: sample_rate as f32 / fft_size as f32,
+        }
+    }
+}
+
 /// Manages FFT configuration based on detected device sample rate
 /// Also handles user override of FFT size for viusalization preferences
 pub struct FFTConfigManager {
     current_sample_rate: u32,
+    fft_size: usize,
     frequency_resolution: f32,
 }
 
 impl FFTConfigManager {
-    /// Create a new FFT config manager with default sample rate
-    pub fn new(sample_rate: u32) -> Self {
+    /// Create a new FFT config manager for a sample rate and requested FFT size
+    pub fn new(sample_rate: u32, fft_size: usize) -> Self {
+        let fft_size = sanitize_fft_size(fft_size);
         Self {
             current_sample_rate: sample_rate,
-            frequency_resolution: Self::calc_resolution(sample_rate),
+            fft_size,
+            frequency_resolution: Self::calc_resolution(sample_rate, fft_size),
         }
     }
 
@@ -48,18 +147,34 @@ impl FFTConfigManager {
         );
       
         self.current_sample_rate = new_sample_rate;
-        self.frequency_resolution = Self::calc_resolution(new_sample_rate);
+        self.frequency_resolution = Self::calc_resolution(new_sample_rate, self.fft_size);
+        true
+    }
+
+    /// Apply a user-selected FFT size (from the active visual profile)
+    /// Returns true if FFT processor rebuild needed
+    pub fn update_fft_size(&mut self, requested: usize) -> bool {
+        let new_size = sanitize_fft_size(requested);
+        if new_size == self.fft_size {
+            return false;
+        }
+
+        tracing::info!(
+            "[FFTConfigManager] FFT size: {} → {} samples",
+            self.fft_size, new_size
+        );
+
+        self.fft_size = new_size;
+        self.frequency_resolution = Self::calc_resolution(self.current_sample_rate, new_size);
         true
     }
      
     // ======= Query Methods ========
     pub fn info(&self) -> FFTInfo {
         FFTInfo {
-            sample_rate: self.current_sample_rate,
-            fft_size: FIXED_FFT_SIZE,
-            latency_ms: (FIXED_FFT_SIZE as f32 / self.current_sample_rate as f32) * 1000.0,
             frequency_resolution: self.frequency_resolution,
-        }  
+            ..FFTInfo::new(self.current_sample_rate, self.fft_size)
+        }
     }
 
     /// Get current sample rate
@@ -67,8 +182,13 @@ impl FFTConfigManager {
         self.current_sample_rate
     }
 
-    fn calc_resolution(rate: u32) -> f32 {
-        rate as f32 / FIXED_FFT_SIZE as f32
+    /// Get current FFT size
+    pub fn get_fft_size(&self) -> usize {
+        self.fft_size
+    }
+
+    fn calc_resolution(rate: u32, fft_size: usize) -> f32 {
+        rate as f32 / fft_size as f32
     }
 }
 
@@ -79,8 +199,8 @@ mod tests {
 
     #[test]
    fn test_resolution_varies_with_sample_rate() {
-    let manager_48k = FFTConfigManager::new(48000);
-    let manager_96k = FFTConfigManager::new(96000);
+    let manager_48k = FFTConfigManager::new(48000, DEFAULT_FFT_SIZE);
+    let manager_96k = FFTConfigManager::new(96000, DEFAULT_FFT_SIZE);
 
     // Higher sample rate =  higher frequency resolution (wider bins)
     // 96000 / 2048 = 46.875 Hz per bin
@@ -93,16 +213,16 @@ mod tests {
    
    #[test]
    fn test_intialization() {
-    let manager = FFTConfigManager::new(48000);
+    let manager = FFTConfigManager::new(48000, DEFAULT_FFT_SIZE);
     let info = manager.info();
 
     assert_eq!(info.sample_rate, 48000);
-    assert_eq!(info.fft_size, FIXED_FFT_SIZE);
+    assert_eq!(info.fft_size, DEFAULT_FFT_SIZE);
    }
 
    #[test]
     fn test_sample_rate_update() {
-        let mut manager = FFTConfigManager::new(48000);
+        let mut manager = FFTConfigManager::new(48000, DEFAULT_FFT_SIZE);
 
         // 1. Update to new rate -> Should return true (changed)
         let changed = manager.update_sample_rate(96000);
@@ -121,9 +241,42 @@ mod tests {
 
     #[test]
     fn test_latency_calculation() {
-        let manager = FFTConfigManager::new(48000);
+        let manager = FFTConfigManager::new(48000, DEFAULT_FFT_SIZE);
         // 2048 samples / 48000 samples/sec = 0.04266 seconds = 42.67ms
         assert!((manager.info().latency_ms - 42.67).abs() < 0.1);
     }
+
+    #[test]
+    fn test_fft_size_update() {
+        let mut manager = FFTConfigManager::new(48000, DEFAULT_FFT_SIZE);
+
+        // 1. Larger FFT -> finer bins, longer latency
+        assert!(manager.update_fft_size(8192));
+        let info = manager.info();
+        assert_eq!(info.fft_size, 8192);
+        assert!((info.frequency_resolution - 5.859).abs() < 0.01);
+        assert!((info.latency_ms - 170.67).abs() < 0.1);
+
+        // 2. Same size -> no rebuild
+        assert!(!manager.update_fft_size(8192));
+    }
+
+    #[test]
+    fn test_fft_size_sanitization() {
+        // Out of range values are clamped, odd sizes rounded up to a power of two
+        assert_eq!(sanitize_fft_size(64), MIN_FFT_SIZE);
+        assert_eq!(sanitize_fft_size(1_000_000), MAX_FFT_SIZE);
+        assert_eq!(sanitize_fft_size(3000), 4096);
+
+        let manager = FFTConfigManager::new(48000, 100);
+        assert_eq!(manager.get_fft_size(), MIN_FFT_SIZE);
+    }
+
+    #[test]
+    fn test_overlap_hop_size() {
+        assert_eq!(AnalysisOverlap::Half.hop_size(2048), 1024);
+        assert_eq!(AnalysisOverlap::ThreeQuarters.hop_size(2048), 512);
+        assert_eq!(AnalysisOverlap::SevenEighths.hop_size(2048), 256);
+        assert_eq!(AnalysisOverlap::SevenEighths.hop_size(512), 64);
+    }
 }
-        
@@ -1,20 +1,48 @@
 use realfft::{RealFftPlanner, RealToComplex};
+use serde::{Serialize, Deserialize};
 use std::sync::Arc;
-use crate::{fft_config::FIXED_FFT_SIZE, shared_state::SILENCE_DB};
-
+use crate::ballistics::{PeakHold, PeakState, Smoothing};
+use crate::constant_q::{self, ConstantQ, CqtResolution};
+use crate::fft_config::{sanitize_fft_size, AnalysisMode, AnalysisOverlap, DEFAULT_FFT_SIZE};
+use crate::frequency_scale::{FrequencyRange, FrequencyScale, DEFAULT_KNEE_FREQ};
+use crate::multi_resolution::{self, ResolutionStage};
+use crate::octave_bands::{self, OctaveFraction};
+use crate::weighting::{self, FrequencyWeighting};
+use crate::shared_state::{VisualProfile, SILENCE_DB};
+use crate::windowing::{WindowCorrection, WindowFunction, DEFAULT_KAISER_BETA};
+
+
+/// How the FFT bins inside one bar's frequency range are reduced to a single value
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub enum BinAggregation {
+    Peak,       // Loudest bin in the range: narrow tones never disappear
+    MeanPower,  // Average power per bin: smooth, level-independent of bar width
+    Rms,        // Total band energy (noise-bandwidth corrected), like an RTA band
+}
 
-// === GLOBAL CONSTANTS FOR MAPPING  ===
-// These define the "physics" of how wer map frequncies to visual bars
-// We exposed them publicly so the GUI can use them, too.
-pub const MAPPING_LINEAR_PROPORTION: f64 = 0.15; // Target 15% Bass (Your choice)
-pub const MAPPING_KNEE_FREQ: f64 = 500.0;            // 0-500Hz is Linear
-pub const MAPPING_MAX_FREQ: f64 = 20000.0;           // Hard limit at 20kHz
-// ===================
+impl BinAggregation {
+    /// All reducers, in the order shown in the settings UI
+    pub const ALL: [BinAggregation; 3] = [
+        BinAggregation::Peak,
+        BinAggregation::MeanPower,
+        BinAggregation::Rms,
+    ];
+
+    /// Human readable name for the settings UI
+    pub fn label(&self) -> &'static str {
+        match self {
+            BinAggregation::Peak => "Peak (Dramatic)",
+            BinAggregation::MeanPower => "Mean Power (Smooth)",
+            BinAggregation::Rms => "RMS (Band Energy)",
+        }
+    }
+}
 
 // configure for FFT processing and visualization
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Debug)]
 pub struct FFTConfig{
     pub fft_size: usize,
+    pub overlap: AnalysisOverlap,       // Frame overlap (sets the hop size)
     pub sample_rate: u32,
     pub num_bars: usize,
     pub sensitivity: f32,               // User-configurable gain
@@ -22,13 +50,24 @@ pub struct FFTConfig{
     pub release_time_ms: f32,           // bar fall speed
     pub peak_hold_time_ms: f32,         // duration of peak hold
     pub peak_release_time_ms: f32,      // peak fall speed
-    pub use_peak_aggregation: bool,     // bar aggregation peak vs average
+    pub aggregation: BinAggregation,    // How bins inside a bar are reduced
+    pub window_function: WindowFunction,
+    pub kaiser_beta: f32,               // Only used by the Kaiser window
+    pub frequency_scale: FrequencyScale,// How bars are spread across the spectrum
+    pub scale_knee_hz: f32,             // Linear/log crossover of the hybrid scale
+    pub frequency_range: FrequencyRange,// Displayed range (profile bounds or zoom)
+    pub analysis_mode: AnalysisMode,    // Free bar layout or standard octave bands
+    pub octave_fraction: OctaveFraction,// Band width in octave-band mode
+    pub cqt_resolution: CqtResolution,  // Bins per octave in constant-Q mode
+    pub weighting: FrequencyWeighting,  // A/C/Z weighting applied per bin
+    pub tilt_db_per_octave: f32,        // Spectral tilt around 1 kHz
 }
 
 impl Default for FFTConfig {
     fn default() -> Self{
         Self {
-            fft_size: FIXED_FFT_SIZE,
+            fft_size: DEFAULT_FFT_SIZE,
+            overlap: AnalysisOverlap::Half,
             sample_rate: 48000,
             num_bars: 64,
             sensitivity: 1.0,
@@ -36,37 +75,117 @@ impl Default for FFTConfig {
             release_time_ms: 200.0,
             peak_hold_time_ms: 1500.0,
             peak_release_time_ms: 1500.0,
-            use_peak_aggregation: true,
+            aggregation: BinAggregation::Peak,
+            window_function: WindowFunction::Hann,
+            kaiser_beta: DEFAULT_KAISER_BETA,
+            frequency_scale: FrequencyScale::Hybrid,
+            scale_knee_hz: DEFAULT_KNEE_FREQ,
+            frequency_range: FrequencyRange::AUDIBLE,
+            analysis_mode: AnalysisMode::Spectrum,
+            octave_fraction: OctaveFraction::Third,
+            cqt_resolution: CqtResolution::HalfSemitone,
+            weighting: FrequencyWeighting::Z,
+            tilt_db_per_octave: 0.0,
         }
      }
 }
 
-/// Maps visual bars to FFT bin ranges (start_bin, end_bin)
-type BarToBinMap = Vec<f64>;
+impl FFTConfig {
+    /// Build the processor configuration from the active visual profile
+    /// The sample rate always comes from the device, never the profile
+    pub fn from_profile(profile: &VisualProfile, sample_rate: u32) -> Self {
+        Self {
+            fft_size: sanitize_fft_size(profile.fft_size),
+            overlap: profile.overlap,
+            sample_rate,
+            num_bars: profile.num_bars,
+            sensitivity: profile.sensitivity,
+            attack_time_ms: profile.attack_time_ms,
+            release_time_ms: profile.release_time_ms,
+            peak_hold_time_ms: profile.peak_hold_time_ms,
+            peak_release_time_ms: profile.peak_release_time_ms,
+            aggregation: profile.aggregation,
+            window_function: profile.window_function,
+            kaiser_beta: profile.kaiser_beta,
+            frequency_scale: profile.frequency_scale,
+            scale_knee_hz: profile.scale_knee_hz,
+            frequency_range: profile.frequency_range(sample_rate),
+            analysis_mode: profile.analysis_mode,
+            octave_fraction: profile.octave_fraction,
+            cqt_resolution: profile.cqt_resolution,
+            weighting: profile.weighting,
+            tilt_db_per_octave: profile.tilt_db_per_octave,
+        }
+    }
+
+    /// True if both configs put the same frequencies on the same bars
+    pub fn same_bar_layout(&self, other: &FFTConfig) -> bool {
+        self.num_bars == other.num_bars
+            && self.frequency_scale == other.frequency_scale
+            && self.scale_knee_hz == other.scale_knee_hz
+            && self.frequency_range == other.frequency_range
+            && self.analysis_mode == other.analysis_mode
+            && self.octave_fraction == other.octave_fraction
+            && self.cqt_resolution == other.cqt_resolution
+    }
+}
+
+/// Fractional FFT bin positions covered by one visual bar
+/// Ranges of neighbouring bars meet half way between their centres, so together
+/// they tile the spectrum without gaps
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct BarBins {
+    center: f64,    // Bin position of the bar's nominal frequency
+    start: f64,     // Lower edge (inclusive)
+    end: f64,       // Upper edge (inclusive)
+    stage: usize,   // Spectrum the positions refer to (multi-resolution stage, else 0)
+}
+
+/// Maps visual bars to FFT bin ranges
+type BarToBinMap = Vec<BarBins>;
 
 /// Main FFT processor - handles windowing, FFT, and bar mapping
 pub struct FFTProcessor{
     config: FFTConfig,
 
+    // Sliding window state: samples are gathered across packets in a ring buffer
+    // and a new frame is analysed every hop_size samples
+    sample_ring: Vec<f32>,      // Last fft_size samples
+    ring_write_pos: usize,      // Next write position (= oldest sample)
+    samples_since_frame: usize, // New samples since the last FFT frame
+
     // FFT State (reusable, no per-frame allocation)
     fft: Arc<dyn RealToComplex<f32>>,
     input_buffer: Vec<f32>,     // Windowed inptut samples
     output_buffer: Vec<f32>,    // FFT magnitude output
     scratch_buffer: Vec<num_complex::Complex<f32>>,   // Scratch space for FFT
 
-    // Hann Window (precomputed, never changes)
-    hann_window: Vec<f32>,
+    // Analysis window (precomputed, rebuilt when the window type changes)
+    window: Vec<f32>,
+    window_correction: WindowCorrection,
+
+    // Per-bin dB offset from frequency weighting and tilt (rebuilt when either changes)
+    weighting_curve: Vec<f32>,
 
     // Bar mapping (linear + log hybrid)
     bar_to_bin_map: BarToBinMap,
 
+    // Constant-Q mode only: the transform (own, longer buffer) and the
+    // weighting + tilt at each of its bins
+    constant_q: Option<ConstantQ>,
+    constant_q_weighting: Vec<f32>,
+
+    // Multi-resolution mode only: one FFT per stage (own buffers and window) and
+    // the weighting + tilt per bin of each
+    stages: Vec<ResolutionStage>,
+    stage_weighting: Vec<Vec<f32>>,
+
+    // Unweighted dB magnitude per bin of the latest frame (peak detection)
+    spectrum_db: Vec<f32>,
+
     // Smoothing state (persists between frames)
     last_bar_heights: Vec<f32>,
-    peak_levels: Vec<f32>,
-    peak_hold_timers: Vec<f32>, // Time remaining for peak hold (ms)
-
-    // Frame Timing for smooth interpoloations
-    last_frame_time: std::time::Instant,
+    peaks: Vec<PeakState>,
 }
 
 impl FFTProcessor {
@@ -81,52 +200,132 @@ impl FFTProcessor {
         let output_buffer = vec![0.0; config.fft_size / 2 + 1];
         let scratch_buffer = fft.make_scratch_vec();
 
-        // Precompute Hann Window
-        let hann_window = Self::compute_hann_window(config.fft_size);
+        // Precompute analysis window and its gain corrections
+        let window = config.window_function.generate(config.fft_size, config.kaiser_beta);
+        let window_correction = WindowCorrection::from_window(&window);
+
+        // Precompute weighting + tilt for every bin
+        let weighting_curve = weighting::bin_curve(
+            config.weighting, config.tilt_db_per_octave, config.sample_rate, config.fft_size
+        );
 
         // Initialize bar mapping
         let bar_to_bin_map = Self::compute_bar_mapping(&config);
+        let constant_q = Self::build_constant_q(&config);
+        let constant_q_weighting = Self::constant_q_weighting(&config, constant_q.as_ref());
+        let stages = Self::build_stages(&config);
+        let stage_weighting = Self::stage_weighting(&config, &stages);
 
-        // Initialize smoothing state
-        let last_bar_heights = vec![SILENCE_DB; config.num_bars];
-        let peak_levels = vec![SILENCE_DB; config.num_bars];
-        let peak_hold_timers = vec![0.0; config.num_bars];
+        // Initialize smoothing state (one entry per bar the mapping produced)
+        let num_bars = bar_to_bin_map.len();
+        let last_bar_heights = vec![SILENCE_DB; num_bars];
+        let peaks = vec![PeakState::new(SILENCE_DB); num_bars];
+
+        let num_bins = config.fft_size / 2 + 1;
 
         Self {
+            sample_ring: vec![0.0; config.fft_size],
+            ring_write_pos: 0,
+            samples_since_frame: 0,
             config,
             fft,
             input_buffer,
             output_buffer,
             scratch_buffer,
-            hann_window,
+            window,
+            window_correction,
+            weighting_curve,
             bar_to_bin_map,
+            constant_q,
+            constant_q_weighting,
+            stages,
+            stage_weighting,
+            spectrum_db: vec![SILENCE_DB; num_bins],
             last_bar_heights,
-            peak_levels,
-            peak_hold_timers,
-            last_frame_time: std::time::Instant::now(),
+            peaks,
         }
     }
 
-    /// Process audio samples and return bar heights
-    /// 
+    /// Feed audio samples (any packet size) into the sliding window
+    ///
+    /// A new FFT frame runs every hop_size samples, so one packet can produce
+    /// zero, one or several frames. Only the most recent frame is returned.
+    /// Returns: Some((bar_heights, peak_heights)) if at least one frame ran
+    pub fn process(&mut self, samples: &[f32]) -> Option<(Vec<f32>, Vec<f32>)> {
+        let hop_size = self.hop_size();
+        let mut latest = None;
+        let mut remaining = samples;
+
+        while !remaining.is_empty() {
+            // Only take what is needed to complete the current hop
+            // (saturating: the hop may have just shrunk after an overlap change)
+            let take = hop_size.saturating_sub(self.samples_since_frame).min(remaining.len());
+            self.push_samples(&remaining[..take]);
+            remaining = &remaining[take..];
+
+            if self.samples_since_frame >= hop_size {
+                self.samples_since_frame = 0;
+                latest = Some(self.process_frame());
+            }
+        }
+
+        latest
+    }
+
+    /// Raw magnitude spectrum of the latest frame in dB, one value per bin
+    /// (before weighting, grouping and smoothing)
+    pub fn spectrum(&self) -> &[f32] {
+        &self.spectrum_db
+    }
+
+    /// Width of one FFT bin in Hz
+    pub fn frequency_resolution(&self) -> f64 {
+        self.config.sample_rate as f64 / self.config.fft_size as f64
+    }
+
+    /// Number of new samples between FFT frames
+    pub fn hop_size(&self) -> usize {
+        self.config.overlap.hop_size(self.config.fft_size)
+    }
+
+    /// Analyse the current contents of the ring buffer
+    ///
     /// Returns: (bar_heights, peak_heights)
-    pub fn process(&mut self, samples: &[f32]) -> (Vec<f32>, Vec<f32>) {
-        // Calculate delta time for smoothing
-        let now = std::time::Instant::now();
-        let delta_ms = now.duration_since(self.last_frame_time).as_secs_f32() * 1000.0;
-        self.last_frame_time = now;
+    fn process_frame(&mut self) -> (Vec<f32>, Vec<f32>) {
+        // Time between frames is fixed by the hop, independent of packet timing
+        let delta_ms = self.hop_size() as f32 / self.config.sample_rate as f32 * 1000.0;
 
         // step 1: Copy samples to input buffer and apply windowing
-        self.apply_window(samples);
+        self.apply_window();
         
         // step 2: Perform FFT
         self.compute_fft();
 
         // Step 3: Convert to magnitudes (dB scale)
-        let magnitudes = self.compute_magnitudes();
+        let mut magnitudes = self.compute_magnitudes();
+        self.spectrum_db.clone_from(&magnitudes);
+
+        // Step 3b: Frequency weighting and tilt, per bin, before grouping
+        self.apply_weighting(&mut magnitudes);
         
-        // Step 4:
-        let raw_bars = self.group_bins(&magnitudes);
+        // Step 4: Group bins into bars (constant-Q bins inside the displayed range are
+        // the bars, multi-resolution bars read their own stage's spectrum)
+        let raw_bars = if let Some(constant_q) = self.constant_q.as_mut() {
+            let sensitivity = self.config.sensitivity;
+            let bins = constant_q.bins_in(self.config.frequency_range);
+            constant_q
+                .analyse()[bins.clone()]
+                .iter()
+                .zip(&self.constant_q_weighting[bins])
+                .map(|(&amplitude, &gain)| 20.0 * (amplitude * sensitivity + 1e-10).log10() + gain)
+                .collect()
+        } else if !self.stages.is_empty() {
+            let spectra = self.stage_magnitudes();
+            let spectra: Vec<&[f32]> = spectra.iter().map(Vec::as_slice).collect();
+            self.group_bins(&spectra)
+        } else {
+            self.group_bins(&[&magnitudes])
+        };
 
         // Step 5: Apply smoothing (attack/release)
         let smoothed_bars = self.apply_smoothing(&raw_bars, delta_ms);
@@ -137,19 +336,60 @@ impl FFTProcessor {
         (smoothed_bars, peaks)
     }
 
-    #[allow(dead_code)]
     /// Update configuration (e.g., user changed the number of bars)
     pub fn update_config(&mut self, config: FFTConfig) {
 
-        // Sample Rate chanmge triggers a full rebuild, not an update
+        // Sample rate or FFT size changes trigger a full rebuild, not an update
+        // (FFT plan, window and every buffer depend on them)
+        if config.fft_size != self.config.fft_size || config.sample_rate != self.config.sample_rate {
+            *self = Self::new(config);
+            return;
+        }
 
-        if config.num_bars != self.config.num_bars {
-            self.last_bar_heights.resize(config.num_bars, SILENCE_DB);
-            self.peak_levels.resize(config.num_bars, SILENCE_DB);
-            self.peak_hold_timers.resize(config.num_bars, 0.0);
-            
-            // Recomput the mapping
+        // Recompute the mapping whenever the bar layout changes
+        if !config.same_bar_layout(&self.config) {
             self.bar_to_bin_map = Self::compute_bar_mapping(&config);
+
+            let num_bars = self.bar_to_bin_map.len();
+            self.last_bar_heights.resize(num_bars, SILENCE_DB);
+            self.peaks.resize(num_bars, PeakState::new(SILENCE_DB));
+        }
+
+        if config.window_function != self.config.window_function || config.kaiser_beta != self.config.kaiser_beta {
+            self.window = config.window_function.generate(config.fft_size, config.kaiser_beta);
+            self.window_correction = WindowCorrection::from_window(&self.window);
+        }
+
+        if config.weighting != self.config.weighting || config.tilt_db_per_octave != self.config.tilt_db_per_octave {
+            self.weighting_curve = weighting::bin_curve(
+                config.weighting, config.tilt_db_per_octave, config.sample_rate, config.fft_size
+            );
+        }
+
+        // The kernels (and the sample history) only depend on the bin layout
+        let constant_q_changed = config.analysis_mode != self.config.analysis_mode
+            || config.cqt_resolution != self.config.cqt_resolution;
+        if constant_q_changed {
+            self.constant_q = Self::build_constant_q(&config);
+        }
+        if constant_q_changed
+            || config.weighting != self.config.weighting
+            || config.tilt_db_per_octave != self.config.tilt_db_per_octave
+        {
+            self.constant_q_weighting = Self::constant_q_weighting(&config, self.constant_q.as_ref());
+        }
+
+        if config.analysis_mode != self.config.analysis_mode
+            || config.window_function != self.config.window_function
+            || config.kaiser_beta != self.config.kaiser_beta
+        {
+            self.stages = Self::build_stages(&config);
+        }
+        if config.analysis_mode != self.config.analysis_mode
+            || config.weighting != self.config.weighting
+            || config.tilt_db_per_octave != self.config.tilt_db_per_octave
+        {
+            self.stage_weighting = Self::stage_weighting(&config, &self.stages);
         }
 
         self.config = config;
@@ -162,53 +402,100 @@ impl FFTProcessor {
         total_bars: usize,
         sample_rate: u32,
         fft_size: usize,
+        scale: FrequencyScale,
+        range: FrequencyRange,
+        knee_hz: f32,
     ) -> f32 {
         let freq_res = sample_rate as f64 / fft_size as f64;
-        let linear_bar_count = (total_bars as f64 * MAPPING_LINEAR_PROPORTION).round() as usize;
+        scale.bar_frequency(bar_index, total_bars, range, knee_hz as f64, freq_res) as f32
+    }
+
+
+    // ============ Private Implementation ============
 
-        // 1. Check linear region
-        if bar_index < linear_bar_count {
-            let t = (bar_index + 1) as f64 / linear_bar_count as f64;
-            return (t * MAPPING_KNEE_FREQ) as f32;
+    // Append samples to the ring buffer (overwriting the oldest)
+    fn push_samples(&mut self, samples: &[f32]) {
+        let size = self.sample_ring.len();
+
+        for &sample in samples {
+            self.sample_ring[self.ring_write_pos] = sample;
+            self.ring_write_pos = (self.ring_write_pos + 1) % size;
         }
+        self.samples_since_frame += samples.len();
 
-        // 2. Check log region
-        let log_bar_count = total_bars - linear_bar_count;
-        let log_index = bar_index - linear_bar_count;
+        if let Some(constant_q) = self.constant_q.as_mut() {
+            constant_q.push(samples);
+        }
+        for stage in &mut self.stages {
+            stage.push(samples);
+        }
+    }
 
-        let t = (log_index + 1) as f64 / log_bar_count as f64;
-        let min_log_freq = MAPPING_KNEE_FREQ.max(freq_res); // Start where lineaer left off
+    // The constant-Q transform, only in constant-Q mode
+    // (the kernels take a moment to build and the buffer is up to 32768 samples)
+    fn build_constant_q(config: &FFTConfig) -> Option<ConstantQ> {
+        if config.analysis_mode != AnalysisMode::ConstantQ {
+            return None;
+        }
+        Some(ConstantQ::new(config.sample_rate, config.cqt_resolution))
+    }
 
-        (min_log_freq * (MAPPING_MAX_FREQ / min_log_freq).powf(t)) as f32
-    }    
-    
+    // Weighting/tilt gain of every CQ bin (cheap, so weighting changes keep the transform)
+    fn constant_q_weighting(config: &FFTConfig, constant_q: Option<&ConstantQ>) -> Vec<f32> {
+        constant_q.map_or_else(Vec::new, |constant_q| {
+            weighting::frequency_curve(config.weighting, config.tilt_db_per_octave, constant_q.frequencies())
+        })
+    }
 
-    // ============ Private Implementation ============
+    // One FFT per stage, only in multi-resolution mode
+    fn build_stages(config: &FFTConfig) -> Vec<ResolutionStage> {
+        if config.analysis_mode != AnalysisMode::MultiResolution {
+            return Vec::new();
+        }
+        multi_resolution::STAGES
+            .iter()
+            .map(|spec| ResolutionStage::new(spec.fft_size_at(config.sample_rate), config.window_function, config.kaiser_beta))
+            .collect()
+    }
 
-    // Precompute Hann Window Function
-    fn compute_hann_window(size: usize) -> Vec<f32> {
-        (0..size)
-            .map(|i| {
-                let angle = 2.0 * std::f32::consts::PI * i as f32 / (size - 1) as f32;
-                0.5 * (1.0 - angle.cos())
-            })
+    fn stage_weighting(config: &FFTConfig, stages: &[ResolutionStage]) -> Vec<Vec<f32>> {
+        stages
+            .iter()
+            .map(|stage| weighting::bin_curve(
+                config.weighting, config.tilt_db_per_octave, config.sample_rate, stage.fft_size()
+            ))
             .collect()
     }
 
-    // Apply Hann Window to input samples
-    fn apply_window(&mut self, samples: &[f32]) {
-        let len = samples.len().min(self.config.fft_size);
+    /// Weighted dB spectrum of every multi-resolution stage
+    /// Levels are scaled to the main FFT size, so a steady tone reads the same as in
+    /// the single-FFT modes (and the same in every stage, with no step at the seams)
+    fn stage_magnitudes(&mut self) -> Vec<Vec<f32>> {
+        // A tone of amplitude A reads A * sqrt(N) / 2 after compute_magnitudes()
+        let tone_scale = (self.config.fft_size as f32).sqrt() / 2.0 * self.config.sensitivity;
+
+        self.stages
+            .iter_mut()
+            .zip(&self.stage_weighting)
+            .map(|(stage, curve)| {
+                stage
+                    .analyse()
+                    .iter()
+                    .zip(curve)
+                    .map(|(&amplitude, &gain)| 20.0 * (amplitude * tone_scale + 1e-10).log10() + gain)
+                    .collect()
+            })
+            .collect()
+    }
 
-        // copy and window
-        for i in 0..len {
-            self.input_buffer[i] = samples[i] * self.hann_window[i];
-        }
+    // Unroll the ring buffer (oldest first) and apply the analysis window
+    fn apply_window(&mut self) {
+        let (newest, oldest) = self.sample_ring.split_at(self.ring_write_pos);
+        let ordered = oldest.iter().chain(newest.iter());
 
-        // zero-pad if needed
-        for i in len..self.config.fft_size {
-            self.input_buffer[i] = 0.0;
+        for ((out, &sample), &w) in self.input_buffer.iter_mut().zip(ordered).zip(self.window.iter()) {
+            *out = sample * w;
         }
-
     }
 
     /// Compute FFT (modifies output_buffer in place)
@@ -230,26 +517,27 @@ impl FFTProcessor {
     /// 
     /// Normalization strategy:
     /// - FFT output scales with FFT size, so we normalize by sqrt(N) for energy preservation
-    /// - Hann window reduces energy by ~0.5, so we correct by 2.0
+    /// - The window attenuates the signal, so we apply its amplitude correction
+    ///   (1 / coherent gain: 2.0 for Hann, ~4.6 for flat-top, 1.0 for rectangular)
     /// - We use sqrt(N) instead of N/2 because we want ENERGY scaling, not amplitude
     ///   This preserves the dynamic range between loud and quiet frequency content
     /// - Sensitivity is applied as a pre-log multiplier to maintain perceptual linearity
     ///
-    /// For a 2048-point FFT:
+    /// For a 2048-point FFT with a Hann window:
     /// - sqrt(2048) ≈ 45.25
     /// - Combined factor: 2.0 / 45.25 ≈ 0.044
     /// - A full-scale sine produces ~22.6 magnitude → ~0.996 normalized → ~0 dB ✓
     /// - But real music with spread energy stays dynamic!
     fn compute_magnitudes(&self) -> Vec<f32> {
-       // Hann window correction (window averages 0.5, so multiply by 2)
-        const HANN_CORRECTION: f32 = 2.0;
-        
+        // Window correction (e.g. Hann averages 0.5, so multiply by 2)
+        let window_correction = self.window_correction.amplitude;
+
         // Use sqrt(N) normalization for energy-preserving scaling
         // This is gentler than N/2 and preserves inter-bin dynamics
         let fft_normalization = 1.0 / (self.config.fft_size as f32).sqrt();
         
         // Combined normalization factor
-        let normalization = HANN_CORRECTION * fft_normalization;
+        let normalization = window_correction * fft_normalization;
 
         self.output_buffer
             .iter()
@@ -271,35 +559,102 @@ impl FFTProcessor {
             .collect()
     }
 
-    /// Perform a linear-log hybrid mapping of the FFT data to visualization bars
-    fn compute_bar_mapping(config: &FFTConfig) -> BarToBinMap {
-        let mut map = Vec::with_capacity(config.num_bars);
+    /// Add the weighting/tilt curve to the dB spectrum
+    fn apply_weighting(&self, magnitudes: &mut [f32]) {
+        for (mag, &gain) in magnitudes.iter_mut().zip(&self.weighting_curve) {
+            *mag += gain;
+        }
+    }
 
+    /// Map visualization bars to FFT bins along the configured frequency scale,
+    /// or to the standard octave bands in octave-band mode
+    /// In constant-Q mode each bar is one CQ bin; the ranges only locate the bars
+    /// on the FFT spectrum (inspector, peak readout)
+    /// In multi-resolution mode the bars follow the scale as usual, but each range
+    /// is given in the bins of its own stage
+    fn compute_bar_mapping(config: &FFTConfig) -> BarToBinMap {
         let frequency_resolution = config.sample_rate as f64 / config.fft_size as f64;
-        
-        let linear_bar_count = (config.num_bars as f64 * MAPPING_LINEAR_PROPORTION).round() as usize;
-        let log_bar_count = config.num_bars - linear_bar_count;
-
-        // === LINEAR SECTION (0 Hz to ) ===
-        for i in 0..linear_bar_count {
-            let freq_target = (i + 1) as f64 / linear_bar_count as f64 * MAPPING_KNEE_FREQ;
-            let bin_pos = freq_target / frequency_resolution;
-            map.push(bin_pos);
+
+        if config.analysis_mode == AnalysisMode::OctaveBands {
+            let nyquist = config.sample_rate as f64 / 2.0;
+            return octave_bands::bands(config.octave_fraction, nyquist)
+                .iter()
+                .filter(|band| config.frequency_range.contains(band.nominal_hz))
+                .map(|band| BarBins {
+                    center: band.center_hz / frequency_resolution,
+                    start: band.lower_hz / frequency_resolution,
+                    end: band.upper_hz / frequency_resolution,
+                    stage: 0,
+                })
+                .collect();
         }
-    
-        // === LOGARITHMIC SECTION ===
-        let min_log_bars = MAPPING_KNEE_FREQ.max(frequency_resolution);
-
-        for i in 0..log_bar_count {
-            let t = (i + 1) as f64 / log_bar_count as f64;
-            // True Logarithmic Interpolation
-            let freq_target = min_log_bars * (MAPPING_MAX_FREQ / min_log_bars).powf(t);
-            let bin_pos = freq_target / frequency_resolution;
-            map.push(bin_pos);
-        
+
+        if config.analysis_mode == AnalysisMode::ConstantQ {
+            let centers: Vec<f64> = constant_q::bin_frequencies(config.cqt_resolution, config.sample_rate, config.frequency_range)
+                .iter()
+                .map(|freq| freq / frequency_resolution)
+                .collect();
+            return Self::bar_ranges(&centers);
         }
 
-        map
+        let centers: Vec<f64> = (0..config.num_bars)
+            .map(|i| {
+                let freq_target = config.frequency_scale.bar_frequency(
+                    i, config.num_bars, config.frequency_range, config.scale_knee_hz as f64, frequency_resolution
+                );
+                freq_target / frequency_resolution
+            })
+            .collect();
+
+        let ranges = Self::bar_ranges(&centers);
+        if config.analysis_mode != AnalysisMode::MultiResolution {
+            return ranges;
+        }
+
+        // Multi-resolution: same bars, each re-expressed in the bins of the stage
+        // that covers its centre frequency
+        ranges
+            .into_iter()
+            .map(|bar| {
+                let stage = multi_resolution::stage_index(bar.center * frequency_resolution);
+                let stage_size = multi_resolution::STAGES[stage].fft_size_at(config.sample_rate);
+                let scale = frequency_resolution * stage_size as f64 / config.sample_rate as f64;
+                BarBins {
+                    center: bar.center * scale,
+                    start: bar.start * scale,
+                    end: bar.end * scale,
+                    stage,
+                }
+            })
+            .collect()
+    }
+
+    /// Turn bar centre positions into contiguous bin ranges
+    /// Each edge sits half way between two neighbouring centres
+    fn bar_ranges(centers: &[f64]) -> BarToBinMap {
+        let n = centers.len();
+
+        (0..n)
+            .map(|i| {
+                let center = centers[i];
+                let prev = if i > 0 { Some(centers[i - 1]) } else { None };
+                let next = centers.get(i + 1).copied();
+
+                // Outer bars mirror the spacing of their only neighbour
+                let start = match (prev, next) {
+                    (Some(p), _) => (p + center) / 2.0,
+                    (None, Some(nx)) => center - (nx - center) / 2.0,
+                    (None, None) => center,
+                };
+                let end = match (prev, next) {
+                    (_, Some(nx)) => (center + nx) / 2.0,
+                    (Some(p), None) => center + (center - p) / 2.0,
+                    (None, None) => center,
+                };
+
+                BarBins { center, start: start.max(0.0), end, stage: 0 }
+            })
+            .collect()
     }
 
     fn interpolate_hermite(y0: f32, y1: f32, y2: f32, y3: f32, t: f32) -> f32 {
@@ -312,77 +667,115 @@ impl FFTProcessor {
     }
 
     // Group FFT bin data into visualization bars
-    fn group_bins(&self, magnitudes: &[f32]) -> Vec<f32> {
-        let max_bin_idx = magnitudes.len().saturating_sub(1);
+    //
+    // Octave bands always sum their band power (like a hardware RTA).
+    // Other bars that cover at least one whole bin reduce their bin range with the
+    // selected aggregation. Bars narrower than a bin (bass end of a large bar count)
+    // have nothing to reduce, so they Hermite-interpolate at their centre instead.
+    //
+    // `spectra` holds one dB spectrum per stage; bars pick theirs by `stage`
+    fn group_bins(&self, spectra: &[&[f32]]) -> Vec<f32> {
+        // Power summing needs the noise-bandwidth of the window:
+        // magnitudes are amplitude-corrected, total energy needs energy correction
+        let energy_ratio = (self.window_correction.energy / self.window_correction.amplitude).powi(2);
 
         self.bar_to_bin_map
             .iter()
-            .map(|&bin_pos| {
-                if bin_pos < 0.0 || bin_pos >= max_bin_idx as f64 {
+            .map(|bar| {
+                let magnitudes = spectra[bar.stage];
+                let max_bin_idx = magnitudes.len().saturating_sub(1);
+                if bar.center < 0.0 || bar.center >= max_bin_idx as f64 {
                     return SILENCE_DB;
                 }
 
-                let idx = bin_pos.floor() as usize;
-                let t = (bin_pos - idx as f64) as f32;
+                if self.config.analysis_mode == AnalysisMode::OctaveBands {
+                    return Self::power_to_db(Self::band_power(magnitudes, bar) * energy_ratio);
+                }
+
+                let first = bar.start.ceil() as usize;
+                let last = (bar.end.floor() as usize).min(max_bin_idx);
 
-                // Get surrounding bins for interpolation
-                let y1 = magnitudes[idx];
-                let y2 = if idx + 1 <= max_bin_idx { magnitudes[idx + 1] } else { y1 };
-                let y0 = if idx > 0 { magnitudes[idx -1] } else { y1 };
-                let y3 = if idx + 2 <= max_bin_idx { magnitudes[idx + 2] } else { y2 };
+                if first > last {
+                    return Self::interpolate_at(magnitudes, bar.center);
+                }
 
-                // Hermite interpolation
-                Self::interpolate_hermite(y0, y1, y2, y3, t)
+                let bins = &magnitudes[first..=last];
+                match self.config.aggregation {
+                    BinAggregation::Peak => bins.iter().cloned().fold(SILENCE_DB, f32::max),
+                    BinAggregation::MeanPower => {
+                        let power: f32 = bins.iter().map(|&db| Self::db_to_power(db)).sum();
+                        Self::power_to_db(power / bins.len() as f32)
+                    }
+                    BinAggregation::Rms => {
+                        let power: f32 = bins.iter().map(|&db| Self::db_to_power(db)).sum();
+                        Self::power_to_db(power * energy_ratio)
+                    }
+                }
             })
             .collect()
     }
 
+    /// Hermite interpolation of the dB spectrum at a fractional bin position
+    fn interpolate_at(magnitudes: &[f32], bin_pos: f64) -> f32 {
+        let max_bin_idx = magnitudes.len().saturating_sub(1);
+        let idx = bin_pos.floor() as usize;
+        let t = (bin_pos - idx as f64) as f32;
+
+        // Get surrounding bins for interpolation
+        let y1 = magnitudes[idx];
+        let y2 = if idx < max_bin_idx { magnitudes[idx + 1] } else { y1 };
+        let y0 = if idx > 0 { magnitudes[idx -1] } else { y1 };
+        let y3 = if idx + 2 <= max_bin_idx { magnitudes[idx + 2] } else { y2 };
+
+        // Hermite interpolation
+        Self::interpolate_hermite(y0, y1, y2, y3, t)
+    }
+
+    /// Total power inside a band with fractional bin edges
+    /// Bin k covers [k - 0.5, k + 0.5]; each bin contributes the share that overlaps
+    /// the band, so adjacent bands split a shared bin instead of counting it twice
+    fn band_power(magnitudes: &[f32], band: &BarBins) -> f32 {
+        let max_bin_idx = magnitudes.len().saturating_sub(1);
+        let first = (band.start + 0.5).floor().max(0.0) as usize;
+        let last = ((band.end + 0.5).floor() as usize).min(max_bin_idx);
+
+        (first..=last)
+            .map(|k| {
+                let lo = (k as f64 - 0.5).max(band.start);
+                let hi = (k as f64 + 0.5).min(band.end);
+                let weight = (hi - lo).max(0.0) as f32;
+                Self::db_to_power(magnitudes[k]) * weight
+            })
+            .sum()
+    }
+
+    fn db_to_power(db: f32) -> f32 {
+        10.0f32.powf(db / 10.0)
+    }
+
+    fn power_to_db(power: f32) -> f32 {
+        10.0 * (power + 1e-20).log10()
+    }
+
     // Apply attack/releaser smoothing
     fn apply_smoothing(&mut self, raw_bars: &[f32], delta_ms: f32) -> Vec<f32> {
-        let attack_factor = (delta_ms / self.config.attack_time_ms).min(1.0);
-        let release_factor = (delta_ms / self.config.release_time_ms).min(1.0);
-
-        for (i, &raw) in raw_bars.iter().enumerate() {
-            let last = self.last_bar_heights[i];
-            
-            // if new value is higher, use attack time
-            // if new value is lower, use release time
-            let smoothed = if raw > last {
-                last + (raw - last) * attack_factor
-            } else {
-                last + (raw - last) * release_factor
-            };
-
-            self.last_bar_heights[i] = smoothed;
-        }
+        let smoothing = Smoothing {
+            attack_ms: self.config.attack_time_ms,
+            release_ms: self.config.release_time_ms,
+        };
+        smoothing.apply(&mut self.last_bar_heights, raw_bars, delta_ms);
 
         self.last_bar_heights.clone()
     }
 
     fn update_peaks(&mut self, bars: &[f32], delta_ms: f32) -> Vec<f32> {
-        for (i, &bar_height) in bars.iter().enumerate() {
-            // if current bar exceeds peak, reset the peak
-            if bar_height > self.peak_levels[i] {
-                self.peak_levels[i] = bar_height;
-                self.peak_hold_timers[i] = self.config.peak_hold_time_ms;
-            } else{
-                // decrement the hold timer
-                self.peak_hold_timers[i] -= delta_ms;
-
-                // if the hold expired, let peak fall!
-                if self.peak_hold_timers[i] <= 0.0 {
-                    let release_factor = (delta_ms / self.config.peak_release_time_ms).min(1.0);
-                    self.peak_levels[i] -= (self.peak_levels[i] - bar_height) * release_factor;
-
-                    // Never fall below current bar
-                    if self.peak_levels[i] < bar_height {
-                       self.peak_levels[i] = bar_height;
-                    }
-                }
-            }
-        }
+        let hold = PeakHold {
+            hold_ms: self.config.peak_hold_time_ms,
+            release_ms: self.config.peak_release_time_ms,
+        };
+        hold.apply(&mut self.peaks, bars, delta_ms);
 
-        self.peak_levels.clone()
+        self.peaks.iter().map(|peak| peak.level).collect()
     }
 
     // Get a copy of the current configuration
@@ -400,7 +793,7 @@ mod tests {
 
     #[test]
     fn test_hann_window() {
-        let window = FFTProcessor::compute_hann_window(1024);
+        let window = WindowFunction::Hann.generate(1024, DEFAULT_KAISER_BETA);
 
         let size = 1024;
 
@@ -496,7 +889,8 @@ mod tests {
         // Check specific indices to ensure the linear/log split is happening where expected
         let knee_freq = FFTProcessor::calculate_bar_frequency(
             15, // Approx knee index for 15% linear proportion
-            num_bars,sample_rate, fft_size
+            num_bars,sample_rate, fft_size,
+            FrequencyScale::Hybrid, FrequencyRange::AUDIBLE, DEFAULT_KNEE_FREQ
         );
         
         // Verify it's close to 500Hz
@@ -567,7 +961,7 @@ mod tests {
         }
 
         // 3. Process
-        let (bars, _) = processor.process(&buffer);
+        let (bars, _) = processor.process(&buffer).expect("A full window should produce a frame");
 
         // 4. Find the peak bar
         // We need to know which bar *should* be the peak.
@@ -586,7 +980,10 @@ mod tests {
             max_idx, 
             bars.len(), 
             sample_rate, 
-            2048
+            2048,
+            FrequencyScale::Hybrid,
+            FrequencyRange::AUDIBLE,
+            DEFAULT_KNEE_FREQ
         );
 
         // 5. Assert (allow some variance due to bin resolution)
@@ -597,9 +994,291 @@ mod tests {
         // 6. Assert Amplitude (Sine wave of amplitude 1.0 should be close to 0 dBfs)
         assert!(max_db > -3.0, "Signal was attenuated too much. Measured: {:.1} dB", max_db);
     }
-}
 
+    #[test]
+    fn test_window_switch_preserves_tone_level() {
+        // Amplitude correction should make every window report the same level for a tone
+        let sample_rate = 48000;
+        let buffer: Vec<f32> = (0..2048)
+            .map(|i| (i as f32 / sample_rate as f32 * 1000.0 * 2.0 * std::f32::consts::PI).sin())
+            .collect();
+
+        let peak_db = |window_function: WindowFunction| {
+            let mut processor = FFTProcessor::new(FFTConfig {
+                num_bars: 256,
+                attack_time_ms: 0.0,
+                window_function,
+                ..Default::default()
+            });
+            processor.process(&buffer);
+            let magnitudes = processor.compute_magnitudes();
+            magnitudes.iter().cloned().fold(f32::MIN, f32::max)
+        };
+
+        let hann = peak_db(WindowFunction::Hann);
+        let flat_top = peak_db(WindowFunction::FlatTop);
+        let blackman_harris = peak_db(WindowFunction::BlackmanHarris);
+
+        // Flat-top has no scalloping loss, so it can only read higher than Hann
+        assert!(flat_top >= hann - 0.1, "Flat-top {:.2} dB vs Hann {:.2} dB", flat_top, hann);
+        assert!((flat_top - hann).abs() < 1.5);
+        assert!((blackman_harris - hann).abs() < 1.5);
+    }
+
+    #[test]
+    fn test_frames_follow_hop_not_packet_size() {
+        let config = FFTConfig {
+            fft_size: 1024,
+            overlap: AnalysisOverlap::ThreeQuarters, // hop = 256
+            num_bars: 16,
+            ..Default::default()
+        };
+        let mut processor = FFTProcessor::new(config);
+
+        // 1. Small packets: nothing until a full hop has been gathered
+        assert!(processor.process(&[0.0; 100]).is_none());
+        assert!(processor.process(&[0.0; 100]).is_none());
+        assert!(processor.process(&[0.0; 56]).is_some());
+
+        // 2. One large packet runs several frames and keeps the remainder
+        assert!(processor.process(&[0.0; 600]).is_some());
+        assert_eq!(processor.samples_since_frame, 600 - 2 * 256);
+    }
+
+    #[test]
+    fn test_ring_buffer_keeps_chronological_order() {
+        let config = FFTConfig {
+            fft_size: 512,
+            overlap: AnalysisOverlap::Half,
+            window_function: WindowFunction::Rectangular,
+            num_bars: 16,
+            ..Default::default()
+        };
+        let mut processor = FFTProcessor::new(config);
+
+        // Push a ramp across several odd-sized packets
+        let ramp: Vec<f32> = (0..1300).map(|i| i as f32).collect();
+        for chunk in ramp.chunks(97) {
+            processor.process(chunk);
+        }
+
+        // The windowed input must be the newest 512 samples, oldest first
+        processor.apply_window();
+        assert_eq!(processor.input_buffer[0], (1300 - 512) as f32);
+        assert_eq!(processor.input_buffer[511], 1299.0);
+    }
+
+    /// Run one frame of a sine through a processor and return the raw
+    /// (unsmoothed) bars together with the loudest FFT bin
+    fn tone_bars(aggregation: BinAggregation, num_bars: usize, freq: f32) -> (Vec<f32>, f32) {
+        let sample_rate = 48000;
+        let mut processor = FFTProcessor::new(FFTConfig {
+            num_bars,
+            aggregation,
+            ..Default::default()
+        });
+        let buffer: Vec<f32> = (0..2048)
+            .map(|i| (i as f32 / sample_rate as f32 * freq * 2.0 * std::f32::consts::PI).sin())
+            .collect();
+        processor.process(&buffer);
+
+        let magnitudes = processor.compute_magnitudes();
+        let loudest_bin = magnitudes.iter().cloned().fold(f32::MIN, f32::max);
+        (processor.group_bins(&[&magnitudes]), loudest_bin)
+    }
+
+    #[test]
+    fn test_bar_ranges_tile_spectrum() {
+        let processor = FFTProcessor::new(FFTConfig { num_bars: 32, ..Default::default() });
+        let map = &processor.bar_to_bin_map;
+
+        for pair in map.windows(2) {
+            assert_eq!(pair[0].end, pair[1].start, "Bar ranges must be contiguous");
+            assert!(pair[0].start <= pair[0].center && pair[0].center <= pair[0].end);
+        }
+    }
+
+    #[test]
+    fn test_peak_aggregation_keeps_narrow_tone() {
+        // With only 16 bars, 7.3 kHz sits between two bar centres.
+        // Point sampling at the centres would miss it, range aggregation must not.
+        let (bars, loudest_bin) = tone_bars(BinAggregation::Peak, 16, 7300.0);
+        let loudest_bar = bars.iter().cloned().fold(f32::MIN, f32::max);
+
+        assert!((loudest_bar - loudest_bin).abs() < 0.01,
+            "Peak bar {:.1} dB should match loudest bin {:.1} dB", loudest_bar, loudest_bin);
+    }
 
+    #[test]
+    fn test_rms_aggregation_measures_band_energy() {
+        // A tone's total band energy equals its amplitude-corrected peak level
+        // (within Hann scalloping), while the per-bin mean spreads it out
+        let (peak_bars, _) = tone_bars(BinAggregation::Peak, 16, 7300.0);
+        let (rms_bars, _) = tone_bars(BinAggregation::Rms, 16, 7300.0);
+        let (mean_bars, _) = tone_bars(BinAggregation::MeanPower, 16, 7300.0);
+
+        let max = |bars: &[f32]| bars.iter().cloned().fold(f32::MIN, f32::max);
+        assert!((max(&rms_bars) - max(&peak_bars)).abs() < 1.5);
+        assert!(max(&mean_bars) < max(&peak_bars) - 6.0, "Wide bands should dilute the mean");
+    }
 
+    #[test]
+    fn test_octave_bands_ignore_bar_count() {
+        let processor = FFTProcessor::new(FFTConfig {
+            num_bars: 200,
+            analysis_mode: AnalysisMode::OctaveBands,
+            octave_fraction: OctaveFraction::Third,
+            ..Default::default()
+        });
 
-            
\ No newline at end of file
+        assert_eq!(processor.bar_to_bin_map.len(), 31);
+        assert_eq!(processor.last_bar_heights.len(), 31);
+    }
+
+    #[test]
+    fn test_octave_band_sums_tone_power() {
+        // A 1 kHz sine sits in the 1k octave band; summed band power should report
+        // the tone's level, and the neighbouring bands should be far below it
+        let sample_rate = 48000;
+        let mut processor = FFTProcessor::new(FFTConfig {
+            analysis_mode: AnalysisMode::OctaveBands,
+            octave_fraction: OctaveFraction::Full,
+            ..Default::default()
+        });
+        let buffer: Vec<f32> = (0..2048)
+            .map(|i| (i as f32 / sample_rate as f32 * 1000.0 * 2.0 * std::f32::consts::PI).sin())
+            .collect();
+        processor.process(&buffer);
+
+        let magnitudes = processor.compute_magnitudes();
+        let bands = processor.group_bins(&[&magnitudes]);
+        let loudest_bin = magnitudes.iter().cloned().fold(f32::MIN, f32::max);
+
+        // Band index 5 is "1k" (31.5, 63, 125, 250, 500, 1k ...)
+        assert!((bands[5] - loudest_bin).abs() < 1.5, "1k band {:.1} dB vs tone {:.1} dB", bands[5], loudest_bin);
+        assert!(bands[3] < bands[5] - 20.0);
+        assert!(bands[7] < bands[5] - 20.0);
+    }
+
+    #[test]
+    fn test_constant_q_bars_follow_bins() {
+        // One bar per CQ bin regardless of the bar count; switching modes at runtime
+        // resizes the smoothing state
+        let mut processor = FFTProcessor::new(FFTConfig { num_bars: 64, ..Default::default() });
+        processor.update_config(FFTConfig {
+            num_bars: 64,
+            analysis_mode: AnalysisMode::ConstantQ,
+            cqt_resolution: CqtResolution::Semitone,
+            ..Default::default()
+        });
+        assert_eq!(processor.bar_to_bin_map.len(), 109);
+        assert_eq!(processor.last_bar_heights.len(), 109);
+
+        // A full-scale A4 (bin 45 above C1) reads 0 dB once the bass kernels are filled
+        let buffer: Vec<f32> = (0..32768)
+            .map(|i| (i as f32 / 48000.0 * 440.0 * 2.0 * std::f32::consts::PI).sin())
+            .collect();
+        processor.process(&buffer);
+
+        let raw = processor.constant_q.as_mut().unwrap().analyse();
+        let loudest = (0..raw.len()).max_by(|&a, &b| raw[a].total_cmp(&raw[b])).unwrap();
+        assert_eq!(loudest, 45);
+        assert!((20.0 * raw[45].log10()).abs() < 0.2, "A4 reads {:.2} dB", 20.0 * raw[45].log10());
+
+        // Changing the weighting only swaps the gain curve: the sample history survives
+        processor.update_config(FFTConfig {
+            num_bars: 64,
+            analysis_mode: AnalysisMode::ConstantQ,
+            cqt_resolution: CqtResolution::Semitone,
+            tilt_db_per_octave: 3.0,
+            ..Default::default()
+        });
+        let raw = processor.constant_q.as_mut().unwrap().analyse();
+        assert!((20.0 * raw[45].log10()).abs() < 0.2, "A4 reads {:.2} dB after a tilt change", 20.0 * raw[45].log10());
+        assert_eq!(processor.constant_q_weighting.len(), 109);
+
+        // Zooming selects bins from the same transform (A3 - A5), history intact
+        processor.update_config(FFTConfig {
+            num_bars: 64,
+            analysis_mode: AnalysisMode::ConstantQ,
+            cqt_resolution: CqtResolution::Semitone,
+            tilt_db_per_octave: 3.0,
+            frequency_range: FrequencyRange { min_hz: 219.0, max_hz: 881.0 },
+            ..Default::default()
+        });
+        assert_eq!(processor.bar_to_bin_map.len(), 25);
+        let raw = processor.constant_q.as_mut().unwrap().analyse();
+        assert!((20.0 * raw[45].log10()).abs() < 0.2, "A4 reads {:.2} dB after zooming", 20.0 * raw[45].log10());
+        let hop = processor.hop_size();
+        let (bars, _) = processor.process(&buffer[..hop]).expect("a hop completes a frame");
+        assert_eq!(bars.len(), 25);
+    }
+
+    #[test]
+    fn test_multi_resolution_stitches_stages() {
+        let config = FFTConfig {
+            num_bars: 128,
+            frequency_scale: FrequencyScale::Logarithmic,
+            analysis_mode: AnalysisMode::MultiResolution,
+            attack_time_ms: 0.001, // No smoothing: read the raw level
+            ..Default::default()
+        };
+        let mut processor = FFTProcessor::new(config.clone());
+
+        // Bass bars read the long FFT, treble bars the short one
+        let map = &processor.bar_to_bin_map;
+        assert_eq!(map.len(), 128);
+        assert_eq!((map[0].stage, map[127].stage), (0, 2));
+        assert!(map.windows(2).all(|pair| pair[0].stage <= pair[1].stage));
+
+        // A steady tone reads the same as in the main 2048-point spectrum, whichever
+        // stage it falls in (multiples of 93.75 Hz are whole bins at every size)
+        for freq in [93.75, 1500.0, 9375.0] {
+            processor = FFTProcessor::new(config.clone());
+            let buffer: Vec<f32> = (0..8192)
+                .map(|i| 0.5 * (i as f32 / 48000.0 * freq * 2.0 * std::f32::consts::PI).sin())
+                .collect();
+            let (bars, _) = processor.process(&buffer).expect("A full window should produce a frame");
+
+            let loudest_bar = bars.iter().cloned().fold(f32::MIN, f32::max);
+            let loudest_bin = processor.spectrum().iter().cloned().fold(f32::MIN, f32::max);
+            assert!((loudest_bar - loudest_bin).abs() < 0.1,
+                "{} Hz: bar {:.2} dB vs bin {:.2} dB", freq, loudest_bar, loudest_bin);
+        }
+    }
+
+    #[test]
+    fn test_tilt_applies_before_grouping() {
+        // +3 dB/oct: a 4 kHz tone is lifted by 6 dB, a 250 Hz tone cut by 6 dB
+        let level = |tilt: f32, freq: f32| {
+            let sample_rate = 48000;
+            let mut processor = FFTProcessor::new(FFTConfig {
+                tilt_db_per_octave: tilt,
+                attack_time_ms: 0.001, // No smoothing: read the raw level
+                ..Default::default()
+            });
+            let buffer: Vec<f32> = (0..2048)
+                .map(|i| (i as f32 / sample_rate as f32 * freq * 2.0 * std::f32::consts::PI).sin())
+                .collect();
+            let (bars, _) = processor.process(&buffer).expect("A full window should produce a frame");
+            bars.iter().cloned().fold(f32::MIN, f32::max)
+        };
+
+        assert!((level(3.0, 4000.0) - level(0.0, 4000.0) - 6.0).abs() < 0.5);
+        assert!((level(3.0, 250.0) - level(0.0, 250.0) + 6.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_bar_layout_follows_frequency_range() {
+        // A zoom keeps the bar count but moves every bar
+        let config = FFTConfig::default();
+        let zoomed = FFTConfig {
+            frequency_range: FrequencyRange { min_hz: 100.0, max_hz: 1000.0 },
+            ..config.clone()
+        };
+        let tilted = FFTConfig { tilt_db_per_octave: 3.0, ..config.clone() };
+
+        assert!(!config.same_bar_layout(&zoomed));
+        assert!(config.same_bar_layout(&tilted));
+    }
+}
@@ -0,0 +1,287 @@
+//! Frequency scales for laying out visual bars
+//! A scale maps bar positions (0.0 - 1.0 across the display) to frequencies.
+//! Perceptual scales (mel, bark, ERB) spread bars the way the ear resolves pitch
+
+use serde::{Serialize, Deserialize};
+
+/// Default displayed range: the audible band
+pub const SCALE_MIN_FREQ: f64 = 20.0;
+pub const SCALE_MAX_FREQ: f64 = 20000.0;
+/// Lowest frequency any range may start at
+pub const LOWEST_FREQ: f64 = 1.0;
+/// Highest usable frequency as a share of Nyquist (the Nyquist bin itself reads silent)
+const NYQUIST_FRACTION: f64 = 0.99;
+/// Narrowest range (upper / lower) the axis can be zoomed to
+pub const MIN_RANGE_RATIO: f64 = 1.1;
+/// Share of bars given to the linear bass section of the hybrid scale
+pub const HYBRID_LINEAR_PROPORTION: f64 = 0.15;
+/// Default hybrid knee: 0-500Hz is linear
+pub const DEFAULT_KNEE_FREQ: f32 = 500.0;
+
+/// Frequencies at the left and right edge of the display
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub struct FrequencyRange {
+    pub min_hz: f64,
+    pub max_hz: f64,
+}
+
+impl FrequencyRange {
+    pub const AUDIBLE: FrequencyRange = FrequencyRange { min_hz: SCALE_MIN_FREQ, max_hz: SCALE_MAX_FREQ };
+
+    /// Everything a device at `sample_rate` can show
+    pub fn full(sample_rate: u32) -> Self {
+        let nyquist = sample_rate as f64 / 2.0 * NYQUIST_FRACTION;
+        Self { min_hz: LOWEST_FREQ, max_hz: nyquist.max(LOWEST_FREQ * MIN_RANGE_RATIO) }
+    }
+
+    /// Fit inside `outer`, keeping at least `MIN_RANGE_RATIO` between the edges
+    pub fn clamped(&self, outer: FrequencyRange) -> Self {
+        let min_hz = self.min_hz.clamp(outer.min_hz, (outer.max_hz / MIN_RANGE_RATIO).max(outer.min_hz));
+        let max_hz = self.max_hz.clamp(min_hz * MIN_RANGE_RATIO, outer.max_hz.max(min_hz * MIN_RANGE_RATIO));
+        Self { min_hz, max_hz }
+    }
+
+    pub fn contains(&self, freq_hz: f64) -> bool {
+        freq_hz >= self.min_hz && freq_hz <= self.max_hz
+    }
+
+    /// Zoom by `factor` (below 1 zooms in) around `position` (0.0 - 1.0 across the
+    /// display), spacing the edges along `scale` so the frequency under the cursor stays put
+    pub fn zoom(&self, scale: FrequencyScale, position: f64, factor: f64) -> Self {
+        let (lo, hi) = (scale.axis(self.min_hz), scale.axis(self.max_hz));
+        let anchor = lo + (hi - lo) * position;
+        Self {
+            min_hz: scale.unaxis(anchor - (anchor - lo) * factor),
+            max_hz: scale.unaxis(anchor + (hi - anchor) * factor),
+        }
+    }
+
+    /// Shift by `fraction` of the displayed width (positive moves towards higher frequencies)
+    pub fn pan(&self, scale: FrequencyScale, fraction: f64) -> Self {
+        let (lo, hi) = (scale.axis(self.min_hz), scale.axis(self.max_hz));
+        let shift = (hi - lo) * fraction;
+        Self { min_hz: scale.unaxis(lo + shift), max_hz: scale.unaxis(hi + shift) }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub enum FrequencyScale {
+    Logarithmic,    // Equal space per octave
+    Linear,         // Equal space per Hz: bass collapses into a few bars
+    Hybrid,         // Linear bass up to the knee, logarithmic above
+    Mel,            // Pitch perception scale (speech / MFCC style)
+    Bark,           // Critical band rate (Traunmüller)
+    Erb,            // Equivalent rectangular bandwidth rate (Glasberg & Moore)
+}
+
+impl FrequencyScale {
+    /// All scales, in the order shown in the settings UI
+    pub const ALL: [FrequencyScale; 6] = [
+        FrequencyScale::Logarithmic,
+        FrequencyScale::Linear,
+        FrequencyScale::Hybrid,
+        FrequencyScale::Mel,
+        FrequencyScale::Bark,
+        FrequencyScale::Erb,
+    ];
+
+    /// Human readable name for the settings UI
+    pub fn label(&self) -> &'static str {
+        match self {
+            FrequencyScale::Logarithmic => "Logarithmic",
+            FrequencyScale::Linear => "Linear",
+            FrequencyScale::Hybrid => "Hybrid (Linear Bass)",
+            FrequencyScale::Mel => "Mel",
+            FrequencyScale::Bark => "Bark",
+            FrequencyScale::Erb => "ERB",
+        }
+    }
+
+    /// Centre frequency (Hz) of a bar
+    ///
+    /// Bars are placed at t = (index + 1) / total_bars along the scale, so the last
+    /// bar lands exactly on the upper limit of `range`. `knee_hz` only affects the
+    /// hybrid scale, `freq_res` (Hz per FFT bin) keeps the log section from starting below bin 1
+    pub fn bar_frequency(&self, bar_index: usize, total_bars: usize, range: FrequencyRange, knee_hz: f64, freq_res: f64) -> f64 {
+        let total_bars = total_bars.max(1);
+        let t = (bar_index + 1) as f64 / total_bars as f64;
+        let (min_hz, max_hz) = (range.min_hz, range.max_hz);
+
+        match self {
+            FrequencyScale::Linear => min_hz + t * (max_hz - min_hz),
+            FrequencyScale::Logarithmic => {
+                let min_freq = min_hz.max(freq_res).min(max_hz);
+                min_freq * (max_hz / min_freq).powf(t)
+            }
+            FrequencyScale::Hybrid => {
+                // A knee outside the range leaves nothing to space linearly
+                let knee = knee_hz.min(max_hz / 2.0);
+                if knee <= min_hz {
+                    return FrequencyScale::Logarithmic.bar_frequency(bar_index, total_bars, range, knee_hz, freq_res);
+                }
+                let linear_bar_count = (total_bars as f64 * HYBRID_LINEAR_PROPORTION).round() as usize;
+
+                // 1. Linear region (from the lower limit up to the knee)
+                if bar_index < linear_bar_count {
+                    let t = (bar_index + 1) as f64 / linear_bar_count as f64;
+                    return min_hz + t * (knee - min_hz);
+                }
+
+                // 2. Log region, starting where the linear one left off
+                let log_bar_count = total_bars - linear_bar_count;
+                let log_index = bar_index - linear_bar_count;
+                let t = (log_index + 1) as f64 / log_bar_count as f64;
+                let min_log_freq = knee.max(freq_res);
+
+                min_log_freq * (max_hz / min_log_freq).powf(t)
+            }
+            FrequencyScale::Mel | FrequencyScale::Bark | FrequencyScale::Erb => {
+                let lo = self.warp(min_hz);
+                let hi = self.warp(max_hz);
+                self.unwarp(lo + (hi - lo) * t)
+            }
+        }
+    }
+
+    /// Hz -> scale units (mel, bark, ERB number). Log/linear/hybrid use plain Hz
+    fn warp(&self, hz: f64) -> f64 {
+        match self {
+            FrequencyScale::Mel => 2595.0 * (1.0 + hz / 700.0).log10(),
+            FrequencyScale::Bark => 26.81 * hz / (1960.0 + hz) - 0.53,
+            FrequencyScale::Erb => 21.4 * (1.0 + 0.00437 * hz).log10(),
+            _ => hz,
+        }
+    }
+
+    /// Hz -> position along the display for zooming and panning
+    /// (log Hz for the logarithmic and hybrid scales)
+    fn axis(&self, hz: f64) -> f64 {
+        match self {
+            FrequencyScale::Logarithmic | FrequencyScale::Hybrid => hz.ln(),
+            _ => self.warp(hz),
+        }
+    }
+
+    /// Inverse of `axis`
+    fn unaxis(&self, value: f64) -> f64 {
+        match self {
+            FrequencyScale::Logarithmic | FrequencyScale::Hybrid => value.exp(),
+            _ => self.unwarp(value),
+        }
+    }
+
+    /// Scale units -> Hz (inverse of `warp`)
+    fn unwarp(&self, value: f64) -> f64 {
+        match self {
+            FrequencyScale::Mel => 700.0 * (10f64.powf(value / 2595.0) - 1.0),
+            FrequencyScale::Bark => 1960.0 * (value + 0.53) / (26.28 - value),
+            FrequencyScale::Erb => (10f64.powf(value / 21.4) - 1.0) / 0.00437,
+            _ => value,
+        }
+    }
+}
+
+// =============== Tests ==================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FREQ_RES: f64 = 48000.0 / 2048.0;
+
+    fn layout(scale: FrequencyScale, bars: usize) -> Vec<f64> {
+        (0..bars)
+            .map(|i| scale.bar_frequency(i, bars, FrequencyRange::AUDIBLE, DEFAULT_KNEE_FREQ as f64, FREQ_RES))
+            .collect()
+    }
+
+    #[test]
+    fn test_scales_are_monotonic_and_end_at_max() {
+        for scale in FrequencyScale::ALL {
+            let freqs = layout(scale, 100);
+            assert!(freqs.windows(2).all(|w| w[1] > w[0]), "{:?} is not increasing", scale);
+            assert!((freqs[99] - SCALE_MAX_FREQ).abs() < 1.0, "{:?} ends at {}", scale, freqs[99]);
+        }
+    }
+
+    #[test]
+    fn test_warp_round_trip() {
+        for scale in [FrequencyScale::Mel, FrequencyScale::Bark, FrequencyScale::Erb] {
+            for hz in [20.0, 440.0, 1000.0, 8000.0, 20000.0] {
+                let back = scale.unwarp(scale.warp(hz));
+                assert!((back - hz).abs() < 1e-6 * hz, "{:?}: {} -> {}", scale, hz, back);
+            }
+        }
+    }
+
+    #[test]
+    fn test_known_scale_values() {
+        // 1000 Hz is 1000 mel by construction; ~8.5 bark; ~15.6 ERB
+        assert!((FrequencyScale::Mel.warp(1000.0) - 1000.0).abs() < 1.0);
+        assert!((FrequencyScale::Bark.warp(1000.0) - 8.53).abs() < 0.05);
+        assert!((FrequencyScale::Erb.warp(1000.0) - 15.6).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_hybrid_knee_is_adjustable() {
+        let knee_bar = 14; // Last linear bar for 100 bars at 15%
+        let at = |knee: f64| FrequencyScale::Hybrid.bar_frequency(knee_bar, 100, FrequencyRange::AUDIBLE, knee, FREQ_RES);
+
+        assert!((at(500.0) - 500.0).abs() < 1e-6);
+        assert!((at(1000.0) - 1000.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_perceptual_scales_favour_bass_over_linear() {
+        // Half way across the display, every perceptual scale is still well below
+        // the linear midpoint (10 kHz) and above the pure log midpoint (~630 Hz)
+        let mid = |scale: FrequencyScale| layout(scale, 100)[49];
+
+        for scale in [FrequencyScale::Mel, FrequencyScale::Bark, FrequencyScale::Erb] {
+            assert!(mid(scale) < mid(FrequencyScale::Linear));
+            assert!(mid(scale) > mid(FrequencyScale::Logarithmic));
+        }
+    }
+
+    #[test]
+    fn test_bars_follow_the_range() {
+        // Zoomed into a whine hunt: every scale spans 14 - 18 kHz
+        let range = FrequencyRange { min_hz: 14000.0, max_hz: 18000.0 };
+        for scale in FrequencyScale::ALL {
+            let freqs: Vec<f64> = (0..64)
+                .map(|i| scale.bar_frequency(i, 64, range, DEFAULT_KNEE_FREQ as f64, FREQ_RES))
+                .collect();
+            assert!(freqs.windows(2).all(|w| w[1] > w[0]), "{:?} is not increasing", scale);
+            assert!(freqs[0] > 14000.0 && (freqs[63] - 18000.0).abs() < 1e-6, "{:?} spans {} - {}", scale, freqs[0], freqs[63]);
+        }
+    }
+
+    #[test]
+    fn test_range_limits() {
+        // 96 kHz devices can show ultrasonic content, but not past Nyquist
+        let full = FrequencyRange::full(96000);
+        assert!(full.max_hz > 40000.0 && full.max_hz < 48000.0);
+
+        let wide = FrequencyRange { min_hz: 0.0, max_hz: 90000.0 }.clamped(full);
+        assert_eq!(wide, full);
+
+        let narrow = FrequencyRange { min_hz: 1000.0, max_hz: 1001.0 }.clamped(full);
+        assert!((narrow.max_hz / narrow.min_hz - MIN_RANGE_RATIO).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_zoom_keeps_anchor_and_pan_round_trips() {
+        let range = FrequencyRange::AUDIBLE;
+        for scale in FrequencyScale::ALL {
+            // Zooming in around the middle keeps the middle frequency in the middle
+            let middle = scale.unaxis((scale.axis(range.min_hz) + scale.axis(range.max_hz)) / 2.0);
+            let zoomed = range.zoom(scale, 0.5, 0.5);
+            let zoomed_middle = scale.unaxis((scale.axis(zoomed.min_hz) + scale.axis(zoomed.max_hz)) / 2.0);
+            assert!((zoomed_middle - middle).abs() < 1e-6 * middle, "{:?}", scale);
+            assert!(zoomed.min_hz > range.min_hz && zoomed.max_hz < range.max_hz, "{:?}", scale);
+
+            let back = zoomed.pan(scale, 0.25).pan(scale, -0.25);
+            assert!((back.min_hz - zoomed.min_hz).abs() < 1e-6 * zoomed.min_hz, "{:?}", scale);
+            assert!((back.max_hz - zoomed.max_hz).abs() < 1e-6 * zoomed.max_hz, "{:?}", scale);
+        }
+    }
+}
@@ -12,10 +12,24 @@ use eframe:: egui;
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
-use crate::fft_config::FIXED_FFT_SIZE;
+use crate::ballistics::MeterBallistics;
+use crate::colormap::Colormap;
+use crate::constant_q::CqtResolution;
+use crate::fft_config::{AnalysisMode, AnalysisOverlap, FFTInfo, FFT_SIZE_OPTIONS};
+use crate::fft_processor::BinAggregation;
+use crate::frequency_scale::FrequencyScale;
+use crate::multi_resolution;
+use crate::octave_bands::OctaveFraction;
+use crate::oscilloscope::{TriggerEdge, TriggerMode};
+use crate::peak_detection::PeakInterpolation;
+use crate::pitch::DEFAULT_A4_HZ;
+use crate::reference::{ReferenceSource, ReferenceTrace};
 use crate::media::{PlatformMedia};
-use crate::shared_state::{Color32 as StateColor32, ColorProfile, MediaDisplayMode, SharedState, VisualMode, VisualProfile};
+use crate::shared_state::{Color32 as StateColor32, ColorProfile, MediaDisplayMode, SharedState, StereoMode, VisualMode, VisualProfile};
 use crate::shared_state::ColorRef;
+use crate::vectorscope::PhosphorDecay;
+use crate::weighting::FrequencyWeighting;
+use crate::windowing::WindowFunction;
 
 #[derive(PartialEq, Debug)]
 pub enum SettingsTab {
@@ -31,6 +45,7 @@ pub enum SaveTarget {
     None,
     Visual,
     Color,
+    Reference(ReferenceSource),
 }
 
 // Main Application GUI - handles rendering and user interaction
@@ -133,6 +148,13 @@ impl eframe::App for SpectrumApp {
 
         if let Some(track) = new_track {
             if let Ok(mut state) = self.shared_state.lock() {
+                // A different song starts a fresh key estimate (and, optionally, fresh traces)
+                let track_changed = state.media_info.as_ref()
+                    .is_none_or(|current| current.title != track.title || current.artist != track.artist);
+                if track_changed {
+                    state.key_reset_requested = true;
+                    state.traces_reset_requested |= state.config.traces_reset_on_track_change;
+                }
                 state.media_info = Some(track.clone());
                 state.last_media_update = Some(Instant::now());
             }
@@ -300,6 +322,7 @@ impl eframe::App for SpectrumApp {
                 // Handle Dragging
                 //self.handle_window_drag(ctx, ui, window_rect);
                 widgets::handle_window_interaction(ui, ctx, window_rect,&mut self.settings_open);
+                widgets::handle_frequency_zoom(ui, viz_rect, &self.shared_state);
                 
                 // === Orchestration Setup: Calculate Opacity
                 // Briefly lock to get the config/timepstamps for logic
@@ -339,6 +362,31 @@ impl eframe::App for SpectrumApp {
                         ui.input(|i| i.pointer.hover_pos()),
                     );
 
+                    // A/B reference over the live bars
+                    let active_reference = state.config.active_reference.as_ref()
+                        .and_then(|name| state.user_references.iter().find(|r| &r.name == name));
+                    if let Some(reference) = active_reference {
+                        if viz::overlays_bars(&state.config.profile, viz_data) {
+                            viz::draw_reference(ui.painter(), viz_rect, &colors, &state.config, perf, viz_data, reference);
+                        }
+                    }
+
+                    // Top-right overlays stack downwards
+                    let mut overlay_top = viz_rect.top();
+                    if state.config.show_loudness {
+                        overlay_top = viz::draw_loudness_overlay(ui.painter(), viz_rect, &colors, &state.loudness).bottom();
+                    }
+                    if state.config.show_peak_list && state.config.profile.visual_mode.is_spectral() {
+                        viz::draw_peak_list(ui.painter(), viz_rect, overlay_top, &colors, &state.config, viz_data);
+                    }
+
+                    // Detected key sits under the now-playing overlay (or in its place without one)
+                    if let (true, Some(key)) = (state.config.show_key, viz_data.key.as_ref()) {
+                        let media_shown = self.media_opacity > 0.01 && media_info.is_some();
+                        let top = viz_rect.top() + 20.0 + if media_shown { viz::MEDIA_OVERLAY_HEIGHT } else { 0.0 };
+                        viz::draw_key_badge(ui.painter(), viz_rect, top, &colors, key);
+                    }
+
                     // Sonar Ping Effect
                     if flash_strength > 0.0 {
                         viz::draw_sonar_ping(ui, ui.max_rect().shrink(5.0), flash_strength, &colors);
@@ -625,19 +673,142 @@ impl SpectrumApp {
                             ui.selectable_value(&mut state.config.profile.visual_mode, VisualMode::SegmentedBars, "Segmented (LED)");
                             ui.selectable_value(&mut state.config.profile.visual_mode, VisualMode::LineSpectrum, "Line Spectrum");
                             ui.selectable_value(&mut state.config.profile.visual_mode, VisualMode::Oscilloscope, "Oscilloscope");
+                            ui.selectable_value(&mut state.config.profile.visual_mode, VisualMode::Spectrogram, "Spectrogram");
+                            ui.selectable_value(&mut state.config.profile.visual_mode, VisualMode::LevelMeter, "Level Meter");
+                            ui.selectable_value(&mut state.config.profile.visual_mode, VisualMode::Vectorscope, "Vectorscope");
+                            ui.selectable_value(&mut state.config.profile.visual_mode, VisualMode::XyScope, "XY Scope");
+                            ui.selectable_value(&mut state.config.profile.visual_mode, VisualMode::Chroma, "Chroma / Key");
                         });
                     ui.end_row();
                     
                     // Specific Controls
-                    if state.config.profile.visual_mode != VisualMode::Oscilloscope {
-                        ui.label("Bar Count");
-                        ui.add(egui::Slider::new(&mut state.config.profile.num_bars, 10..=512)
-                            .step_by(1.0).drag_value_speed(1.0).smart_aim(false));
+                    if state.config.profile.visual_mode.is_spectral() {
+                        ui.label("Analysis");
+                        egui::ComboBox::from_id_salt("analysis_mode_combo")
+                            .selected_text(state.config.profile.analysis_mode.label())
+                            .show_ui(ui, |ui| {
+                                for mode in AnalysisMode::ALL {
+                                    ui.selectable_value(&mut state.config.profile.analysis_mode, mode, mode.label());
+                                }
+                            });
+                        ui.end_row();
+
+                        if state.config.profile.analysis_mode == AnalysisMode::OctaveBands {
+                            ui.label("Band Width");
+                            egui::ComboBox::from_id_salt("octave_fraction_combo")
+                                .selected_text(state.config.profile.octave_fraction.label())
+                                .show_ui(ui, |ui| {
+                                    for fraction in OctaveFraction::ALL {
+                                        ui.selectable_value(&mut state.config.profile.octave_fraction, fraction, fraction.label());
+                                    }
+                                })
+                                .response
+                                .on_hover_text("Narrow low bands need a large FFT size (8192+) to be resolved");
+                            ui.end_row();
+                        } else if state.config.profile.analysis_mode == AnalysisMode::ConstantQ {
+                            ui.label("Resolution");
+                            egui::ComboBox::from_id_salt("cqt_resolution_combo")
+                                .selected_text(state.config.profile.cqt_resolution.label())
+                                .show_ui(ui, |ui| {
+                                    for resolution in CqtResolution::ALL {
+                                        ui.selectable_value(&mut state.config.profile.cqt_resolution, resolution, resolution.label());
+                                    }
+                                })
+                                .response
+                                .on_hover_text("Bins per octave, from C1 upwards. Bass bins use windows up to 32768 samples long");
+                            ui.end_row();
+                        } else {
+                            if state.config.profile.analysis_mode == AnalysisMode::MultiResolution {
+                                let sample_rate = match state.performance.fft_info.sample_rate {
+                                    0 => 48000,
+                                    rate => rate,
+                                };
+                                let stages: Vec<String> = multi_resolution::STAGES
+                                    .iter()
+                                    .map(|stage| stage.fft_size_at(sample_rate).to_string())
+                                    .collect();
+                                ui.label("Stages");
+                                ui.label(stages.join(" / "))
+                                    .on_hover_text("FFT sizes for bass (< 250 Hz), mids (< 4 kHz) and treble. \
+                                        The FFT Size setting still drives the update rate and the peak readout");
+                                ui.end_row();
+                            }
+
+                            ui.label("Bar Count");
+                            ui.add(egui::Slider::new(&mut state.config.profile.num_bars, 10..=512)
+                                .step_by(1.0).drag_value_speed(1.0).smart_aim(false));
+                            ui.end_row();
+
+                            ui.label("Frequency Scale");
+                            egui::ComboBox::from_id_salt("freq_scale_combo")
+                                .selected_text(state.config.profile.frequency_scale.label())
+                                .show_ui(ui, |ui| {
+                                    for scale in FrequencyScale::ALL {
+                                        ui.selectable_value(&mut state.config.profile.frequency_scale, scale, scale.label());
+                                    }
+                                });
+                            ui.end_row();
+
+                            if state.config.profile.frequency_scale == FrequencyScale::Hybrid {
+                                ui.label("Linear Knee");
+                                ui.add(egui::Slider::new(&mut state.config.profile.scale_knee_hz, 100.0..=2000.0)
+                                    .suffix(" Hz").logarithmic(true))
+                                    .on_hover_text("Frequencies below the knee are spaced linearly, above it logarithmically");
+                                ui.end_row();
+                            }
+                        }
+
+                        let nyquist = match state.performance.fft_info.sample_rate {
+                            0 => 24000.0,
+                            rate => rate as f32 / 2.0,
+                        };
+                        ui.label("Frequency Range");
+                        let range = ui.horizontal(|ui| {
+                            let profile = &mut state.config.profile;
+                            let min = ui.add(egui::DragValue::new(&mut profile.min_freq_hz)
+                                .range(1.0..=profile.max_freq_hz / 2.0).speed(1.0).suffix(" Hz"));
+                            ui.label("to");
+                            let max = ui.add(egui::DragValue::new(&mut profile.max_freq_hz)
+                                .range(profile.min_freq_hz * 2.0..=96000.0).speed(50.0).suffix(" Hz"));
+                            min.changed() || max.changed()
+                        });
+                        range.response.on_hover_text(format!(
+                            "Left and right edge of the display. Limited to Nyquist ({:.1} kHz on this device)",
+                            nyquist / 1000.0
+                        ));
+                        // Every bar moves, like zooming: the traces belong to the old frequencies
+                        if range.inner {
+                            state.traces_reset_requested = true;
+                        }
                         ui.end_row();
 
+                        if state.config.profile.frequency_zoom.is_some() {
+                            ui.label("Zoom");
+                            if ui.button("Reset Zoom")
+                                .on_hover_text("Mouse wheel zooms, Shift + drag pans, Shift + double-click resets")
+                                .clicked()
+                            {
+                                state.config.profile.frequency_zoom = None;
+                                state.traces_reset_requested = true;
+                            }
+                            ui.end_row();
+                        }
+
                         ui.label("Bar Gap");
                         ui.add(egui::Slider::new(&mut state.config.profile.bar_gap_px, 0..=10).suffix(" px"));
                         ui.end_row();
+
+                        ui.label("Stereo");
+                        egui::ComboBox::from_id_salt("stereo_mode_combo")
+                            .selected_text(state.config.profile.stereo_mode.label())
+                            .show_ui(ui, |ui| {
+                                for mode in StereoMode::ALL {
+                                    ui.selectable_value(&mut state.config.profile.stereo_mode, mode, mode.label());
+                                }
+                            })
+                            .response
+                            .on_hover_text("Per-channel FFTs. Content that is loud in Side but missing from Mid is out of phase");
+                        ui.end_row();
                     }
                     
                     ui.label("Bar Opacity");
@@ -677,6 +848,132 @@ impl SpectrumApp {
                     });
                     ui.end_row();
 
+                    if state.config.profile.visual_mode == VisualMode::Spectrogram {
+                        ui.label("History");
+                        ui.add(egui::Slider::new(&mut state.config.profile.spectrogram_history_secs, 2.0..=60.0)
+                            .suffix(" s").logarithmic(true));
+                        ui.end_row();
+
+                        ui.label("Colour Map");
+                        egui::ComboBox::from_id_salt("colormap_combo")
+                            .selected_text(state.config.profile.spectrogram_colormap.label())
+                            .show_ui(ui, |ui| {
+                                for colormap in Colormap::ALL {
+                                    ui.selectable_value(&mut state.config.profile.spectrogram_colormap, colormap, colormap.label());
+                                }
+                            });
+                        ui.end_row();
+                    }
+
+                    if state.config.profile.visual_mode == VisualMode::Vectorscope {
+                        ui.label("Persistence");
+                        ui.add(egui::Slider::new(&mut state.config.profile.vectorscope_persistence_secs, 0.05..=5.0)
+                            .suffix(" s").logarithmic(true))
+                            .on_hover_text("How long the trace takes to fade");
+                        ui.end_row();
+                    }
+
+                    if state.config.profile.visual_mode == VisualMode::XyScope {
+                        // Channels the metering thread has seen (stereo until the first packet)
+                        let channels = state.visualization.levels.len().max(2);
+                        let profile = &mut state.config.profile;
+                        ui.label("Channels");
+                        ui.horizontal(|ui| {
+                            for (axis, channel) in [("X", &mut profile.xy_x_channel), ("Y", &mut profile.xy_y_channel)] {
+                                egui::ComboBox::from_id_salt(format!("xy_{}_channel_combo", axis))
+                                    .selected_text(format!("{}: Ch {}", axis, *channel + 1))
+                                    .show_ui(ui, |ui| {
+                                        for index in 0..channels {
+                                            ui.selectable_value(channel, index, format!("Ch {}", index + 1));
+                                        }
+                                    });
+                            }
+                        });
+                        ui.end_row();
+
+                        ui.label("Persistence");
+                        ui.add(egui::Slider::new(&mut profile.xy_persistence_secs, 0.01..=5.0)
+                            .suffix(" s").logarithmic(true))
+                            .on_hover_text("How long the trace takes to fade");
+                        ui.end_row();
+
+                        ui.label("Decay");
+                        egui::ComboBox::from_id_salt("xy_decay_combo")
+                            .selected_text(profile.xy_decay.label())
+                            .show_ui(ui, |ui| {
+                                for decay in PhosphorDecay::ALL {
+                                    ui.selectable_value(&mut profile.xy_decay, decay, decay.label());
+                                }
+                            })
+                            .response
+                            .on_hover_text("Exponential leaves a long faint tail like a CRT; Linear clears every trace after the persistence time");
+                        ui.end_row();
+                    }
+
+                    if state.config.profile.visual_mode == VisualMode::Oscilloscope {
+                        let profile = &mut state.config.profile;
+                        ui.label("Timebase");
+                        ui.add(egui::Slider::new(&mut profile.scope_ms_per_div, 0.1..=1000.0)
+                            .suffix(" ms/div").logarithmic(true))
+                            .on_hover_text("Time per horizontal division (10 divisions across). \
+                                Long sweeps are drawn as a min/max envelope, so short peaks still show");
+                        ui.end_row();
+
+                        ui.label("Trigger");
+                        ui.horizontal(|ui| {
+                            egui::ComboBox::from_id_salt("scope_trigger_mode_combo")
+                                .selected_text(profile.scope_trigger_mode.label())
+                                .show_ui(ui, |ui| {
+                                    for mode in TriggerMode::ALL {
+                                        ui.selectable_value(&mut profile.scope_trigger_mode, mode, mode.label());
+                                    }
+                                })
+                                .response
+                                .on_hover_text("Auto free-runs without a trigger, Normal waits, Single captures one sweep");
+                            egui::ComboBox::from_id_salt("scope_trigger_edge_combo")
+                                .selected_text(profile.scope_trigger_edge.label())
+                                .show_ui(ui, |ui| {
+                                    for edge in TriggerEdge::ALL {
+                                        ui.selectable_value(&mut profile.scope_trigger_edge, edge, edge.label());
+                                    }
+                                });
+                            if profile.scope_trigger_mode == TriggerMode::Single && ui.button("Arm").clicked() {
+                                state.scope_rearm_requested = true;
+                            }
+                        });
+                        ui.end_row();
+
+                        let profile = &mut state.config.profile;
+                        ui.label("Trigger Level");
+                        ui.add(egui::Slider::new(&mut profile.scope_trigger_level, -1.0..=1.0).fixed_decimals(2));
+                        ui.end_row();
+
+                        ui.label("Holdoff");
+                        ui.add(egui::Slider::new(&mut profile.scope_holdoff_ms, 0.0..=500.0).suffix(" ms"))
+                            .on_hover_text("Ignore triggers for this long after each sweep (steadies complex waveforms)");
+                        ui.end_row();
+                    }
+
+                    if state.config.profile.visual_mode == VisualMode::LevelMeter {
+                        ui.label("Ballistics");
+                        egui::ComboBox::from_id_salt("meter_ballistics_combo")
+                            .selected_text(state.config.meter_ballistics.label())
+                            .show_ui(ui, |ui| {
+                                for ballistics in MeterBallistics::ALL {
+                                    ui.selectable_value(&mut state.config.meter_ballistics, ballistics, ballistics.label());
+                                }
+                            })
+                            .response
+                            .on_hover_text("VU averages the RMS level; PPM and Digital follow the sample peak");
+                        ui.end_row();
+
+                        ui.label("Peak Hold / Clip");
+                        if ui.button("↺ Reset").on_hover_text("Clear the peak holds and clip indicators").clicked() {
+                            state.levels_reset_requested = true;
+                        }
+                        ui.end_row();
+                    }
+
                     if state.config.profile.visual_mode == VisualMode::SegmentedBars {
                         ui.label("Segment Height");
                         ui.add(egui::Slider::new(&mut state.config.profile.segment_height_px, 1.0..=20.0).suffix(" px"));
@@ -687,7 +984,7 @@ impl SpectrumApp {
                         ui.end_row();
                     }
 
-                    if state.config.profile.visual_mode != VisualMode::Oscilloscope {
+                    if state.config.profile.visual_mode.is_spectral() {
                         ui.label("Peak Indicators");
                         ui.horizontal(|ui| {
                             ui.checkbox(&mut state.config.profile.show_peaks, "Show");
@@ -696,8 +993,28 @@ impl SpectrumApp {
                             }
                         });
                         ui.end_row();
+
+                        ui.label("Traces");
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut state.config.show_max_hold, "Max").on_hover_text("Loudest level of each band since the last reset");
+                            ui.checkbox(&mut state.config.show_min_hold, "Min").on_hover_text("Quietest level of each band since the last reset");
+                            ui.checkbox(&mut state.config.show_ltas, "LTAS").on_hover_text("Long-term average spectrum since the last reset");
+                            let frames = state.visualization.traces.frames();
+                            if ui.button("↺ Reset").on_hover_text(format!("{} frames accumulated", frames)).clicked() {
+                                state.traces_reset_requested = true;
+                            }
+                        });
+                        ui.end_row();
+
+                        ui.label("");
+                        ui.checkbox(&mut state.config.traces_reset_on_track_change, "Reset traces on track change");
+                        ui.end_row();
                     }
 
+                    ui.label("Beat Flash");
+                    ui.checkbox(&mut state.config.profile.beat_flash, "Pulse background on beats");
+                    ui.end_row();
+
                     ui.label("Font Style");
                     egui::ComboBox::from_id_salt("font_combo")
                         .selected_text(format!("{:?}", state.config.profile.overlay_font))
@@ -713,14 +1030,78 @@ impl SpectrumApp {
             ui.group(|ui| {
                 ui.label("Aggregation Mode:");
                 ui.horizontal(|ui| {
-                    ui.radio_value(&mut state.config.profile.use_peak_aggregation, true, "Peak (Dramatic)");
-                    ui.radio_value(&mut state.config.profile.use_peak_aggregation, false, "Average (Smooth)");
+                    for aggregation in BinAggregation::ALL {
+                        ui.radio_value(&mut state.config.profile.aggregation, aggregation, aggregation.label());
+                    }
                 });
 
                 ui.add_space(5.0);
                 ui.label("Orientation:");
                 ui.checkbox(&mut state.config.profile.inverted_spectrum, "Inverted (Top-Down)");
             });
+
+            ui.add_space(10.0);
+            ui.group(|ui| {
+                ui.label("Reference (A/B):");
+                ui.horizontal(|ui| {
+                    egui::ComboBox::from_id_salt("reference_combo")
+                        .selected_text(state.config.active_reference.as_deref().unwrap_or("None"))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut state.config.active_reference, None, "None");
+                            let references = state.user_references.clone();
+                            for reference in &references {
+                                ui.horizontal(|ui| {
+                                    ui.selectable_value(&mut state.config.active_reference, Some(reference.name.clone()), &reference.name);
+                                    if ui.small_button("🗑").clicked() {
+                                        let _ = crate::shared_state::AppConfig::delete_user_reference(&reference.name);
+                                        state.user_references.retain(|r| r.name != reference.name);
+                                        if state.config.active_reference.as_ref() == Some(&reference.name) {
+                                            state.config.active_reference = None;
+                                        }
+                                    }
+                                });
+                            }
+                        });
+
+                    for source in ReferenceSource::ALL {
+                        // The average is empty until the traces have seen a frame
+                        let available = source != ReferenceSource::Average || state.visualization.traces.frames() > 0;
+                        if ui.add_enabled(available, egui::Button::new("📷"))
+                            .on_hover_text(format!("Freeze the {} as a reference", source.label()))
+                            .on_disabled_hover_text("Nothing accumulated since the last trace reset")
+                            .clicked()
+                        {
+                            self.save_target = SaveTarget::Reference(source);
+                            self.new_preset_name = format!("Reference {}", state.user_references.len() + 1);
+                        }
+                    }
+                });
+
+                if let SaveTarget::Reference(source) = self.save_target {
+                    ui.small(format!("Capture: {}", source.label()));
+                    widgets::ui_save_popup(ui, &mut self.new_preset_name, |name| {
+                        let levels = match source {
+                            ReferenceSource::Live => state.visualization.bars.clone(),
+                            ReferenceSource::Average => state.visualization.traces.average().to_vec(),
+                        };
+                        let centers = viz::bar_center_frequencies(&state.config.profile, &state.performance, levels.len());
+                        let reference = ReferenceTrace::capture(&name, &centers, &levels);
+
+                        // The traces may have been reset while the name was being typed
+                        if reference.frequencies_hz.is_empty() {
+                            eprintln!("Not saving reference '{}': nothing to capture", reference.name);
+                        } else if let Err(e) = crate::shared_state::AppConfig::save_user_reference(&reference) {
+                            eprintln!("Error saving reference: {}", e);
+                        } else {
+                            state.user_references.retain(|r| r.name != reference.name);
+                            state.config.active_reference = Some(reference.name.clone());
+                            state.user_references.push(reference);
+                        }
+                    }, &mut self.save_target);
+                }
+
+                ui.checkbox(&mut state.config.show_reference_difference, "Show difference (live - reference)");
+            });
     }
 
     fn settings_tab_audio(&mut self, ui: &mut egui::Ui, state: &mut SharedState) {
@@ -735,7 +1116,84 @@ impl SpectrumApp {
                 .striped(true)
                 .show(ui, |ui| {
                     ui.label("FFT Window Size");
-                    ui.label(format!("{} samples (fixed)", FIXED_FFT_SIZE));
+                    egui::ComboBox::from_id_salt("fft_size_combo")
+                        .selected_text(format!("{} samples", state.config.profile.fft_size))
+                        .show_ui(ui, |ui| {
+                            for size in FFT_SIZE_OPTIONS {
+                                ui.selectable_value(&mut state.config.profile.fft_size, size, format!("{} samples", size));
+                            }
+                        });
+                    ui.end_row();
+
+                    // Live preview of the trade-off at the current device rate
+                    // (falls back to 48 kHz until the first packet arrives)
+                    let sample_rate = match state.performance.fft_info.sample_rate {
+                        0 => 48000,
+                        rate => rate,
+                    };
+                    let preview = FFTInfo::new(sample_rate, state.config.profile.fft_size);
+                    ui.label("Latency / Resolution");
+                    ui.label(format!("{:.1} ms | {:.2} Hz / bin", preview.latency_ms, preview.frequency_resolution));
+                    ui.end_row();
+
+                    ui.label("Frame Overlap");
+                    egui::ComboBox::from_id_salt("overlap_combo")
+                        .selected_text(state.config.profile.overlap.label())
+                        .show_ui(ui, |ui| {
+                            for overlap in AnalysisOverlap::ALL {
+                                ui.selectable_value(&mut state.config.profile.overlap, overlap, overlap.label());
+                            }
+                        });
+                    ui.end_row();
+
+                    let hop = state.config.profile.overlap.hop_size(state.config.profile.fft_size);
+                    ui.label("Update Rate");
+                    ui.label(format!("{:.0} frames/s (hop {} samples)", sample_rate as f32 / hop as f32, hop));
+                    ui.end_row();
+
+                    ui.label("Window Function");
+                    egui::ComboBox::from_id_salt("window_fn_combo")
+                        .selected_text(state.config.profile.window_function.label())
+                        .show_ui(ui, |ui| {
+                            for window_fn in WindowFunction::ALL {
+                                ui.selectable_value(&mut state.config.profile.window_function, window_fn, window_fn.label());
+                            }
+                        })
+                        .response
+                        .on_hover_text(
+                            "Hann: general purpose\n\
+                            Blackman-Harris: lowest leakage, for narrow whines near loud content\n\
+                            Flat-Top: accurate tone amplitudes\n\
+                            Kaiser: adjustable leakage/resolution trade-off\n\
+                            Rectangular: no window, sharpest bins, most leakage"
+                        );
+                    ui.end_row();
+
+                    if state.config.profile.window_function == WindowFunction::Kaiser {
+                        ui.label("Kaiser β");
+                        ui.add(egui::Slider::new(&mut state.config.profile.kaiser_beta, 0.0..=20.0).step_by(0.1));
+                        ui.end_row();
+                    }
+
+                    let enbw = state.config.profile.window_function.enbw_bins(state.config.profile.kaiser_beta);
+                    ui.label("Noise Bandwidth");
+                    ui.label(format!("{:.2} bins ({:.1} Hz)", enbw, enbw * preview.frequency_resolution));
+                    ui.end_row();
+
+                    ui.label("Weighting");
+                    egui::ComboBox::from_id_salt("weighting_combo")
+                        .selected_text(state.config.profile.weighting.label())
+                        .show_ui(ui, |ui| {
+                            for weighting in FrequencyWeighting::ALL {
+                                ui.selectable_value(&mut state.config.profile.weighting, weighting, weighting.label());
+                            }
+                        });
+                    ui.end_row();
+
+                    ui.label("Spectral Tilt");
+                    ui.add(egui::Slider::new(&mut state.config.profile.tilt_db_per_octave, -6.0..=6.0)
+                        .step_by(0.5).suffix(" dB/oct"))
+                        .on_hover_text("Pivots around 1 kHz. +3 dB/oct makes pink noise (and most music) look flat");
                     ui.end_row();
 
                     ui.label("Sensitivity");
@@ -746,8 +1204,35 @@ impl SpectrumApp {
                     ui.end_row();
 
                     ui.label("Noise Floor");
-                    ui.add(egui::Slider::new(&mut state.config.noise_floor_db, -120.0..=-20.0).suffix(" dB"));
+                    ui.add(egui::Slider::new(&mut state.config.noise_floor_db, -120.0..=-20.0).suffix(" dB"))
+                        .on_hover_text("Bottom of the display (level meters always use it, even with Auto Gain)");
+                    ui.end_row();
+
+                    ui.label("Auto Gain");
+                    ui.checkbox(&mut state.config.auto_gain, "Follow programme level")
+                        .on_hover_text("Slowly moves the floor and ceiling so quiet material fills the window");
                     ui.end_row();
+
+                    if state.config.auto_gain {
+                        ui.label("Adapt Speed");
+                        ui.add(egui::Slider::new(&mut state.config.auto_gain_speed_secs, 0.5..=30.0)
+                            .logarithmic(true).suffix(" s"));
+                        ui.end_row();
+
+                        ui.label("Lowest Floor");
+                        ui.add(egui::Slider::new(&mut state.config.auto_gain_lowest_floor_db, -140.0..=-40.0).suffix(" dB"));
+                        ui.end_row();
+
+                        ui.label("Highest Ceiling");
+                        ui.add(egui::Slider::new(&mut state.config.auto_gain_highest_ceiling_db, -12.0..=40.0).suffix(" dB"));
+                        ui.end_row();
+
+                        if let Some(range) = state.visualization.auto_range {
+                            ui.label("Range");
+                            ui.label(format!("{:.0} to {:+.0} dB", range.floor_db, range.ceiling_db));
+                            ui.end_row();
+                        }
+                    }
                 });
         });
 
@@ -985,7 +1470,43 @@ impl SpectrumApp {
                     ui.end_row();
 
                     ui.label("Inspector Tool");
-                    ui.checkbox(&mut state.config.inspector_enabled, "Enabled").on_hover_text("Show frequency and dB on mouse hover");
+                    ui.checkbox(&mut state.config.inspector_enabled, "Enabled").on_hover_text("Show frequency, note and dB on mouse hover");
+                    ui.end_row();
+
+                    ui.label("Tuning (A4)");
+                    ui.horizontal(|ui| {
+                        ui.add(egui::DragValue::new(&mut state.config.a4_reference_hz)
+                            .range(400.0..=480.0).speed(0.1).suffix(" Hz"))
+                            .on_hover_text("Reference pitch for note names and cents");
+                        if state.config.a4_reference_hz != DEFAULT_A4_HZ && ui.button("↺").on_hover_text("Reset to 440 Hz").clicked() {
+                            state.config.a4_reference_hz = DEFAULT_A4_HZ;
+                        }
+                    });
+                    ui.end_row();
+
+                    ui.label("Peak Interpolation");
+                    egui::ComboBox::from_id_salt("peak_interpolation_combo")
+                        .selected_text(state.config.peak_interpolation.label())
+                        .show_ui(ui, |ui| {
+                            for method in PeakInterpolation::ALL {
+                                ui.selectable_value(&mut state.config.peak_interpolation, method, method.label());
+                            }
+                        })
+                        .response
+                        .on_hover_text("Sub-bin estimate of the true peak frequency and level");
+                    ui.end_row();
+
+                    ui.label("Peak List");
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut state.config.show_peak_list, "Show");
+                        if state.config.show_peak_list {
+                            ui.add(egui::Slider::new(&mut state.config.peak_list_count, 1..=10).text("peaks"));
+                        }
+                    });
+                    ui.end_row();
+
+                    ui.label("Piano Axis");
+                    ui.checkbox(&mut state.config.show_piano_axis, "Show").on_hover_text("Keyboard along the bottom of the spectrum");
                     ui.end_row();
 
                     // Media Settings
@@ -998,6 +1519,15 @@ impl SpectrumApp {
                             ui.selectable_value(&mut state.config.media_display_mode, MediaDisplayMode::Off, "Off");
                         });
                     ui.end_row();
+
+                    ui.label("Musical Key");
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut state.config.show_key, "Show").on_hover_text("Key estimate with Camelot code, next to Now Playing");
+                        if ui.button("↺ Reset").on_hover_text("Start a fresh estimate (also happens on track change)").clicked() {
+                            state.key_reset_requested = true;
+                        }
+                    });
+                    ui.end_row();
                 });
         });
     }
@@ -1008,7 +1538,7 @@ impl SpectrumApp {
         ui.group(|ui| {
             ui.heading("Performance Monitoring");
             ui.checkbox(&mut state.config.show_stats, "Show Performance Overlay");
-            ui.small("Displays FPS, FFT latency, and processing times.");
+            ui.small("Displays FPS, FFT latency, processing times and tempo.");
             
             ui.add_space(10.0);
             ui.heading("Diagnostics");
@@ -1024,7 +1554,7 @@ impl SpectrumApp {
                     ui.end_row();
 
                     ui.label("FFT Size");
-                    ui.label(format!("{} samples (fixed)", info.fft_size));
+                    ui.label(format!("{} samples", info.fft_size));
                     ui.end_row();
 
                     ui.label("Frequency Resolution");
@@ -1038,7 +1568,54 @@ impl SpectrumApp {
                     ui.label("GUI Frame Rate");
                     ui.label(format!("{:.1} FPS", state.performance.gui_fps));
                     ui.end_row();
+
+                    let tempo = &state.visualization.tempo;
+                    ui.label("Tempo");
+                    ui.label(match tempo.bpm {
+                        Some(bpm) => format!("{:.1} BPM ({} beats)", bpm, tempo.beat_count),
+                        None => "--".to_string(),
+                    });
+                    ui.end_row();
+                });
+        });
+
+        ui.add_space(10.0);
+        ui.group(|ui| {
+            ui.heading("Loudness (EBU R128)");
+            ui.checkbox(&mut state.config.show_loudness, "Show Loudness Overlay");
+            ui.small("ITU-R BS.1770 K-weighted loudness of the full input stream.");
+
+            ui.add_space(10.0);
+            let loudness = state.loudness;
+            egui::Grid::new("loudness_grid")
+                .num_columns(2)
+                .spacing([20.0, 10.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label("Momentary (400 ms)");
+                    ui.label(viz::format_level(loudness.momentary_lufs, "LUFS"));
+                    ui.end_row();
+
+                    ui.label("Short-term (3 s)");
+                    ui.label(viz::format_level(loudness.short_term_lufs, "LUFS"));
+                    ui.end_row();
+
+                    ui.label("Integrated");
+                    ui.label(viz::format_level(loudness.integrated_lufs, "LUFS"));
+                    ui.end_row();
+
+                    ui.label("Loudness Range");
+                    ui.label(format!("{:.1} LU", loudness.loudness_range_lu));
+                    ui.end_row();
+
+                    ui.label("True Peak (4x)");
+                    ui.label(viz::format_level(loudness.true_peak_dbtp, "dBTP"));
+                    ui.end_row();
                 });
+
+            if ui.button("↺ Reset Measurement").on_hover_text("Restart integrated loudness, LRA and true peak").clicked() {
+                state.loudness_reset_requested = true;
+            }
         });
     }
 }
@@ -1,6 +1,7 @@
 use egui::{Color32, FontId, FontFamily};
 use crate::shared_state::{Color32 as SharedColor, ThemeFont};
 use crate::gui::StateColor32;
+use crate::auto_range::DbRange;
 
 // === BeOS / Haiku Design Tokens ====
 pub const BEOS_YELLOW: Color32 = Color32::from_rgb(255, 203, 0);
@@ -22,9 +23,9 @@ pub fn to_egui_color(color: SharedColor) -> egui::Color32 {
 
 // == Helper Functions ==
 
-pub fn db_to_px(db: f32, noise_floor: f32, max_height: f32) -> f32 {
-    let range = (0.0 - noise_floor).max(1.0);
-    let normalized = ((db - noise_floor) / range).clamp(0.0, 1.0);
+pub fn db_to_px(db: f32, range: DbRange, max_height: f32) -> f32 {
+    let span = range.span_db().max(1.0);
+    let normalized = ((db - range.floor_db) / span).clamp(0.0, 1.0);
     normalized * max_height
 }
 
@@ -1,10 +1,30 @@
 use egui::{Painter, Rect, Stroke};
 use crate::media::MediaController;
-use crate::shared_state::{AppConfig, ColorProfile, PerformanceStats, VisualMode, 
+use crate::shared_state::{SILENCE_DB, AppConfig, ColorProfile, PerformanceStats, StereoMode, VisualMode, 
     VisualProfile, VisualizationData, MediaDisplayMode};
 use crate::gui::theme::{to_egui_color, db_to_px, lerp_color};
 use crate::gui::widgets::draw_transport_controls;
+use crate::auto_range::DbRange;
+use crate::chroma::MusicalKey;
+use crate::constant_q;
+use crate::fft_config::AnalysisMode;
 use crate::fft_processor::FFTProcessor;
+use crate::level_meter::ChannelLevel;
+use crate::loudness::LoudnessReadings;
+use crate::octave_bands::{self, OctaveBand};
+use crate::oscilloscope::{ScopeTrace, SCOPE_DIVISIONS};
+use crate::peak_detection;
+use crate::pitch::{self, Note, PIANO_HIGHEST_MIDI, PIANO_LOWEST_MIDI};
+use crate::reference::{self, ReferenceTrace, DIFFERENCE_RANGE_DB};
+use crate::spectrogram::SPECTROGRAM_ROWS;
+use crate::spectrum_traces::SpectrumTraces;
+use crate::tempo::TempoReadings;
+use crate::vectorscope::Phosphor;
+
+/// Height of the optional piano keyboard strip
+const PIANO_AXIS_HEIGHT: f32 = 16.0;
+/// Space reserved for the now-playing overlay (top-right)
+pub const MEDIA_OVERLAY_HEIGHT: f32 = 100.0;
 
 pub fn draw_main_visualizer(
     painter: &Painter,
@@ -17,9 +37,10 @@ pub fn draw_main_visualizer(
 ){
     let profile = &config.profile;
     let num_bars = data.bars.len();
+    let range = display_range(config, data);
 
     // Early exit if no data (unless in Oscope mode)
-    if num_bars == 0 && profile.visual_mode != VisualMode::Oscilloscope {
+    if num_bars == 0 && profile.visual_mode.is_spectral() {
         // Draw "Waiting..." in the center
         let text_color = to_egui_color(colors.text).linear_multiply(0.5);
         painter.text(
@@ -41,6 +62,14 @@ pub fn draw_main_visualizer(
     let bg_plate = to_egui_color(colors.background).linear_multiply(0.5);
     painter.rect_filled(rect, 4.0, bg_plate);
 
+    // Beat pulse: a short wash of the highlight color on every detected beat
+    if profile.beat_flash {
+        let pulse = data.tempo.pulse();
+        if pulse > 0.0 {
+            painter.rect_filled(rect, 4.0, to_egui_color(colors.high).linear_multiply(0.25 * pulse));
+        }
+    }
+
     // 1. Calculate Common Layout Helpers
     // Ensure we don't divide by zero even if bars are missing
     let bar_slot_width = rect.width() / num_bars.max(1) as f32;
@@ -48,7 +77,8 @@ pub fn draw_main_visualizer(
 
     // 2. Handle mouse interactions (for frequency modes)
     // Calculate hovered index using the passed-in mouse_pos
-    let hovered_bar_index = if config.inspector_enabled && profile.visual_mode != VisualMode::Oscilloscope {
+    let frequency_mode = profile.visual_mode.is_spectral();
+    let hovered_bar_index = if config.inspector_enabled && frequency_mode {
         mouse_pos.and_then(|pos| {
             if rect.contains(pos) {
                 let relative_x = pos.x - rect.left();
@@ -59,7 +89,32 @@ pub fn draw_main_visualizer(
     } else { None };
 
     // 3. Dispatch Drawing Strategy
-    match profile.visual_mode {
+    // Stereo modes replace the bar styles once both channels are published
+    let stereo = profile.stereo_mode != StereoMode::Mono
+        && frequency_mode
+        && profile.visual_mode != VisualMode::Spectrogram
+        && data.channel_bars.len() == 2;
+
+    if stereo && profile.stereo_mode == StereoMode::Mirrored {
+        draw_mirrored_bars(
+            painter,
+            rect,
+            profile,
+            colors,
+            data,
+            bar_slot_width,
+            range);
+    } else if stereo {
+        draw_stereo_overlay(
+            painter,
+            rect,
+            profile,
+            colors,
+            data,
+            hovered_bar_index,
+            range);
+    } else {
+        match profile.visual_mode {
             VisualMode::SolidBars => {
                 draw_solid_bars(
                     &painter,
@@ -70,7 +125,7 @@ pub fn draw_main_visualizer(
                     bar_width,
                     bar_slot_width,
                     hovered_bar_index,
-                    config.noise_floor_db);
+                    range);
             },
             VisualMode::SegmentedBars => {
                 draw_segmented_bars(
@@ -82,7 +137,7 @@ pub fn draw_main_visualizer(
                     bar_width,
                     bar_slot_width,
                     hovered_bar_index,
-                    config.noise_floor_db);
+                    range);
             },
             VisualMode::LineSpectrum => {
                 draw_line_spectrum(
@@ -92,7 +147,7 @@ pub fn draw_main_visualizer(
                     &colors,
                     data,
                     hovered_bar_index,
-                    config.noise_floor_db);
+                    range);
             },
             VisualMode::Oscilloscope => {
                 draw_oscilloscope(
@@ -103,19 +158,95 @@ pub fn draw_main_visualizer(
                     data,
                 );
             },
+            VisualMode::Spectrogram => {
+                draw_spectrogram(
+                    painter,
+                    rect,
+                    profile,
+                    colors,
+                    data,
+                    range);
+            },
+            VisualMode::Vectorscope => {
+                draw_vectorscope(
+                    painter,
+                    rect,
+                    profile,
+                    colors,
+                    data);
+            },
+            VisualMode::LevelMeter => {
+                draw_level_meters(
+                    painter,
+                    rect,
+                    profile,
+                    colors,
+                    &data.levels,
+                    config.noise_floor_db);
+            },
+            VisualMode::Chroma => {
+                draw_chroma(
+                    painter,
+                    rect,
+                    profile,
+                    colors,
+                    data);
+            },
+            VisualMode::XyScope => {
+                draw_xy_scope(
+                    painter,
+                    rect,
+                    profile,
+                    colors,
+                    data);
+            },
         }
+    }
         
+        // 6. Long-running traces over the bars
+        if overlays_bars(profile, data) {
+            draw_spectrum_traces(painter, rect, profile, colors, config, &data.traces, range);
+        }
+
         // 7. Draw Overlays
+        if frequency_mode {
+            // Band labels sit just above the keyboard when both are shown
+            let mut label_rect = rect;
+            if config.show_piano_axis {
+                let centers = bar_center_frequencies(profile, perf, num_bars);
+                draw_piano_axis(painter, rect, &centers, bar_slot_width, config.a4_reference_hz);
+                label_rect.max.y -= PIANO_AXIS_HEIGHT;
+            }
+            if let Some(bands) = octave_band_layout(profile, perf, num_bars) {
+                draw_band_labels(painter, label_rect, colors, &bands, bar_slot_width);
+                label_rect.max.y -= 16.0;
+            }
+
+            // Zoomed frequency axis: show the visible range (Shift + double-click resets)
+            if profile.frequency_zoom.is_some() {
+                let sample_rate = if perf.fft_info.sample_rate > 0 { perf.fft_info.sample_rate } else { 48000 };
+                let range = profile.frequency_range(sample_rate);
+                painter.text(
+                    label_rect.left_bottom() + egui::vec2(6.0, -4.0),
+                    egui::Align2::LEFT_BOTTOM,
+                    format!("🔍 {} - {}", format_frequency(range.min_hz), format_frequency(range.max_hz)),
+                    egui::FontId::proportional(12.0),
+                    to_egui_color(colors.text).linear_multiply(0.7),
+                );
+            }
+        }
+
         if let Some(index) = hovered_bar_index {
             draw_inspector_overlay(
                 &painter,
                 rect,
                 &colors,
+                profile,
                 data,
                 perf,
                 index,
                 bar_slot_width,
-                config.noise_floor_db);
+                config);
         }
 
         if config.show_stats {
@@ -123,7 +254,8 @@ pub fn draw_main_visualizer(
                 &painter,
                 rect,
                 &colors,
-                perf);
+                perf,
+                &data.tempo);
         }
 }
 
@@ -137,7 +269,7 @@ pub fn draw_solid_bars(
     bar_width: f32,
     bar_slot_width: f32,
     hovered_index: Option<usize>,
-    noise_floor_db: f32,
+    range: DbRange,
 ){
     let low = to_egui_color(colors.low).gamma_multiply(profile.bar_opacity);
     let high = to_egui_color(colors.high).gamma_multiply(profile.bar_opacity);
@@ -147,7 +279,7 @@ pub fn draw_solid_bars(
         let x = rect.left() + (i as f32 * bar_slot_width);
         
 
-        let bar_height = db_to_px(db, noise_floor_db, rect.height());
+        let bar_height = db_to_px(db, range, rect.height());
         
         
         // Safe clamp for gradient
@@ -199,7 +331,7 @@ pub fn draw_solid_bars(
 
         // Peaks
         if profile.show_peaks && i < data.peaks.len() {
-            let peak_h = db_to_px(data.peaks[i], noise_floor_db, rect.height());
+            let peak_h = db_to_px(data.peaks[i], range, rect.height());
             
             let peak_rect = if profile.inverted_spectrum {
                 let y = rect.top() + peak_h;
@@ -221,7 +353,7 @@ pub fn draw_solid_bars(
 /// - Inverted/Standard orientation
 /// - Peak indicators
 /// - "Fill to Peak" warning mode
-///(painter, rect, profile, colors, data, bar_width, bar_slot_width, hovered_bar_index, config.noise_floor_db);
+///(painter, rect, profile, colors, data, bar_width, bar_slot_width, hovered_bar_index, range);
 pub fn draw_segmented_bars(
     painter: &egui::Painter, 
     rect: egui::Rect,
@@ -231,7 +363,7 @@ pub fn draw_segmented_bars(
     bar_width: f32,
     bar_slot_width: f32,
     _hovered_index: Option<usize>,
-    noise_floor_db: f32
+    range: DbRange
 ) {
     // 1. Resolve Colors & Opacity
     let low = to_egui_color(colors.low).linear_multiply(profile.bar_opacity);
@@ -249,7 +381,7 @@ pub fn draw_segmented_bars(
             let x = rect.left() + (i as f32 * bar_slot_width);
             
             // Convert dB to pixel height
-            let total_h = db_to_px(db, noise_floor_db, rect.height());
+            let total_h = db_to_px(db, range, rect.height());
             
             // Determine how many segments fit in this height
             let num_segments = (total_h / total_seg_h).floor() as i32;
@@ -282,7 +414,7 @@ pub fn draw_segmented_bars(
 
             // --- Draw Peak Indicators ---
             if profile.show_peaks && i < data.peaks.len() {
-                let peak_h = db_to_px(data.peaks[i], noise_floor_db, rect.height());
+                let peak_h = db_to_px(data.peaks[i], range, rect.height());
                 
                 // Snap peak to the nearest segment grid position
                 let peak_seg_idx = (peak_h / total_seg_h).floor();
@@ -327,7 +459,7 @@ pub fn draw_line_spectrum(
     colors: &ColorProfile,
     data: &crate::shared_state::VisualizationData,
     hovered_index: Option<usize>,
-    noise_floor_db: f32
+    range: DbRange
 ) {
     if data.bars.is_empty() { return; }
     
@@ -337,7 +469,7 @@ pub fn draw_line_spectrum(
     // Pre-calculate points 
     let points: Vec<egui::Pos2> = data.bars.iter().enumerate().map(|(i, &db)| {
         let x = rect.left() + (i as f32 / data.bars.len() as f32) * rect.width();
-        let height = db_to_px(db, noise_floor_db, rect.height());
+        let height = db_to_px(db, range, rect.height());
     
         let y = if profile.inverted_spectrum {
             rect.top() + height
@@ -378,7 +510,308 @@ pub fn draw_line_spectrum(
     }
 }
 
+/// dB range the spectrum is drawn over: auto-ranged while auto gain is on,
+/// otherwise the noise floor up to 0 dB
+pub fn display_range(config: &AppConfig, data: &VisualizationData) -> DbRange {
+    match data.auto_range {
+        Some(range) if config.auto_gain => range,
+        _ => DbRange::fixed(config.noise_floor_db),
+    }
+}
+
+/// Line overlays (traces, references) follow the bars in the frequency modes,
+/// except the waterfall and the mirrored stereo layout
+pub fn overlays_bars(profile: &VisualProfile, data: &VisualizationData) -> bool {
+    let mirrored = profile.stereo_mode == StereoMode::Mirrored && data.channel_bars.len() == 2;
+    profile.visual_mode.is_spectral() && profile.visual_mode != VisualMode::Spectrogram && !mirrored
+}
+
+/// Max-hold, min-hold and LTAS as thin lines through the bar centres
+pub fn draw_spectrum_traces(
+    painter: &Painter,
+    rect: Rect,
+    profile: &VisualProfile,
+    colors: &ColorProfile,
+    config: &AppConfig,
+    traces: &SpectrumTraces,
+    range: DbRange,
+) {
+    let line = |levels: &[f32], stroke: Stroke| {
+        if levels.len() < 2 { return; }
+        let slot_width = rect.width() / levels.len() as f32;
+        let points: Vec<egui::Pos2> = levels.iter().enumerate().map(|(i, &db)| {
+            let height = db_to_px(db, range, rect.height());
+            let y = if profile.inverted_spectrum { rect.top() + height } else { rect.bottom() - height };
+            egui::pos2(rect.left() + (i as f32 + 0.5) * slot_width, y)
+        }).collect();
+        painter.add(egui::Shape::line(points, stroke));
+    };
+
+    if config.show_max_hold {
+        line(traces.max_hold(), Stroke::new(1.5, to_egui_color(colors.peak)));
+    }
+    if config.show_min_hold {
+        line(traces.min_hold(), Stroke::new(1.5, to_egui_color(colors.low).linear_multiply(0.8)));
+    }
+    if config.show_ltas {
+        line(traces.average(), Stroke::new(2.0, to_egui_color(colors.text).linear_multiply(0.8)));
+    }
+}
+
+/// Reference spectrum (dashed) resampled onto the current bars, and optionally the
+/// live - reference difference around a 0 dB centre line
+pub fn draw_reference(
+    painter: &Painter,
+    rect: Rect,
+    colors: &ColorProfile,
+    config: &AppConfig,
+    perf: &PerformanceStats,
+    data: &VisualizationData,
+    reference: &ReferenceTrace,
+) {
+    let profile = &config.profile;
+    let num_bars = data.bars.len();
+    if num_bars < 2 { return; }
+
+    let slot_width = rect.width() / num_bars as f32;
+    let x = |i: usize| rect.left() + (i as f32 + 0.5) * slot_width;
+    let centers = bar_center_frequencies(profile, perf, num_bars);
+    let levels = reference.resample(&centers);
+
+    // Reference: dashed, broken where the reference has no data
+    let stroke = Stroke::new(1.5, to_egui_color(colors.inspector_fg));
+    let points = levels.iter().enumerate().map(|(i, level)| level.map(|db| {
+        let height = db_to_px(db, display_range(config, data), rect.height());
+        let y = if profile.inverted_spectrum { rect.top() + height } else { rect.bottom() - height };
+        egui::pos2(x(i), y)
+    }));
+    for run in polyline_runs(points) {
+        painter.extend(egui::Shape::dashed_line(&run, stroke, 6.0, 4.0));
+    }
+
+    if !config.show_reference_difference { return; }
+
+    // Difference: louder than the reference goes up
+    let text_color = to_egui_color(colors.text);
+    let center_y = rect.center().y;
+    let half_height = rect.height() / 2.0;
+    painter.hline(rect.x_range(), center_y, Stroke::new(1.0, text_color.linear_multiply(0.3)));
+    painter.text(
+        egui::pos2(rect.left() + 4.0, center_y - 2.0),
+        egui::Align2::LEFT_BOTTOM,
+        format!("Δ 0 dB (±{:.0})", DIFFERENCE_RANGE_DB),
+        egui::FontId::monospace(10.0),
+        text_color.linear_multiply(0.6),
+    );
+
+    let diff = reference::difference(&data.bars, &levels);
+    let points = diff.iter().enumerate().map(|(i, delta)| delta.map(|db| {
+        let offset = (db / DIFFERENCE_RANGE_DB).clamp(-1.0, 1.0) * half_height;
+        egui::pos2(x(i), center_y - offset)
+    }));
+    for run in polyline_runs(points) {
+        painter.add(egui::Shape::line(run, Stroke::new(2.0, to_egui_color(colors.peak))));
+    }
+}
+
+/// Split a sequence of optional points into the unbroken runs between the gaps
+fn polyline_runs(points: impl Iterator<Item = Option<egui::Pos2>>) -> Vec<Vec<egui::Pos2>> {
+    let mut runs = vec![Vec::new()];
+    for point in points {
+        match point {
+            Some(point) => runs.last_mut().unwrap().push(point),
+            None if !runs.last().unwrap().is_empty() => runs.push(Vec::new()),
+            None => {}
+        }
+    }
+    runs.retain(|run| run.len() > 1);
+    runs
+}
+
+/// Scrolling waterfall: newest row at the top (bottom when inverted)
+/// Columns are the same bars as the other modes, so the frequency scale matches.
+/// The history is uploaded as a texture each frame; the handle lives in egui's temp memory
+pub fn draw_spectrogram(
+    painter: &egui::Painter,
+    rect: egui::Rect,
+    profile: &VisualProfile,
+    colors: &ColorProfile,
+    data: &VisualizationData,
+    range: DbRange,
+) {
+    let history = &data.spectrogram;
+    let width = history.width();
+    if width == 0 { return; }
+
+    // 256-step lookup table: dB -> colour index via the same range as the bars
+    let lut = profile.spectrogram_colormap.lut(256, colors);
+    let [r, g, b] = lut[0];
+    let empty = egui::Color32::from_rgb(r, g, b);
+    let mut pixels = Vec::with_capacity(width * SPECTROGRAM_ROWS);
+
+    let mut rows = history.rows();
+    for _ in 0..SPECTROGRAM_ROWS {
+        match rows.next() {
+            Some(row) => pixels.extend(row.iter().map(|&db| {
+                let t = db_to_px(db, range, 1.0);
+                let [r, g, b] = lut[(t * 255.0) as usize];
+                egui::Color32::from_rgb(r, g, b)
+            })),
+            None => pixels.extend(std::iter::repeat_n(empty, width)),
+        }
+    }
+
+    let image = egui::ColorImage { size: [width, SPECTROGRAM_ROWS], pixels };
+    let ctx = painter.ctx();
+    let texture_id = egui::Id::new("spectrogram_texture");
+    let texture = match ctx.data(|d| d.get_temp::<egui::TextureHandle>(texture_id)) {
+        Some(mut handle) => {
+            handle.set(image, egui::TextureOptions::LINEAR);
+            handle
+        }
+        None => ctx.load_texture("spectrogram", image, egui::TextureOptions::LINEAR),
+    };
+    ctx.data_mut(|d| d.insert_temp(texture_id, texture.clone()));
+
+    // Newest row is row 0 of the image; flip the UVs to put it at the bottom when inverted
+    let uv = if profile.inverted_spectrum {
+        egui::Rect::from_min_max(egui::pos2(0.0, 1.0), egui::pos2(1.0, 0.0))
+    } else {
+        egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0))
+    };
+    let tint = egui::Color32::WHITE.gamma_multiply(profile.bar_opacity);
+    painter.image(texture.id(), rect, uv, tint);
+}
+
+/// Two spectra on top of each other: channel 0 (L / Mid) in the low colour,
+/// channel 1 (R / Side) in the high colour, each as a line over a translucent fill
+pub fn draw_stereo_overlay(
+    painter: &egui::Painter,
+    rect: egui::Rect,
+    profile: &VisualProfile,
+    colors: &ColorProfile,
+    data: &VisualizationData,
+    hovered_index: Option<usize>,
+    range: DbRange,
+) {
+    let channel_colors = [
+        to_egui_color(colors.low).gamma_multiply(profile.bar_opacity),
+        to_egui_color(colors.high).gamma_multiply(profile.bar_opacity),
+    ];
+    let baseline = if profile.inverted_spectrum { rect.top() } else { rect.bottom() };
+
+    for (bars, &color) in data.channel_bars.iter().zip(&channel_colors) {
+        if bars.is_empty() { continue; }
+
+        let slot_width = rect.width() / bars.len() as f32;
+        let points: Vec<egui::Pos2> = bars.iter().enumerate().map(|(i, &db)| {
+            let x = rect.left() + (i as f32 + 0.5) * slot_width;
+            let height = db_to_px(db, range, rect.height());
+            let y = if profile.inverted_spectrum { rect.top() + height } else { rect.bottom() - height };
+            egui::pos2(x, y)
+        }).collect();
+
+        // Fill: one quad per segment down to the baseline (the outline is not convex)
+        let fill = color.linear_multiply(0.25);
+        let mut mesh = egui::Mesh::default();
+        for pair in points.windows(2) {
+            let base = mesh.vertices.len() as u32;
+            mesh.colored_vertex(pair[0], fill);
+            mesh.colored_vertex(pair[1], fill);
+            mesh.colored_vertex(egui::pos2(pair[1].x, baseline), fill);
+            mesh.colored_vertex(egui::pos2(pair[0].x, baseline), fill);
+            mesh.add_triangle(base, base + 1, base + 2);
+            mesh.add_triangle(base, base + 2, base + 3);
+        }
+        painter.add(egui::Shape::mesh(mesh));
+        painter.add(egui::Shape::line(points.clone(), egui::Stroke::new(2.0, color)));
+
+        if let Some(point) = hovered_index.and_then(|idx| points.get(idx)) {
+            painter.circle_filled(*point, 4.0, egui::Color32::WHITE);
+            painter.circle_stroke(*point, 5.0, egui::Stroke::new(1.0, color));
+        }
+    }
+
+    draw_channel_legend(painter, rect, profile, channel_colors);
+}
+
+/// Left channel grows up from the centre line, right channel grows down
+pub fn draw_mirrored_bars(
+    painter: &egui::Painter,
+    rect: egui::Rect,
+    profile: &VisualProfile,
+    colors: &ColorProfile,
+    data: &VisualizationData,
+    bar_slot_width: f32,
+    range: DbRange,
+) {
+    let bar_width = (bar_slot_width - profile.bar_gap_px as f32).max(1.0);
+    let low = to_egui_color(colors.low).gamma_multiply(profile.bar_opacity);
+    let high = to_egui_color(colors.high).gamma_multiply(profile.bar_opacity);
+    let peak = to_egui_color(colors.peak).gamma_multiply(profile.bar_opacity);
+
+    let center_y = rect.center().y;
+    let half_height = rect.height() / 2.0;
+
+    for (channel, bars) in data.channel_bars.iter().enumerate() {
+        // Channel 0 points up (-1), channel 1 points down (+1)
+        let direction = if channel == 0 { -1.0 } else { 1.0 };
+        let peaks = data.channel_peaks.get(channel);
+
+        for (i, &db) in bars.iter().enumerate() {
+            let x = rect.left() + (i as f32 * bar_slot_width);
+            let bar_height = db_to_px(db, range, half_height);
+            let color = lerp_color(low, high, bar_height / half_height);
+
+            let tip_y = center_y + direction * bar_height;
+            let bar_rect = egui::Rect::from_two_pos(
+                egui::pos2(x, center_y),
+                egui::pos2(x + bar_width, tip_y),
+            );
+            painter.rect_filled(bar_rect, 0.0, color);
+
+            if profile.show_peaks {
+                if let Some(&peak_db) = peaks.and_then(|p| p.get(i)) {
+                    let peak_y = center_y + direction * db_to_px(peak_db, range, half_height);
+                    painter.line_segment(
+                        [egui::pos2(x, peak_y), egui::pos2(x + bar_width, peak_y)],
+                        Stroke::new(2.0, peak),
+                    );
+                }
+            }
+        }
+    }
+
+    // Centre line
+    painter.line_segment(
+        [egui::pos2(rect.left(), center_y), egui::pos2(rect.right(), center_y)],
+        Stroke::new(1.0, to_egui_color(colors.text).linear_multiply(0.3)),
+    );
+
+    draw_channel_legend(painter, rect, profile, [low, high]);
+}
+
+/// "L" / "R" (or "M" / "S") tags in the channel colours, top-left corner
+fn draw_channel_legend(
+    painter: &egui::Painter,
+    rect: egui::Rect,
+    profile: &VisualProfile,
+    channel_colors: [egui::Color32; 2],
+) {
+    let font_id = egui::FontId::monospace(12.0);
+    let mut pos = rect.left_top() + egui::vec2(8.0, 8.0);
+
+    for (name, color) in profile.stereo_mode.channel_names().iter().zip(channel_colors) {
+        let galley = painter.layout_no_wrap(name.to_string(), font_id.clone(), color);
+        let width = galley.size().x;
+        painter.galley(pos, galley, color);
+        pos.x += width + 8.0;
+    }
+}
+
 /// Draws a classic oscillioscope waveform
+/// The latest triggered sweep over a 10 × 8 graticule, with the trigger point and
+/// level marked and the timebase / trigger status in the corner
 pub fn draw_oscilloscope(
     painter: &Painter,
     rect: Rect,
@@ -386,42 +819,355 @@ pub fn draw_oscilloscope(
     colors: &ColorProfile,
     data: &VisualizationData,
 ) {
-    if data.waveform.is_empty() { return; }
+    let text_color = to_egui_color(colors.text);
+    let grid = Stroke::new(1.0, text_color.linear_multiply(0.12));
+    let middle_y = rect.center().y;
+    let height_scale = rect.height() * 0.45; // Leave some cushion for the pushing
+    let y_at = |sample: f32| middle_y - sample.clamp(-1.1, 1.1) * height_scale;
+
+    // Graticule: time divisions across, 0.25 FS divisions up and down
+    for i in 0..=SCOPE_DIVISIONS {
+        let x = rect.left() + rect.width() * i as f32 / SCOPE_DIVISIONS as f32;
+        painter.vline(x, rect.y_range(), grid);
+    }
+    for i in -4..=4 {
+        painter.hline(rect.x_range(), y_at(i as f32 * 0.25), grid);
+    }
+
+    // Trigger point (one division in) and level
+    let trigger_color = to_egui_color(colors.peak).linear_multiply(0.5);
+    let trigger_x = rect.left() + rect.width() / SCOPE_DIVISIONS as f32;
+    painter.vline(trigger_x, rect.y_range(), Stroke::new(1.0, trigger_color));
+    painter.hline(rect.left()..=trigger_x, y_at(profile.scope_trigger_level), Stroke::new(2.0, trigger_color));
+
+    let font_id = egui::FontId::monospace(11.0);
+    let status = format!(
+        "{} ms/div  {}  {}",
+        (profile.scope_ms_per_div * 100.0).round() / 100.0,
+        profile.scope_trigger_edge.label(),
+        data.scope_status.label()
+    );
+    painter.text(rect.left_top() + egui::vec2(trigger_x - rect.left() + 6.0, 4.0),
+        egui::Align2::LEFT_TOP, status, font_id, text_color);
 
     let line_color = to_egui_color(colors.high).gamma_multiply(profile.bar_opacity);
 
-    let samples = &data.waveform;
-    let len = samples.len();
-    let middle_y = rect.center().y;
-    let height_scale = rect.height() * 0.45; // Leave some cushion for the pushing
+    match &data.waveform {
+        ScopeTrace::Samples(samples) => {
+            if samples.len() < 2 { return; }
+            let last = (samples.len() - 1) as f32;
+
+            let points: Vec<egui::Pos2> = samples
+                .iter()
+                .enumerate()
+                .map(|(i, &sample)|{
+                    let x = rect.left() + (i as f32 / last) * rect.width();
+                    egui::pos2(x, y_at(sample))
+                })
+                .collect();
+
+            painter.add(egui::Shape::line(
+                points,
+                Stroke::new(1.5, line_color),
+            ));
+        }
+        ScopeTrace::Envelope(columns) => {
+            // One vertical stroke per column from its min to its max (at least 1.5 px tall)
+            let column_width = rect.width() / columns.len().max(1) as f32;
+            let stroke = Stroke::new(column_width.max(1.0), line_color);
+            for (i, &[min, max]) in columns.iter().enumerate() {
+                let x = rect.left() + (i as f32 + 0.5) * column_width;
+                let (top, bottom) = (y_at(max), y_at(min));
+                let pad = ((1.5 - (bottom - top)) / 2.0).max(0.0);
+                painter.line_segment([egui::pos2(x, top - pad), egui::pos2(x, bottom + pad)], stroke);
+            }
+        }
+    }
+}
+
 
-    let points: Vec<egui::Pos2> = samples
+/// Persistence trace, uploaded as a texture (handle kept in egui temp memory)
+fn draw_phosphor(painter: &Painter, rect: Rect, phosphor: &Phosphor, color: egui::Color32, opacity: f32) {
+    let size = phosphor.size();
+    let pixels = phosphor
+        .intensity()
         .iter()
-        .enumerate()
-        .map(|(i, &sample)|{
-            let x = rect.left() + (i as f32 / len as f32) *  rect.width();
-            let y = middle_y - (sample * height_scale);
-            egui::pos2(x,y)
-        })
+        .map(|&i| egui::Color32::from_rgba_unmultiplied(color.r(), color.g(), color.b(), (i * 255.0) as u8))
         .collect();
+    let image = egui::ColorImage { size: [size, size], pixels };
+
+    let ctx = painter.ctx();
+    let texture_id = egui::Id::new("phosphor_texture");
+    let texture = match ctx.data(|d| d.get_temp::<egui::TextureHandle>(texture_id)) {
+        Some(mut handle) => {
+            handle.set(image, egui::TextureOptions::LINEAR);
+            handle
+        }
+        None => ctx.load_texture("phosphor", image, egui::TextureOptions::LINEAR),
+    };
+    ctx.data_mut(|d| d.insert_temp(texture_id, texture.clone()));
+
+    let uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+    painter.image(texture.id(), rect, uv, egui::Color32::WHITE.gamma_multiply(opacity));
+}
+
+/// XY (Lissajous) scope: one channel across, another up, unrotated
+/// An 8 × 8 graticule of 0.25 FS divisions with the two axes emphasised
+pub fn draw_xy_scope(
+    painter: &Painter,
+    rect: Rect,
+    profile: &VisualProfile,
+    colors: &ColorProfile,
+    data: &VisualizationData,
+) {
+    let text_color = to_egui_color(colors.text);
+    let grid = Stroke::new(1.0, text_color.linear_multiply(0.12));
+    let axis = Stroke::new(1.0, text_color.linear_multiply(0.3));
+    let font_id = egui::FontId::monospace(11.0);
+
+    let side = (rect.height().min(rect.width()) - 8.0).max(0.0);
+    if side <= 0.0 { return; }
+    let scope = Rect::from_center_size(rect.center(), egui::vec2(side, side));
+
+    for i in 0..=8 {
+        let offset = side * i as f32 / 8.0;
+        let stroke = if i == 4 { axis } else { grid };
+        painter.vline(scope.left() + offset, scope.y_range(), stroke);
+        painter.hline(scope.x_range(), scope.top() + offset, stroke);
+    }
 
-   
-    painter.add(egui::Shape::line(
-        points,
-        Stroke::new(1.5, line_color),
-    ));
+    let label_color = text_color.linear_multiply(0.6);
+    painter.text(scope.right_bottom() + egui::vec2(-4.0, -4.0), egui::Align2::RIGHT_BOTTOM,
+        format!("X: Ch {}", profile.xy_x_channel + 1), font_id.clone(), label_color);
+    painter.text(scope.left_top() + egui::vec2(4.0, 4.0), egui::Align2::LEFT_TOP,
+        format!("Y: Ch {}", profile.xy_y_channel + 1), font_id, label_color);
+
+    draw_phosphor(painter, scope, &data.phosphor, to_egui_color(colors.high), profile.bar_opacity);
+}
+
+/// Goniometer: fading L/R trace rotated 45° (Mid up, Side across) above a correlation meter
+pub fn draw_vectorscope(
+    painter: &Painter,
+    rect: Rect,
+    profile: &VisualProfile,
+    colors: &ColorProfile,
+    data: &VisualizationData,
+) {
+    let trace = to_egui_color(colors.high);
+    let text_color = to_egui_color(colors.text);
+    let grid = Stroke::new(1.0, text_color.linear_multiply(0.15));
+    let font_id = egui::FontId::monospace(11.0);
+
+    // Square scope above a strip for the correlation meter
+    let meter_height = 28.0;
+    let side = (rect.height() - meter_height - 8.0).min(rect.width()).max(0.0);
+    if side <= 0.0 { return; }
+    let scope = Rect::from_center_size(
+        egui::pos2(rect.center().x, rect.top() + 4.0 + side / 2.0),
+        egui::vec2(side, side),
+    );
+
+    // Graticule: Mid (vertical), Side (horizontal) and the L / R diagonals
+    let c = scope.center();
+    let r = side / 2.0;
+    painter.line_segment([egui::pos2(c.x, scope.top()), egui::pos2(c.x, scope.bottom())], grid);
+    painter.line_segment([egui::pos2(scope.left(), c.y), egui::pos2(scope.right(), c.y)], grid);
+    painter.line_segment([c + egui::vec2(-r, -r) * 0.5, c + egui::vec2(r, r) * 0.5], grid);
+    painter.line_segment([c + egui::vec2(r, -r) * 0.5, c + egui::vec2(-r, r) * 0.5], grid);
+    painter.circle_stroke(c, r, grid);
+    for (label, offset, align) in [
+        ("M", egui::vec2(0.0, -r), egui::Align2::CENTER_TOP),
+        ("L", egui::vec2(-r, -r) * 0.5, egui::Align2::RIGHT_BOTTOM),
+        ("R", egui::vec2(r, -r) * 0.5, egui::Align2::LEFT_BOTTOM),
+        ("S", egui::vec2(r, 0.0), egui::Align2::RIGHT_BOTTOM),
+        ("S", egui::vec2(-r, 0.0), egui::Align2::LEFT_BOTTOM),
+    ] {
+        painter.text(c + offset, align, label, font_id.clone(), text_color.linear_multiply(0.6));
+    }
+
+    draw_phosphor(painter, scope, &data.phosphor, trace, profile.bar_opacity);
+
+    // Correlation meter: -1 (out of phase) .. 0 .. +1 (mono)
+    let meter = Rect::from_min_size(
+        egui::pos2(scope.left(), scope.bottom() + 8.0),
+        egui::vec2(side, 8.0),
+    );
+    let x_at = |value: f32| meter.left() + (value.clamp(-1.0, 1.0) + 1.0) * 0.5 * meter.width();
+    painter.rect_filled(meter, 2.0, text_color.linear_multiply(0.08));
+    painter.rect_filled(
+        Rect::from_min_max(meter.left_top(), egui::pos2(meter.center().x, meter.bottom())),
+        2.0,
+        to_egui_color(colors.peak).linear_multiply(0.25),
+    );
+    painter.vline(meter.center().x, meter.y_range(), grid);
+
+    let correlation = data.correlation;
+    let marker_color = if correlation < 0.0 { to_egui_color(colors.peak) } else { to_egui_color(colors.low) };
+    let marker_x = x_at(correlation);
+    painter.rect_filled(
+        Rect::from_min_max(egui::pos2(marker_x - 2.0, meter.top() - 2.0), egui::pos2(marker_x + 2.0, meter.bottom() + 2.0)),
+        1.0,
+        marker_color,
+    );
+
+    painter.text(meter.left_bottom() + egui::vec2(0.0, 2.0), egui::Align2::LEFT_TOP, "-1", font_id.clone(), text_color);
+    painter.text(meter.right_bottom() + egui::vec2(0.0, 2.0), egui::Align2::RIGHT_TOP, "+1", font_id.clone(), text_color);
+    painter.text(
+        egui::pos2(meter.center().x, meter.bottom() + 2.0),
+        egui::Align2::CENTER_TOP,
+        format!("Correlation {:+.2}", correlation),
+        font_id,
+        text_color,
+    );
 }
 
+/// Per-channel level meters: ballistic bar, peak hold, clip latch and peak/RMS readouts
+pub fn draw_level_meters(
+    painter: &Painter,
+    rect: Rect,
+    profile: &VisualProfile,
+    colors: &ColorProfile,
+    levels: &[ChannelLevel],
+    noise_floor_db: f32,
+) {
+    if levels.is_empty() { return; }
+    // Meters read absolute dBFS, so auto gain doesn't apply
+    let range = DbRange::fixed(noise_floor_db);
+
+    let low = to_egui_color(colors.low).gamma_multiply(profile.bar_opacity);
+    let high = to_egui_color(colors.high).gamma_multiply(profile.bar_opacity);
+    let peak = to_egui_color(colors.peak);
+    let text_color = to_egui_color(colors.text);
+    let font_id = egui::FontId::monospace(11.0);
+
+    // Leave room for the dB scale on the left, clip lights above and readouts below
+    let scale_width = 32.0;
+    let clip_height = 10.0;
+    let readout_height = 30.0;
+    let meter_area = Rect::from_min_max(
+        rect.left_top() + egui::vec2(scale_width, clip_height + 8.0),
+        rect.right_bottom() - egui::vec2(8.0, readout_height + 4.0),
+    );
+    if meter_area.width() <= 0.0 || meter_area.height() <= 0.0 { return; }
+
+    // dB scale (0 dBFS at the top, noise floor at the bottom)
+    for tick_db in [0.0, -3.0, -6.0, -12.0, -20.0, -30.0, -40.0, -60.0, -80.0] {
+        if tick_db < noise_floor_db { continue; }
+        let y = meter_area.bottom() - db_to_px(tick_db, range, meter_area.height());
+        painter.text(
+            egui::pos2(meter_area.left() - 6.0, y),
+            egui::Align2::RIGHT_CENTER,
+            format!("{}", tick_db),
+            font_id.clone(),
+            text_color.linear_multiply(0.6),
+        );
+        painter.hline(meter_area.x_range(), y, Stroke::new(1.0, text_color.linear_multiply(0.1)));
+    }
+
+    let slot_width = meter_area.width() / levels.len() as f32;
+    let meter_width = (slot_width - profile.bar_gap_px.max(4) as f32).max(2.0);
+
+    for (ch, level) in levels.iter().enumerate() {
+        let x = meter_area.left() + ch as f32 * slot_width;
+        let track = Rect::from_min_size(egui::pos2(x, meter_area.top()), egui::vec2(meter_width, meter_area.height()));
+        painter.rect_filled(track, 0.0, text_color.linear_multiply(0.05));
+
+        // Ballistic reading
+        let bar_height = db_to_px(level.meter_db, range, track.height());
+        let norm_height = bar_height / track.height();
+        let bar_rect = Rect::from_min_max(egui::pos2(x, track.bottom() - bar_height), track.right_bottom());
+        painter.rect_filled(bar_rect, 0.0, lerp_color(low, high, norm_height));
+
+        // Peak hold marker
+        let hold_y = track.bottom() - db_to_px(level.hold_db, range, track.height());
+        painter.hline(track.x_range(), hold_y, Stroke::new(2.0, peak));
+
+        // Clip latch: lit until the meters are reset
+        let clip_rect = Rect::from_min_size(
+            egui::pos2(x, rect.top() + 4.0),
+            egui::vec2(meter_width, clip_height),
+        );
+        let clip_color = if level.clipped { peak } else { peak.linear_multiply(0.15) };
+        painter.rect_filled(clip_rect, 2.0, clip_color);
+
+        // Channel name and readouts
+        let name = match levels.len() {
+            2 => ["L", "R"][ch].to_string(),
+            _ => format!("{}", ch + 1),
+        };
+        painter.text(
+            egui::pos2(track.center().x, track.bottom() + 2.0),
+            egui::Align2::CENTER_TOP,
+            format!("{}\n{:.1} / {:.1}", name, level.peak_db.max(noise_floor_db), level.rms_db.max(noise_floor_db)),
+            font_id.clone(),
+            text_color,
+        );
+    }
+}
+
+/// Chromagram: one bar per pitch class (C to B), with the detected key's tonic highlighted
+pub fn draw_chroma(
+    painter: &Painter,
+    rect: Rect,
+    profile: &VisualProfile,
+    colors: &ColorProfile,
+    data: &VisualizationData,
+) {
+    let low = to_egui_color(colors.low).gamma_multiply(profile.bar_opacity);
+    let high = to_egui_color(colors.high).gamma_multiply(profile.bar_opacity);
+    let peak = to_egui_color(colors.peak);
+    let text_color = to_egui_color(colors.text);
+    let font_id = egui::FontId::monospace(11.0);
+
+    // Key name above, note names below
+    let header_height = 20.0;
+    let label_height = 16.0;
+    let bar_area = Rect::from_min_max(
+        rect.left_top() + egui::vec2(8.0, header_height),
+        rect.right_bottom() - egui::vec2(8.0, label_height),
+    );
+    if bar_area.width() <= 0.0 || bar_area.height() <= 0.0 { return; }
+
+    let key_text = match data.key {
+        Some(key) => format!("Key: {} ({})", key.name(), key.camelot()),
+        None => "Key: --".to_string(),
+    };
+    painter.text(rect.left_top() + egui::vec2(8.0, 4.0), egui::Align2::LEFT_TOP, key_text, font_id.clone(), text_color);
+
+    let slot_width = bar_area.width() / data.chroma.len() as f32;
+    let bar_width = (slot_width - profile.bar_gap_px.max(2) as f32).max(1.0);
+    let tonic = data.key.map(|key| key.tonic);
+
+    for (pitch_class, &value) in data.chroma.iter().enumerate() {
+        let x = bar_area.left() + pitch_class as f32 * slot_width;
+        let value = value.clamp(0.0, 1.0);
+        let bar_rect = Rect::from_min_max(
+            egui::pos2(x, bar_area.bottom() - value * bar_area.height()),
+            egui::pos2(x + bar_width, bar_area.bottom()),
+        );
+        painter.rect_filled(bar_rect, 0.0, lerp_color(low, high, value));
+
+        let label_color = if tonic == Some(pitch_class) { peak } else { text_color.linear_multiply(0.7) };
+        painter.text(
+            egui::pos2(x + bar_width / 2.0, bar_area.bottom() + 2.0),
+            egui::Align2::CENTER_TOP,
+            pitch::NOTE_NAMES[pitch_class],
+            font_id.clone(),
+            label_color,
+        );
+    }
+}
 
 pub fn draw_inspector_overlay( 
     painter: &egui::Painter, 
     rect: egui::Rect, 
     colors: &ColorProfile,
+    profile: &VisualProfile,
     data: &crate::shared_state::VisualizationData,
     perf: &crate::shared_state::PerformanceStats,
     index: usize,
     slot_width: f32,
-    _noise_floor: f32,
+    config: &AppConfig,
 ) {
 
     // Crosshair
@@ -433,19 +1179,57 @@ pub fn draw_inspector_overlay(
 
     // Label Calculation
     let amp_db = data.bars[index];
-    let freq_hz = FFTProcessor::calculate_bar_frequency(
-        index, 
-        data.bars.len(),
-        perf.fft_info.sample_rate,
-        perf.fft_info.fft_size
-    );
+    let band = octave_band_layout(profile, perf, data.bars.len())
+        .and_then(|bands| bands.get(index).copied());
+    let centers = bar_center_frequencies(profile, perf, data.bars.len());
 
-    let freq_text = if freq_hz >= 1000.0 {
-        format!("{:.1} kHz", freq_hz / 1000.0)
+    let (freq_hz, freq_text) = if let Some(band) = band {
+        (band.center_hz, format!("{} ({})", band.label(), profile.octave_fraction.label()))
     } else {
-        format!("{:.0} Hz", freq_hz)
+        let freq_hz = centers.get(index).copied().unwrap_or(0.0);
+        (freq_hz, format_frequency(freq_hz))
+    };
+
+    // Nearest note and how far off it the bar centre is
+    let freq_text = match Note::from_frequency(freq_hz, config.a4_reference_hz) {
+        Some(note) => format!("{} | {} {:+.0}¢", freq_text, note.name(), note.cents),
+        None => freq_text,
+    };
+
+    // Strongest raw-spectrum peak between the neighbouring bar centres, to sub-bin precision
+    let peak = match (centers.get(index.saturating_sub(1)), centers.get(index + 1).or(centers.last())) {
+        (Some(&low), Some(&high)) => peak_detection::strongest_peak_in(
+            &data.spectrum,
+            data.spectrum_resolution_hz,
+            config.peak_interpolation,
+            low,
+            high,
+        ),
+        _ => None,
+    };
+    let label = match (data.channel_bars.first(), data.channel_bars.get(1)) {
+        (Some(first), Some(second)) if index < first.len() && index < second.len() => {
+            let [a, b] = profile.stereo_mode.channel_names();
+            format!("{} | {} {:+.1} / {} {:+.1} dB", freq_text, a, first[index], b, second[index])
+        }
+        _ => format!("{} | {:+.1} dB", freq_text, amp_db),
+    };
+    let label = match peak {
+        Some(peak) => {
+            let note = Note::from_frequency(peak.frequency_hz, config.a4_reference_hz)
+                .map(|note| format!(" ({} {:+.0}¢)", note.name(), note.cents))
+                .unwrap_or_default();
+            format!("{}\nPeak {:.2} Hz{} | {:+.1} dB", label, peak.frequency_hz, note, peak.level_db)
+        }
+        None => label,
     };
-    let label = format!("{} | {:+.1} dB", freq_text, amp_db);
+
+    // Mark the interpolated peak on the spectrum
+    if let Some(x) = peak.and_then(|p| frequency_to_x(p.frequency_hz, &centers, rect, slot_width)) {
+        let height = db_to_px(peak.map_or(SILENCE_DB, |p| p.level_db), display_range(config, data), rect.height());
+        let y = if profile.inverted_spectrum { rect.top() + height } else { rect.bottom() - height };
+        painter.circle_stroke(egui::pos2(x, y), 4.0, egui::Stroke::new(1.5, to_egui_color(colors.inspector_fg)));
+    }
 
     // ToolTip
     let font_id = egui::FontId::proportional(14.0);
@@ -470,23 +1254,162 @@ pub fn draw_inspector_overlay(
     painter.galley(label_rect.min + egui::vec2(padding, padding), galley, egui::Color32::WHITE);
 }
 
+/// Octave bands currently shown, if the profile is in octave-band mode
+/// Returns None when the published bars don't match the band layout yet
+/// (e.g. for the frame or two after switching modes)
+fn octave_band_layout(profile: &VisualProfile, perf: &PerformanceStats, num_bars: usize) -> Option<Vec<OctaveBand>> {
+    if profile.analysis_mode != AnalysisMode::OctaveBands {
+        return None;
+    }
+    let sample_rate = if perf.fft_info.sample_rate > 0 { perf.fft_info.sample_rate } else { 48000 };
+    let range = profile.frequency_range(sample_rate);
+    let bands: Vec<OctaveBand> = octave_bands::bands(profile.octave_fraction, sample_rate as f64 / 2.0)
+        .into_iter()
+        .filter(|band| range.contains(band.nominal_hz))
+        .collect();
+
+    if bands.len() == num_bars { Some(bands) } else { None }
+}
+
+/// Centre frequency of every bar on screen (band centres in octave-band mode,
+/// bin centres in constant-Q mode)
+pub fn bar_center_frequencies(profile: &VisualProfile, perf: &PerformanceStats, num_bars: usize) -> Vec<f64> {
+    if let Some(bands) = octave_band_layout(profile, perf, num_bars) {
+        return bands.iter().map(|band| band.center_hz).collect();
+    }
+    let sample_rate = if perf.fft_info.sample_rate > 0 { perf.fft_info.sample_rate } else { 48000 };
+    let range = profile.frequency_range(sample_rate);
+    if profile.analysis_mode == AnalysisMode::ConstantQ {
+        let frequencies = constant_q::bin_frequencies(profile.cqt_resolution, sample_rate, range);
+        if frequencies.len() == num_bars {
+            return frequencies;
+        }
+    }
+    (0..num_bars)
+        .map(|i| FFTProcessor::calculate_bar_frequency(
+            i,
+            num_bars,
+            perf.fft_info.sample_rate,
+            perf.fft_info.fft_size,
+            profile.frequency_scale,
+            range,
+            profile.scale_knee_hz) as f64)
+        .collect()
+}
+
+/// Horizontal position of `freq_hz`, interpolated (in log frequency) between bar centres
+/// None outside the displayed range
+fn frequency_to_x(freq_hz: f64, centers: &[f64], rect: egui::Rect, slot_width: f32) -> Option<f32> {
+    let (&first, &last) = (centers.first()?, centers.last()?);
+    if freq_hz < first || freq_hz > last || first <= 0.0 {
+        return None;
+    }
+
+    let upper = centers.partition_point(|&c| c < freq_hz).clamp(1, centers.len() - 1);
+    let (lo, hi) = (centers[upper - 1], centers[upper]);
+    let frac = if hi > lo { ((freq_hz / lo).ln() / (hi / lo).ln()) as f32 } else { 0.0 };
+
+    Some(rect.left() + ((upper - 1) as f32 + frac + 0.5) * slot_width)
+}
+
+/// Piano keyboard along the bottom edge, keys placed on the current frequency scale
+/// C keys are labelled with their octave where they are wide enough
+fn draw_piano_axis(
+    painter: &egui::Painter,
+    rect: egui::Rect,
+    centers: &[f64],
+    slot_width: f32,
+    a4_hz: f32,
+) {
+    let strip = egui::Rect::from_min_max(egui::pos2(rect.left(), rect.bottom() - PIANO_AXIS_HEIGHT), rect.right_bottom());
+    let white = egui::Color32::from_gray(225);
+    let black = egui::Color32::from_gray(20);
+    let outline = egui::Stroke::new(1.0, egui::Color32::from_gray(90));
+
+    // Key edges sit half a semitone either side of the note
+    let key_span = |midi: i32| {
+        let left = frequency_to_x(pitch::midi_frequency(midi as f64 - 0.5, a4_hz), centers, rect, slot_width)?;
+        let right = frequency_to_x(pitch::midi_frequency(midi as f64 + 0.5, a4_hz), centers, rect, slot_width)?;
+        Some((left, right))
+    };
+
+    // White keys first, black keys drawn shorter on top
+    for black_pass in [false, true] {
+        for midi in PIANO_LOWEST_MIDI..=PIANO_HIGHEST_MIDI {
+            if pitch::is_black_key(midi) != black_pass { continue; }
+            let Some((left, right)) = key_span(midi) else { continue };
+
+            if black_pass {
+                let key = egui::Rect::from_min_max(
+                    egui::pos2(left, strip.top()),
+                    egui::pos2(right, strip.top() + strip.height() * 0.6),
+                );
+                painter.rect_filled(key, 0.0, black);
+            } else {
+                let key = egui::Rect::from_min_max(egui::pos2(left, strip.top()), egui::pos2(right, strip.bottom()));
+                painter.rect_filled(key, 0.0, white);
+                painter.rect_stroke(key, 0.0, outline);
+
+                if midi % 12 == 0 && right - left >= 14.0 {
+                    let name = Note { midi, cents: 0.0 }.name();
+                    painter.text(
+                        egui::pos2((left + right) / 2.0, strip.bottom() - 1.0),
+                        egui::Align2::CENTER_BOTTOM,
+                        name,
+                        egui::FontId::proportional(8.0),
+                        black,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Nominal centre frequencies (31.5, 63, 125 ...) along the bottom edge
+/// Labels are thinned out evenly when the bands are too narrow to fit them all
+fn draw_band_labels(
+    painter: &egui::Painter,
+    rect: egui::Rect,
+    colors: &ColorProfile,
+    bands: &[OctaveBand],
+    slot_width: f32,
+) {
+    let font_id = egui::FontId::proportional(10.0);
+    let text_color = to_egui_color(colors.text).linear_multiply(0.7);
+    let min_label_width = 30.0;
+    let step = (min_label_width / slot_width.max(1.0)).ceil().max(1.0) as usize;
+
+    for (i, band) in bands.iter().enumerate().step_by(step) {
+        let center_x = rect.left() + (i as f32 * slot_width) + (slot_width / 2.0);
+        painter.text(
+            egui::pos2(center_x, rect.bottom() - 2.0),
+            egui::Align2::CENTER_BOTTOM,
+            band.label(),
+            font_id.clone(),
+            text_color,
+        );
+    }
+}
+
 /// Render performance statistics overlay
 pub fn draw_stats_overlay(
     painter: &egui::Painter,
     rect: egui::Rect,
     colors: &ColorProfile,
-    perf: &crate::shared_state::PerformanceStats
+    perf: &crate::shared_state::PerformanceStats,
+    tempo: &TempoReadings,
 ) {
     // Position in top-left (with padding)
     let pos = rect.left_top() + egui::vec2(10.0, 10.0);
     
     let text = format!(
-        "FPS: {:.0}\nFFT: {:.1}ms\nMin/Max: {:.1}/{:.1}ms\nRes: {:.1}Hz",
+        "FPS: {:.0}\nFFT: {:.1}ms\nMin/Max: {:.1}/{:.1}ms\nRes: {:.1}Hz\nBPM: {}",
         perf.gui_fps,
         perf.fft_ave_time.as_micros() as f32 / 1000.0,
         perf.fft_min_time.as_micros() as f32 / 1000.0,
         perf.fft_max_time.as_micros() as f32 / 1000.0,
-        perf.fft_info.frequency_resolution
+        perf.fft_info.frequency_resolution,
+        tempo.bpm.map_or("--".to_string(), |bpm| format!("{:.1}", bpm))
     );
 
     // Reuse Inspector colors for consistency
@@ -508,6 +1431,120 @@ pub fn draw_stats_overlay(
     painter.galley(pos + egui::vec2(pad, pad), galley, egui::Color32::WHITE); // Text color is baked into galley
 }
 
+/// "440 Hz" / "14.5 kHz"
+fn format_frequency(freq_hz: f64) -> String {
+    if freq_hz >= 1000.0 {
+        format!("{:.1} kHz", freq_hz / 1000.0)
+    } else {
+        format!("{:.0} Hz", freq_hz)
+    }
+}
+
+/// Format a meter value, showing a dash until the meter has a reading
+pub fn format_level(value: f32, unit: &str) -> String {
+    if value.is_finite() {
+        format!("{:+.1} {}", value, unit)
+    } else {
+        format!("-- {}", unit)
+    }
+}
+
+/// Render EBU R128 loudness readings (top-right corner)
+/// Returns the area covered so further overlays can stack below it
+pub fn draw_loudness_overlay(
+    painter: &egui::Painter,
+    rect: egui::Rect,
+    colors: &ColorProfile,
+    loudness: &LoudnessReadings,
+) -> egui::Rect {
+    let text = format!(
+        "M:  {}\nS:  {}\nI:  {}\nLRA: {:.1} LU\nTP: {}",
+        format_level(loudness.momentary_lufs, "LUFS"),
+        format_level(loudness.short_term_lufs, "LUFS"),
+        format_level(loudness.integrated_lufs, "LUFS"),
+        loudness.loudness_range_lu,
+        format_level(loudness.true_peak_dbtp, "dBTP"),
+    );
+
+    // Reuse Inspector colors for consistency
+    let bg_color = to_egui_color(colors.inspector_bg);
+    let text_color = to_egui_color(colors.inspector_fg);
+
+    let galley = painter.layout_no_wrap(text, egui::FontId::monospace(12.0), text_color);
+
+    let pad = 6.0;
+    let size = galley.size() + egui::vec2(pad * 2.0, pad * 2.0);
+    let pos = rect.right_top() + egui::vec2(-10.0 - size.x, 10.0);
+    let bg_rect = egui::Rect::from_min_size(pos, size);
+
+    painter.rect_filled(bg_rect, 4.0, bg_color);
+    painter.galley(pos + egui::vec2(pad, pad), galley, egui::Color32::WHITE);
+    bg_rect
+}
+
+/// Strongest interpolated spectral peaks, loudest first (top-right, below `top`)
+pub fn draw_peak_list(
+    painter: &egui::Painter,
+    rect: egui::Rect,
+    top: f32,
+    colors: &ColorProfile,
+    config: &AppConfig,
+    data: &VisualizationData,
+) {
+    let peaks = peak_detection::find_peaks(
+        &data.spectrum,
+        data.spectrum_resolution_hz,
+        config.peak_interpolation,
+        display_range(config, data).floor_db,
+        config.peak_list_count,
+    );
+
+    let mut text = String::from("Peaks");
+    if peaks.is_empty() {
+        text.push_str("\n  --");
+    }
+    for (i, peak) in peaks.iter().enumerate() {
+        let note = Note::from_frequency(peak.frequency_hz, config.a4_reference_hz)
+            .map(|note| format!("{:<4}{:+3.0}¢", note.name(), note.cents))
+            .unwrap_or_default();
+        text.push_str(&format!("\n{}. {:>10.2} Hz {:+6.1} dB  {}", i + 1, peak.frequency_hz, peak.level_db, note));
+    }
+
+    let bg_color = to_egui_color(colors.inspector_bg);
+    let text_color = to_egui_color(colors.inspector_fg);
+    let galley = painter.layout_no_wrap(text, egui::FontId::monospace(12.0), text_color);
+
+    let pad = 6.0;
+    let size = galley.size() + egui::vec2(pad * 2.0, pad * 2.0);
+    let pos = egui::pos2(rect.right() - 10.0 - size.x, top + 10.0);
+    let bg_rect = egui::Rect::from_min_size(pos, size);
+
+    painter.rect_filled(bg_rect, 4.0, bg_color);
+    painter.galley(pos + egui::vec2(pad, pad), galley, egui::Color32::WHITE);
+}
+
+/// Detected key with its Camelot code, right-aligned below `top`
+pub fn draw_key_badge(
+    painter: &egui::Painter,
+    rect: egui::Rect,
+    top: f32,
+    colors: &ColorProfile,
+    key: &MusicalKey,
+) {
+    let text = format!("Key: {} ({})", key.name(), key.camelot());
+
+    let bg_color = to_egui_color(colors.inspector_bg);
+    let text_color = to_egui_color(colors.inspector_fg);
+    let galley = painter.layout_no_wrap(text, egui::FontId::monospace(12.0), text_color);
+
+    let pad = 6.0;
+    let size = galley.size() + egui::vec2(pad * 2.0, pad * 2.0);
+    let pos = egui::pos2(rect.right() - 20.0 - size.x, top);
+
+    painter.rect_filled(egui::Rect::from_min_size(pos, size), 4.0, bg_color);
+    painter.galley(pos + egui::vec2(pad, pad), galley, egui::Color32::WHITE);
+}
+
 pub fn draw_media_overlay(
     ui: &mut egui::Ui,
     rect: egui::Rect,
@@ -533,7 +1570,7 @@ pub fn draw_media_overlay(
     // 3. Layout calculation
     // Anchor relative to the visulalizer 'rect' passed in
     let overlay_w = rect.width() * 0.5;
-    let overlay_h = 100.0;
+    let overlay_h = MEDIA_OVERLAY_HEIGHT;
     let pos = egui::pos2(rect.right() - overlay_w - 20.0, rect.top() + 20.0);
     let overlay_rect = egui::Rect::from_min_size(pos, egui::vec2(overlay_w, overlay_h));
 
@@ -2,6 +2,8 @@ use eframe::egui::{self, Ui, Rect, Context, Sense, Color32};
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
 use crate::gui::{SettingsTab, SaveTarget};
+use crate::fft_config::AnalysisMode;
+use crate::frequency_scale::FrequencyScale;
 use crate::shared_state::{SharedState};
 use crate::media::MediaController;
 
@@ -9,10 +11,16 @@ use crate::media::MediaController;
 // WINDOW CONTROLS  (Drag, Resize, Lock)
 // =======================================================================================
 
+/// Zoom step per point of scroll (50 points is about one wheel notch)
+const ZOOM_PER_POINT: f64 = 0.002;
+
 /// Handle high-level windo interaction:
 /// 1. Dragging (primary click)
 /// 2. Maximize/Restore (double click)
 /// 3. Settings Menu (right click)
+///
+/// With Shift held, drags and double clicks belong to the frequency axis instead
+/// (see `handle_frequency_zoom`)
 pub fn handle_window_interaction(
     ui: &mut Ui,
     ctx: &Context,
@@ -32,12 +40,13 @@ pub fn handle_window_interaction(
         // - click() fixes the context menu.
         // - Window Dragging still works because we trigger StartDrag manually via
         //   pointer.button_pressed() below, which doesn't depend on egui's high-level drag state.
-    if interaction.hovered() && ui.input(|i| i.pointer.button_pressed(egui::PointerButton::Primary)){
+    let shift = ui.input(|i| i.modifiers.shift);
+    if interaction.hovered() && !shift && ui.input(|i| i.pointer.button_pressed(egui::PointerButton::Primary)){
         ctx.send_viewport_cmd(egui::ViewportCommand::StartDrag);
     }
 
     // 2. Double-clicking toggles Maximize
-    if interaction.double_clicked() {
+    if interaction.double_clicked() && !shift {
         let is_max = ctx.input(|i| i.viewport().maximized.unwrap_or(false));
         ctx.send_viewport_cmd(egui::ViewportCommand::Maximized(!is_max));
     }
@@ -62,6 +71,70 @@ pub fn handle_window_interaction(
     });
 }
 
+/// Zoom and pan the frequency axis of the main window:
+/// 1. Mouse wheel zooms around the cursor
+/// 2. Shift + drag pans
+/// 3. Shift + double click resets to the profile's full range
+///
+/// Plain drags and double clicks keep moving and maximizing the window
+pub fn handle_frequency_zoom(
+    ui: &Ui,
+    rect: Rect,
+    shared_state: &Arc<Mutex<SharedState>>,
+) {
+    let Some(pointer) = ui.input(|i| i.pointer.hover_pos()) else { return };
+    if !rect.contains(pointer) || rect.width() <= 0.0 { return; }
+
+    let mut state = match shared_state.lock() {
+        Ok(s) => s,
+        Err(_) => return,
+    };
+    let profile = &state.config.profile;
+    if !profile.visual_mode.is_spectral() { return; }
+
+    let sample_rate = if state.performance.fft_info.sample_rate > 0 { state.performance.fft_info.sample_rate } else { 48000 };
+    let bounds = profile.frequency_bounds(sample_rate);
+    let current = profile.frequency_range(sample_rate);
+    // Octave bands and constant-Q bins are spaced logarithmically whatever the scale
+    let scale = match profile.analysis_mode {
+        AnalysisMode::Spectrum | AnalysisMode::MultiResolution => profile.frequency_scale,
+        AnalysisMode::OctaveBands | AnalysisMode::ConstantQ => FrequencyScale::Logarithmic,
+    };
+
+    let (scroll, shift, drag_x, double_clicked) = ui.input(|i| (
+        i.raw_scroll_delta.x + i.raw_scroll_delta.y,
+        i.modifiers.shift,
+        if i.pointer.primary_down() { i.pointer.delta().x } else { 0.0 },
+        i.pointer.button_double_clicked(egui::PointerButton::Primary),
+    ));
+
+    let zoom = if shift && double_clicked {
+        None
+    } else if scroll != 0.0 {
+        // Scrolling up zooms in, about 10% per wheel notch
+        let position = ((pointer.x - rect.left()) / rect.width()) as f64;
+        let zoomed = current.zoom(scale, position, (-scroll as f64 * ZOOM_PER_POINT).exp());
+        Some(zoomed.clamped(bounds))
+    } else if shift && drag_x != 0.0 {
+        // The spectrum follows the pointer; stop at the edges instead of squeezing
+        let panned = current.pan(scale, -(drag_x / rect.width()) as f64);
+        if !bounds.contains(panned.min_hz) || !bounds.contains(panned.max_hz) { return; }
+        Some(panned)
+    } else {
+        return;
+    };
+
+    // Fully zoomed out is the same as no zoom
+    let zoom = zoom.filter(|range| {
+        range.min_hz.is_finite() && range.max_hz.is_finite() && *range != bounds
+    });
+    if zoom != state.config.profile.frequency_zoom {
+        state.config.profile.frequency_zoom = zoom;
+        // Max/min hold and LTAS belong to the old bar frequencies
+        state.traces_reset_requested = true;
+    }
+}
+
 /// Draw the discrete resize grip in the bottom-right (or tope-right if inverted)
 pub fn draw_resize_grip(
     ui: &mut Ui,
@@ -0,0 +1,152 @@
+//! Per-channel level meters: sample peak, RMS, a ballistic reading and a peak hold
+//! Measured on the raw multichannel stream, one block (packet) at a time
+
+use crate::ballistics::{MeterBallistics, PeakHold, PeakState};
+use crate::shared_state::SILENCE_DB;
+
+/// Sample peaks at or above this level latch the clip indicator
+pub const CLIP_LEVEL_DB: f32 = -0.1;
+/// Peak hold marker timing
+const METER_PEAK_HOLD: PeakHold = PeakHold { hold_ms: 2000.0, release_ms: 1500.0 };
+
+/// Readings for one channel (all in dBFS)
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ChannelLevel {
+    pub peak_db: f32,       // Sample peak of the last block
+    pub rms_db: f32,        // RMS of the last block (sine reads 3 dB below its peak)
+    pub meter_db: f32,      // Reading after the selected ballistics
+    pub hold_db: f32,       // Peak hold marker
+    pub clipped: bool,      // Latched until reset
+}
+
+impl Default for ChannelLevel {
+    fn default() -> Self {
+        Self {
+            peak_db: SILENCE_DB,
+            rms_db: SILENCE_DB,
+            meter_db: SILENCE_DB,
+            hold_db: SILENCE_DB,
+            clipped: false,
+        }
+    }
+}
+
+pub struct LevelMeter {
+    sample_rate: u32,
+    levels: Vec<ChannelLevel>,
+    holds: Vec<PeakState>,
+}
+
+impl LevelMeter {
+    pub fn new(sample_rate: u32, channels: usize) -> Self {
+        Self {
+            sample_rate,
+            levels: vec![ChannelLevel::default(); channels],
+            holds: vec![PeakState::new(SILENCE_DB); channels],
+        }
+    }
+
+    /// Clear the peak holds and clip latches
+    pub fn reset(&mut self) {
+        for (level, hold) in self.levels.iter_mut().zip(&mut self.holds) {
+            level.clipped = false;
+            *hold = PeakState::new(level.meter_db);
+            level.hold_db = level.meter_db;
+        }
+    }
+
+    pub fn levels(&self) -> &[ChannelLevel] {
+        &self.levels
+    }
+
+    /// Measure one block of interleaved samples
+    pub fn process(&mut self, interleaved: &[f32], ballistics: MeterBallistics) {
+        let channels = self.levels.len();
+        if channels == 0 || interleaved.len() < channels {
+            return;
+        }
+
+        let frames = interleaved.len() / channels;
+        let delta_ms = frames as f32 / self.sample_rate.max(1) as f32 * 1000.0;
+
+        for (ch, (level, hold)) in self.levels.iter_mut().zip(&mut self.holds).enumerate() {
+            let (peak, sum_sq) = interleaved
+                .iter()
+                .skip(ch)
+                .step_by(channels)
+                .fold((0.0f32, 0.0f64), |(peak, sum), &s| (peak.max(s.abs()), sum + (s as f64) * (s as f64)));
+
+            level.peak_db = amplitude_to_db(peak);
+            level.rms_db = amplitude_to_db((sum_sq / frames as f64).sqrt() as f32);
+            level.clipped |= level.peak_db >= CLIP_LEVEL_DB;
+
+            let target = if ballistics.reads_rms() { level.rms_db } else { level.peak_db };
+            level.meter_db = ballistics.step(level.meter_db, target, delta_ms);
+
+            METER_PEAK_HOLD.step(hold, level.meter_db, delta_ms);
+            level.hold_db = hold.level;
+        }
+    }
+}
+
+fn amplitude_to_db(amplitude: f32) -> f32 {
+    if amplitude <= 0.0 {
+        return SILENCE_DB;
+    }
+    (20.0 * amplitude.log10()).max(SILENCE_DB)
+}
+
+// =============== Tests ==================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Interleaved stereo: left carries a sine at `amplitude`, right is silent
+    fn left_sine(amplitude: f32, frames: usize) -> Vec<f32> {
+        (0..frames)
+            .flat_map(|i| {
+                let s = amplitude * (2.0 * std::f32::consts::PI * 1000.0 * i as f32 / 48000.0).sin();
+                [s, 0.0]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sine_peak_and_rms() {
+        let mut meter = LevelMeter::new(48000, 2);
+        meter.process(&left_sine(0.5, 4800), MeterBallistics::Digital);
+
+        let left = meter.levels()[0];
+        assert!((left.peak_db + 6.02).abs() < 0.05, "peak = {}", left.peak_db);
+        assert!((left.rms_db + 9.03).abs() < 0.05, "rms = {}", left.rms_db);
+        assert_eq!(meter.levels()[1].peak_db, SILENCE_DB);
+    }
+
+    #[test]
+    fn test_clip_latches_until_reset() {
+        let mut meter = LevelMeter::new(48000, 2);
+        meter.process(&left_sine(1.0, 480), MeterBallistics::Ppm);
+        meter.process(&left_sine(0.1, 480), MeterBallistics::Ppm);
+        assert!(meter.levels()[0].clipped);
+        assert!(!meter.levels()[1].clipped);
+
+        meter.reset();
+        assert!(!meter.levels()[0].clipped);
+    }
+
+    #[test]
+    fn test_vu_reads_rms_with_lag() {
+        let mut meter = LevelMeter::new(48000, 2);
+
+        // One 10 ms block: a VU meter has barely moved, a digital meter is already there
+        meter.process(&left_sine(0.5, 480), MeterBallistics::Vu);
+        assert!(meter.levels()[0].meter_db < -20.0);
+
+        for _ in 0..100 {
+            meter.process(&left_sine(0.5, 480), MeterBallistics::Vu);
+        }
+        let left = meter.levels()[0];
+        assert!((left.meter_db - left.rms_db).abs() < 0.1, "VU = {}", left.meter_db);
+        assert!(left.hold_db >= left.meter_db);
+    }
+}
@@ -0,0 +1,503 @@
+//! Loudness metering per ITU-R BS.1770-4 / EBU R128
+//!
+//! - K-weighting (high shelf + RLB high-pass) per channel
+//! - Momentary (400 ms), short-term (3 s) and gated integrated loudness in LUFS
+//! - Loudness range (LRA, EBU Tech 3342) in LU
+//! - True peak with 4x polyphase oversampling in dBTP
+//!
+//! Audio is measured in 100 ms steps: momentary and integrated gating blocks are
+//! 4 steps long (75% overlap), short-term windows 30 steps
+
+use std::collections::VecDeque;
+
+/// BS.1770 loudness offset: LUFS = -0.691 + 10 log10(weighted mean square)
+const LOUDNESS_OFFSET: f64 = -0.691;
+/// Gating block length in 100 ms steps
+const MOMENTARY_STEPS: usize = 4;
+const SHORT_TERM_STEPS: usize = 30;
+/// Absolute gate for integrated loudness and LRA
+const ABSOLUTE_GATE_LUFS: f64 = -70.0;
+/// Relative gates (below the ungated mean)
+const INTEGRATED_RELATIVE_GATE_LU: f64 = -10.0;
+const LRA_RELATIVE_GATE_LU: f64 = -20.0;
+/// Gating histograms: 0.1 LU bins from the absolute gate up to +10 LUFS (EBU Tech 3342)
+const HISTOGRAM_BIN_LU: f64 = 0.1;
+const HISTOGRAM_MAX_LUFS: f64 = 10.0;
+const HISTOGRAM_BINS: usize = ((HISTOGRAM_MAX_LUFS - ABSOLUTE_GATE_LUFS) / HISTOGRAM_BIN_LU) as usize;
+/// True-peak oversampling factor and taps per polyphase branch
+const OVERSAMPLING: usize = 4;
+const TAPS_PER_PHASE: usize = 12;
+
+/// Current meter readings. Values are -inf until enough audio has been measured
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct LoudnessReadings {
+    pub momentary_lufs: f32,
+    pub short_term_lufs: f32,
+    pub integrated_lufs: f32,
+    pub loudness_range_lu: f32,
+    pub true_peak_dbtp: f32,
+}
+
+impl Default for LoudnessReadings {
+    fn default() -> Self {
+        Self {
+            momentary_lufs: f32::NEG_INFINITY,
+            short_term_lufs: f32::NEG_INFINITY,
+            integrated_lufs: f32::NEG_INFINITY,
+            loudness_range_lu: 0.0,
+            true_peak_dbtp: f32::NEG_INFINITY,
+        }
+    }
+}
+
+// ==== K-Weighting ====
+
+/// Direct form I biquad
+#[derive(Clone, Copy, Debug, Default)]
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 3],
+    x: [f64; 2],
+    y: [f64; 2],
+}
+
+impl Biquad {
+    fn process(&mut self, input: f64) -> f64 {
+        let output = self.b[0] * input + self.b[1] * self.x[0] + self.b[2] * self.x[1]
+            - self.a[1] * self.y[0] - self.a[2] * self.y[1];
+        self.x = [input, self.x[0]];
+        self.y = [output, self.y[0]];
+        output
+    }
+}
+
+/// Two-stage K-weighting filter for one channel
+/// Coefficients are derived from the analogue prototype, so any sample rate works
+#[derive(Clone, Copy, Debug)]
+struct KWeighting {
+    shelf: Biquad,
+    high_pass: Biquad,
+}
+
+impl KWeighting {
+    fn new(sample_rate: u32) -> Self {
+        let fs = sample_rate as f64;
+
+        // Stage 1: high shelf (+4 dB above ~1.7 kHz, models the head)
+        let f0 = 1681.974450955533;
+        let gain_db = 3.999843853973347;
+        let q = 0.7071752369554196;
+        let k = (std::f64::consts::PI * f0 / fs).tan();
+        let vh = 10f64.powf(gain_db / 20.0);
+        let vb = vh.powf(0.4996667741545416);
+        let a0 = 1.0 + k / q + k * k;
+        let shelf = Biquad {
+            b: [(vh + vb * k / q + k * k) / a0, 2.0 * (k * k - vh) / a0, (vh - vb * k / q + k * k) / a0],
+            a: [1.0, 2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+            ..Default::default()
+        };
+
+        // Stage 2: RLB high-pass (~38 Hz)
+        let f0 = 38.13547087602444;
+        let q = 0.5003270373238773;
+        let k = (std::f64::consts::PI * f0 / fs).tan();
+        let a0 = 1.0 + k / q + k * k;
+        let high_pass = Biquad {
+            b: [1.0, -2.0, 1.0],
+            a: [1.0, 2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+            ..Default::default()
+        };
+
+        Self { shelf, high_pass }
+    }
+
+    fn process(&mut self, input: f64) -> f64 {
+        self.high_pass.process(self.shelf.process(input))
+    }
+}
+
+// ==== True Peak ====
+
+/// 4x polyphase interpolator that tracks the largest absolute (inter-)sample value
+#[derive(Clone, Debug)]
+struct TruePeakDetector {
+    /// phases[p][k]: tap k of polyphase branch p
+    phases: Vec<[f64; TAPS_PER_PHASE]>,
+    history: [f64; TAPS_PER_PHASE],
+    peak: f64,
+}
+
+impl TruePeakDetector {
+    fn new() -> Self {
+        // Windowed-sinc low-pass at the original Nyquist, split into polyphase branches
+        let len = OVERSAMPLING * TAPS_PER_PHASE;
+        let center = (len - 1) as f64 / 2.0;
+        let taps: Vec<f64> = (0..len)
+            .map(|n| {
+                let t = (n as f64 - center) / OVERSAMPLING as f64;
+                let sinc = if t.abs() < 1e-12 { 1.0 } else {
+                    (std::f64::consts::PI * t).sin() / (std::f64::consts::PI * t)
+                };
+                // Blackman window
+                let w = 2.0 * std::f64::consts::PI * n as f64 / (len - 1) as f64;
+                sinc * (0.42 - 0.5 * w.cos() + 0.08 * (2.0 * w).cos())
+            })
+            .collect();
+
+        let phases = (0..OVERSAMPLING)
+            .map(|p| {
+                let mut branch = [0.0; TAPS_PER_PHASE];
+                for (k, tap) in branch.iter_mut().enumerate() {
+                    *tap = taps[p + k * OVERSAMPLING];
+                }
+                // Normalise each branch to unity DC gain
+                let sum: f64 = branch.iter().sum();
+                branch.iter_mut().for_each(|tap| *tap /= sum);
+                branch
+            })
+            .collect();
+
+        Self { phases, history: [0.0; TAPS_PER_PHASE], peak: 0.0 }
+    }
+
+    fn process(&mut self, input: f64) {
+        self.history.rotate_right(1);
+        self.history[0] = input;
+
+        for branch in &self.phases {
+            let value: f64 = branch.iter().zip(&self.history).map(|(h, x)| h * x).sum();
+            self.peak = self.peak.max(value.abs());
+        }
+        self.peak = self.peak.max(input.abs());
+    }
+}
+
+// ==== Gating Histogram ====
+
+/// Blocks above the absolute gate, counted per 0.1 LU of loudness
+/// Fixed size, so gating and the LRA percentiles cost the same after hours of audio
+#[derive(Clone, Debug)]
+struct LoudnessHistogram {
+    counts: Vec<u64>,
+    energy: Vec<f64>,   // Summed mean square per bin, keeps the gated mean exact
+}
+
+impl LoudnessHistogram {
+    fn new() -> Self {
+        Self { counts: vec![0; HISTOGRAM_BINS], energy: vec![0.0; HISTOGRAM_BINS] }
+    }
+
+    fn bin(lufs: f64) -> usize {
+        // Saturating cast: anything below the absolute gate lands in bin 0
+        (((lufs - ABSOLUTE_GATE_LUFS) / HISTOGRAM_BIN_LU) as usize).min(HISTOGRAM_BINS - 1)
+    }
+
+    /// Loudness at the centre of `bin`
+    fn bin_lufs(bin: usize) -> f64 {
+        ABSOLUTE_GATE_LUFS + (bin as f64 + 0.5) * HISTOGRAM_BIN_LU
+    }
+
+    /// Add one block (mean square); blocks at or below the absolute gate are dropped
+    fn add(&mut self, mean_square: f64) {
+        let lufs = to_lufs(mean_square);
+        if lufs <= ABSOLUTE_GATE_LUFS {
+            return;
+        }
+        let bin = Self::bin(lufs);
+        self.counts[bin] += 1;
+        self.energy[bin] += mean_square;
+    }
+
+    /// Mean energy of the blocks in `first` and above, None if there are none
+    fn mean_from(&self, first: usize) -> Option<f64> {
+        let count: u64 = self.counts[first..].iter().sum();
+        if count == 0 {
+            return None;
+        }
+        Some(self.energy[first..].iter().sum::<f64>() / count as f64)
+    }
+
+    /// First bin passing the relative gate, `gate_lu` below the absolute-gated mean
+    fn relative_gate_bin(&self, gate_lu: f64) -> Option<usize> {
+        let mean = self.mean_from(0)?;
+        Some(Self::bin(to_lufs(mean) + gate_lu))
+    }
+
+    /// Two-stage gated mean energy of 400 ms blocks (BS.1770-4 integrated loudness)
+    fn integrated_energy(&self) -> f64 {
+        self.relative_gate_bin(INTEGRATED_RELATIVE_GATE_LU)
+            .and_then(|first| self.mean_from(first))
+            .unwrap_or(0.0)
+    }
+
+    /// Loudness range: spread between the 10th and 95th percentile of gated
+    /// short-term loudness (EBU Tech 3342)
+    fn loudness_range(&self) -> f64 {
+        let Some(first) = self.relative_gate_bin(LRA_RELATIVE_GATE_LU) else {
+            return 0.0;
+        };
+        let counts = &self.counts[first..];
+        let total: u64 = counts.iter().sum();
+        if total == 0 {
+            return 0.0;
+        }
+
+        let percentile = |p: f64| {
+            let rank = ((total - 1) as f64 * p).round() as u64;
+            let mut seen = 0;
+            let bin = counts.iter().position(|&count| {
+                seen += count;
+                seen > rank
+            });
+            Self::bin_lufs(first + bin.unwrap_or(0))
+        };
+        percentile(0.95) - percentile(0.10)
+    }
+}
+
+// ==== Meter ====
+
+#[derive(Clone, Debug)]
+pub struct LoudnessMeter {
+    sample_rate: u32,
+    channels: usize,
+
+    filters: Vec<KWeighting>,
+    channel_weights: Vec<f64>,
+    true_peaks: Vec<TruePeakDetector>,
+
+    // Current 100 ms step
+    step_len: usize,
+    step_pos: usize,
+    step_energy: f64,
+
+    /// Weighted mean square of the most recent steps (newest at the back)
+    recent_steps: VecDeque<f64>,
+
+    /// Histograms of every 400 ms block (integrated loudness) and 3 s window (LRA) so far
+    momentary_blocks: LoudnessHistogram,
+    short_term_blocks: LoudnessHistogram,
+
+    readings: LoudnessReadings,
+}
+
+impl LoudnessMeter {
+    pub fn new(sample_rate: u32, channels: usize) -> Self {
+        let channels = channels.max(1);
+
+        Self {
+            sample_rate,
+            channels,
+            filters: vec![KWeighting::new(sample_rate); channels],
+            channel_weights: Self::channel_weights(channels),
+            true_peaks: vec![TruePeakDetector::new(); channels],
+            step_len: (sample_rate as usize / 10).max(1),
+            step_pos: 0,
+            step_energy: 0.0,
+            recent_steps: VecDeque::with_capacity(SHORT_TERM_STEPS),
+            momentary_blocks: LoudnessHistogram::new(),
+            short_term_blocks: LoudnessHistogram::new(),
+            readings: LoudnessReadings::default(),
+        }
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    /// Start a fresh measurement (integrated, LRA and true peak restart)
+    pub fn reset(&mut self) {
+        *self = Self::new(self.sample_rate, self.channels);
+    }
+
+    pub fn readings(&self) -> LoudnessReadings {
+        self.readings
+    }
+
+    /// Feed interleaved samples (`channels` per frame, as passed to `new`)
+    pub fn process(&mut self, interleaved: &[f32]) {
+        for frame in interleaved.chunks_exact(self.channels) {
+            for (ch, &sample) in frame.iter().enumerate() {
+                let x = sample as f64;
+                self.true_peaks[ch].process(x);
+
+                let weighted = self.filters[ch].process(x);
+                self.step_energy += self.channel_weights[ch] * weighted * weighted;
+            }
+
+            self.step_pos += 1;
+            if self.step_pos >= self.step_len {
+                self.finish_step();
+            }
+        }
+
+        let peak = self.true_peaks.iter().map(|tp| tp.peak).fold(0.0, f64::max);
+        self.readings.true_peak_dbtp = (20.0 * peak.log10()) as f32;
+    }
+
+    /// BS.1770 channel weights: 1.0 for front channels, 1.41 for surrounds,
+    /// LFE excluded (5.1 order: L R C LFE Ls Rs)
+    fn channel_weights(channels: usize) -> Vec<f64> {
+        (0..channels)
+            .map(|ch| match (channels, ch) {
+                (6, 3) => 0.0,
+                (6, 4) | (6, 5) => 1.41,
+                _ => 1.0,
+            })
+            .collect()
+    }
+
+    fn finish_step(&mut self) {
+        let mean_square = self.step_energy / self.step_len as f64;
+        self.step_energy = 0.0;
+        self.step_pos = 0;
+
+        self.recent_steps.push_back(mean_square);
+        if self.recent_steps.len() > SHORT_TERM_STEPS {
+            self.recent_steps.pop_front();
+        }
+
+        if self.recent_steps.len() >= MOMENTARY_STEPS {
+            let block = Self::mean_of_last(&self.recent_steps, MOMENTARY_STEPS);
+            self.momentary_blocks.add(block);
+            self.readings.momentary_lufs = to_lufs(block) as f32;
+            self.readings.integrated_lufs = to_lufs(self.momentary_blocks.integrated_energy()) as f32;
+        }
+
+        if self.recent_steps.len() >= SHORT_TERM_STEPS {
+            let window = Self::mean_of_last(&self.recent_steps, SHORT_TERM_STEPS);
+            self.short_term_blocks.add(window);
+            self.readings.short_term_lufs = to_lufs(window) as f32;
+            self.readings.loudness_range_lu = self.short_term_blocks.loudness_range() as f32;
+        }
+    }
+
+    fn mean_of_last(steps: &VecDeque<f64>, count: usize) -> f64 {
+        steps.iter().rev().take(count).sum::<f64>() / count as f64
+    }
+}
+
+fn to_lufs(mean_square: f64) -> f64 {
+    if mean_square <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    LOUDNESS_OFFSET + 10.0 * mean_square.log10()
+}
+
+// =============== Tests ==================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_lufs(lufs: f64) -> f64 {
+        10f64.powf((lufs - LOUDNESS_OFFSET) / 10.0)
+    }
+
+    /// Interleaved stereo sine, same signal on both channels
+    fn stereo_sine(sample_rate: u32, freq: f64, dbfs: f64, secs: f64, phase: f64) -> Vec<f32> {
+        let amplitude = 10f64.powf(dbfs / 20.0);
+        let frames = (sample_rate as f64 * secs) as usize;
+        (0..frames)
+            .flat_map(|i| {
+                let s = (amplitude * (2.0 * std::f64::consts::PI * freq * i as f64 / sample_rate as f64 + phase).sin()) as f32;
+                [s, s]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_k_weighting_coefficients_48k() {
+        // Reference coefficients from BS.1770-4 Tables 1 and 2
+        let k = KWeighting::new(48000);
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-6;
+
+        assert!(close(k.shelf.b[0], 1.53512485958697));
+        assert!(close(k.shelf.b[1], -2.69169618940638));
+        assert!(close(k.shelf.b[2], 1.19839281085285));
+        assert!(close(k.shelf.a[1], -1.69065929318241));
+        assert!(close(k.shelf.a[2], 0.73248077421585));
+        assert!(close(k.high_pass.a[1], -1.99004745483398));
+        assert!(close(k.high_pass.a[2], 0.99007225036621));
+    }
+
+    #[test]
+    fn test_stereo_sine_reads_minus_23() {
+        // EBU Tech 3341 case 1: 1 kHz stereo sine at -23 dBFS reads -23.0 LUFS
+        let mut meter = LoudnessMeter::new(48000, 2);
+        meter.process(&stereo_sine(48000, 1000.0, -23.0, 5.0, 0.0));
+        let r = meter.readings();
+
+        assert!((r.momentary_lufs + 23.0).abs() < 0.1, "M = {}", r.momentary_lufs);
+        assert!((r.short_term_lufs + 23.0).abs() < 0.1, "S = {}", r.short_term_lufs);
+        assert!((r.integrated_lufs + 23.0).abs() < 0.1, "I = {}", r.integrated_lufs);
+    }
+
+    #[test]
+    fn test_gating_ignores_silence() {
+        // Long tone so the few blocks straddling the tone/silence edge barely count
+        let mut meter = LoudnessMeter::new(48000, 2);
+        meter.process(&stereo_sine(48000, 1000.0, -23.0, 20.0, 0.0));
+        meter.process(&vec![0.0; 48000 * 2 * 5]);
+
+        let r = meter.readings();
+        assert!((r.integrated_lufs + 23.0).abs() < 0.1, "I = {}", r.integrated_lufs);
+        assert!(r.momentary_lufs < -70.0, "M = {}", r.momentary_lufs);
+    }
+
+    #[test]
+    fn test_loudness_range() {
+        // EBU Tech 3342 case 1: 20 s at -20 dBFS then 20 s at -30 dBFS -> LRA 10 LU
+        // (lower sample rate keeps the test quick; K-weighting adapts to any rate)
+        let rate = 16000;
+        let mut meter = LoudnessMeter::new(rate, 2);
+        meter.process(&stereo_sine(rate, 1000.0, -20.0, 20.0, 0.0));
+        meter.process(&stereo_sine(rate, 1000.0, -30.0, 20.0, 0.0));
+
+        let lra = meter.readings().loudness_range_lu;
+        assert!((lra - 10.0).abs() < 1.0, "LRA = {}", lra);
+    }
+
+    #[test]
+    fn test_histogram_gating_and_percentiles() {
+        let mut histogram = LoudnessHistogram::new();
+        for _ in 0..50 {
+            histogram.add(from_lufs(-20.0));
+            histogram.add(from_lufs(-30.0));
+            histogram.add(from_lufs(-80.0));
+        }
+
+        // Both levels pass the relative gate; the -80 LUFS blocks never enter
+        let expected = -20.0 + 10.0 * (1.1f64 / 2.0).log10();
+        let integrated = to_lufs(histogram.integrated_energy());
+        assert!((integrated - expected).abs() < 1e-6, "I = {}", integrated);
+
+        let lra = histogram.loudness_range();
+        assert!((lra - 10.0).abs() < 0.15, "LRA = {}", lra);
+
+        assert_eq!(LoudnessHistogram::new().integrated_energy(), 0.0);
+        assert_eq!(LoudnessHistogram::new().loudness_range(), 0.0);
+    }
+
+    #[test]
+    fn test_true_peak_finds_inter_sample_peak() {
+        // fs/4 sine at 45° phase: every sample sits at ±0.707, the waveform peaks at 1.0
+        let mut meter = LoudnessMeter::new(48000, 2);
+        meter.process(&stereo_sine(48000, 12000.0, 0.0, 0.5, std::f64::consts::FRAC_PI_4));
+
+        let tp = meter.readings().true_peak_dbtp;
+        assert!(tp > -0.5 && tp < 0.3, "True peak {} dBTP should be ~0, sample peak is -3 dB", tp);
+    }
+
+    #[test]
+    fn test_reset_clears_measurement() {
+        let mut meter = LoudnessMeter::new(48000, 2);
+        meter.process(&stereo_sine(48000, 1000.0, -23.0, 1.0, 0.0));
+        meter.reset();
+
+        assert_eq!(meter.readings(), LoudnessReadings::default());
+    }
+}
@@ -2,12 +2,32 @@
 
 mod audio_capture;
 mod audio_device;
+mod auto_range;
+mod ballistics;
+mod chroma;
+mod colormap;
+mod constant_q;
 mod fft_config;
 mod fft_processor;
+mod frequency_scale;
 mod gui;
+mod level_meter;
+mod loudness;
 mod shared_state;
 mod media;
+mod multi_resolution;
+mod octave_bands;
+mod oscilloscope;
+mod peak_detection;
+mod pitch;
 mod presets;
+mod reference;
+mod spectrogram;
+mod spectrum_traces;
+mod tempo;
+mod vectorscope;
+mod weighting;
+mod windowing;
 
 use core::panic;
 use std::thread;
@@ -20,29 +40,41 @@ use time::macros::format_description;
 use tracing_subscriber::fmt::time::OffsetTime;
 use tracing_subscriber::EnvFilter;
 
-use crossbeam_channel::bounded;
+use crossbeam_channel::{bounded, unbounded};
 use directories::ProjectDirs;
 
 use crate::audio_device::AudioDeviceEnumerator;
+use crate::auto_range::{AutoRange, DbRange};
+use crate::ballistics::Smoothing;
+use crate::chroma::{chroma_from_spectrum, KeyEstimator, PITCH_CLASSES};
 use crate::fft_processor::{FFTProcessor, FFTConfig};
-use crate::shared_state::{SILENCE_DB, VisualMode};
+use crate::shared_state::{SILENCE_DB, StereoMode, VisualMode};
 use shared_state::SharedState;
 use crate::gui::SpectrumApp;
 use crate::audio_capture::{AudioCaptureManager, AudioPacket};
-use crate::fft_config::{FFTConfigManager, FIXED_FFT_SIZE};
+use crate::fft_config::FFTConfigManager;
+use crate::level_meter::LevelMeter;
+use crate::loudness::LoudnessMeter;
 use crate::media::{PlatformMedia, MediaMonitor};
+use crate::oscilloscope::{Oscilloscope, ScopeTrace, TriggerSettings};
+use crate::tempo::TempoTracker;
+use crate::vectorscope::{mid_side_point, CorrelationMeter};
 
 // ========================================================================
 // AUDIO CAPTURE THREAD
 // ========================================================================
 //    Uses AudioCaptureManager for defvice enumeration and auto-detection
 
+//    Returns two receivers: one for the FFT thread, one for the metering thread
 fn start_audio_capture(
     shutdown: Arc<AtomicBool>,
     shared_state: Arc<Mutex<SharedState>>
-) -> crossbeam_channel::Receiver<AudioPacket> {
+) -> (crossbeam_channel::Receiver<AudioPacket>, crossbeam_channel::Receiver<AudioPacket>) {
     
     let (tx, rx) = bounded(10);
+    // Metering needs every sample (gating, LRA, tempo, true peak, clips), so its queue
+    // never drops: the meter thread is cheap and catches up after a hitch
+    let (meter_tx, meter_rx) = unbounded();
 
     thread::spawn(move || {
         tracing::info!("[Capture] Starting audio capture thread");
@@ -152,7 +184,8 @@ fn start_audio_capture(
             // === PROCESS AUDIO ===
             match capture.receiver().recv_timeout(Duration::from_millis(100)) {
                 Ok(packet) => {
-                    // Forward to FFT thread
+                    // Forward to metering and FFT threads
+                    let _ = meter_tx.send(packet.clone());
                     let _ = tx.try_send(packet);
                 }
                 Err(crossbeam_channel::RecvTimeoutError::Timeout) => continue,
@@ -167,7 +200,7 @@ fn start_audio_capture(
         capture.stop_capture();
     });
 
-    rx
+    (rx, meter_rx)
 }
 
 
@@ -184,10 +217,22 @@ fn start_fft_processing(
 
               
         let mut processor: Option<FFTProcessor> = None;
+        // Second channel (R or Side) in stereo modes, same config as `processor`
+        let mut second_processor: Option<FFTProcessor> = None;
         let mut fft_config: Option<FFTConfigManager> = None;
         let mut frame_count= 0u64;
+        // Audio time since the last published frame (spectrogram row timing)
+        let mut ms_since_frame = 0.0f32;
+        // Running key estimate from the chroma of each published frame
+        let mut key_estimator = KeyEstimator::new();
+        // Triggered sweeps for oscilloscope mode (configured from the profile per packet)
+        let mut scope: Option<Oscilloscope> = None;
+        // Auto gain display range (present while auto gain is enabled)
+        let mut auto_range: Option<AutoRange> = None;
 
         let mut mono_buffer: Vec<f32> = Vec::with_capacity(4096);
+        let mut first_channel: Vec<f32> = Vec::with_capacity(4096);
+        let mut second_channel: Vec<f32> = Vec::with_capacity(4096);
         
         // === Performance Tracking ===
         let mut total_process_time = Duration::ZERO;
@@ -211,31 +256,21 @@ fn start_fft_processing(
                         );
                     
                         // Initialize FFT config with ACTUAL device sample rates!
-                        let new_fft_config = FFTConfigManager::new(packet.sample_rate);
-
-                        // Get initial settings from shared state
-                        let config: FFTConfig = {
+                        // FFT size comes from the active visual profile
+                        let (new_fft_config, config) = {
                             let state = shared_state.lock().unwrap();
-                            FFTConfig {
-                                fft_size: FIXED_FFT_SIZE,
-                                sample_rate: packet.sample_rate,
-                                num_bars: state.config.profile.num_bars,
-                                sensitivity: state.config.profile.sensitivity,
-                                attack_time_ms: state.config.profile.attack_time_ms,
-                                release_time_ms: state.config.profile.release_time_ms,
-                                peak_hold_time_ms: state.config.profile.peak_hold_time_ms,
-                                peak_release_time_ms: state.config.profile.peak_release_time_ms,
-                                use_peak_aggregation: state.config.profile.use_peak_aggregation,
-                            }
+                            let manager = FFTConfigManager::new(packet.sample_rate, state.config.profile.fft_size);
+                            let config = FFTConfig::from_profile(&state.config.profile, packet.sample_rate);
+                            (manager, config)
                         };
 
                         let new_processor = FFTProcessor::new(config);
                         
                         let info = new_fft_config.info();
                         tracing::info!(
-                            "[FFT] ✓ Initialized: {} Hz, FFT size: {}, latency: {:.2}ms, mode: {}",
+                            "[FFT] ✓ Initialized: {} Hz, FFT size: {}, latency: {:.2}ms, mode: {:?}",
                                 info.sample_rate, info.fft_size, info.latency_ms,
-                                if new_processor.get_config().use_peak_aggregation { "Peak" } else { "Average" }
+                                new_processor.get_config().aggregation
                         );
                     
                         processor = Some(new_processor);
@@ -270,53 +305,124 @@ fn start_fft_processing(
                         // Rebuild FFT processor with new FFT size
                         let info = fft_config.info();
                         tracing::info!(
-                            "[FFT] ⚙️  Rebuilding FFT: {} Hz, latency: {:.2}ms",
-                            info.sample_rate, info.latency_ms
+                            "[FFT] ⚙️  Rebuilding FFT: {} Hz, size: {}, latency: {:.2}ms",
+                            info.sample_rate, fft_config.get_fft_size(), info.latency_ms
                         );
 
                         let new_config = {
                             let state = shared_state.lock().unwrap();
-                            FFTConfig {
-                                fft_size: FIXED_FFT_SIZE, 
-                                sample_rate: info.sample_rate,
-                                num_bars: state.config.profile.num_bars,
-                                sensitivity: state.config.profile.sensitivity,
-                                attack_time_ms: state.config.profile.attack_time_ms,
-                                release_time_ms: state.config.profile.release_time_ms,
-                                peak_hold_time_ms: state.config.profile.peak_hold_time_ms,
-                                peak_release_time_ms: state.config.profile.peak_release_time_ms,
-                                use_peak_aggregation: state.config.profile.use_peak_aggregation,
-                            }
+                            FFTConfig::from_profile(&state.config.profile, info.sample_rate)
                         };
 
                         *processor = FFTProcessor::new(new_config);
-                         
+                        second_processor = None; // Recreated below from the new config
                     }
 
                     // Convert to mono (FFT expects single channel)
                     //let mono = packet.to_mono();
                     packet.to_mono_with_buffer(&mut mono_buffer);
                     
-                    let mode  = { shared_state.lock().unwrap().config.profile.visual_mode };
+                    let (mode, stereo_mode) = {
+                        let state = shared_state.lock().unwrap();
+                        (state.config.profile.visual_mode, state.config.profile.stereo_mode)
+                    };
 
                     match mode {
                         VisualMode::Oscilloscope => {
                             // === SCOPE MODE: BYPASS FFT ===
-                            // Just normalize/copy raw samples directly to visualization
-                            // We might want to decimate or window here if the packet is huge.
+                            // Samples collect in the scope's history; a sweep is
+                            // published whenever the trigger completes one
+                            let (settings, rearm) = {
+                                let mut state = shared_state.lock().unwrap();
+                                let rearm = std::mem::take(&mut state.scope_rearm_requested);
+                                (TriggerSettings::from_profile(&state.config.profile), rearm)
+                            };
+                            let scope = scope.get_or_insert_with(|| Oscilloscope::new(settings, packet.sample_rate));
+                            scope.configure(settings, packet.sample_rate);
+                            if rearm {
+                                scope.arm();
+                            }
+
+                            // Long sweeps are decimated here, outside the lock
+                            let trace = scope.push(&mono_buffer).map(ScopeTrace::from_sweep);
+
                             let mut state = shared_state.lock().unwrap();
-                            state.visualization.waveform = mono_buffer.clone();
+                            if let Some(trace) = trace {
+                                state.visualization.waveform = trace;
+                            }
+                            state.visualization.scope_status = scope.status();
                             state.visualization.bars.fill(SILENCE_DB);
                         }
+                        VisualMode::Vectorscope => {
+                            // === GONIOMETER: BYPASS FFT ===
+                            // Every L/R pair is plotted into the fading trace, so no
+                            // samples are lost between GUI frames
+                            packet.to_stereo_with_buffers(&mut first_channel, &mut second_channel);
+                            let elapsed_ms = first_channel.len() as f32 / packet.sample_rate.max(1) as f32 * 1000.0;
+
+                            let mut state = shared_state.lock().unwrap();
+                            let persistence_ms = state.config.profile.vectorscope_persistence_secs * 1000.0;
+                            let viz = &mut state.visualization;
+                            viz.stereo_waveform.clear();
+                            viz.stereo_waveform.extend(first_channel.iter().zip(&second_channel).map(|(&l, &r)| [l, r]));
+
+                            viz.phosphor.decay(elapsed_ms, persistence_ms);
+                            for &[left, right] in &viz.stereo_waveform {
+                                let (x, y) = mid_side_point(left, right);
+                                viz.phosphor.plot(x, y);
+                            }
+                            viz.bars.fill(SILENCE_DB);
+                        }
+                        VisualMode::XyScope => {
+                            // === XY SCOPE: BYPASS FFT ===
+                            // The beam moves sample by sample from one channel pair point
+                            // to the next, like a CRT fed on its X and Y inputs
+                            let mut state = shared_state.lock().unwrap();
+                            let profile = &state.config.profile;
+                            let (x_channel, y_channel) = (profile.xy_x_channel, profile.xy_y_channel);
+                            let (decay, persistence_ms) = (profile.xy_decay, profile.xy_persistence_secs * 1000.0);
+                            packet.channel_pair_with_buffers(x_channel, y_channel, &mut first_channel, &mut second_channel);
+                            let elapsed_ms = first_channel.len() as f32 / packet.sample_rate.max(1) as f32 * 1000.0;
+
+                            let viz = &mut state.visualization;
+                            viz.stereo_waveform.clear();
+                            viz.stereo_waveform.extend(first_channel.iter().zip(&second_channel).map(|(&x, &y)| [x, y]));
+
+                            viz.phosphor.fade(decay, elapsed_ms, persistence_ms);
+                            for &[x, y] in &viz.stereo_waveform {
+                                viz.phosphor.trace(x, y);
+                            }
+                            viz.bars.fill(SILENCE_DB);
+                        }
                         _ => {
+                            // Stereo modes run one FFT per channel (L/R or M/S)
+                            match stereo_mode {
+                                StereoMode::Mono => second_processor = None,
+                                StereoMode::MidSide => packet.to_mid_side_with_buffers(&mut first_channel, &mut second_channel),
+                                StereoMode::Overlay | StereoMode::Mirrored => {
+                                    packet.to_stereo_with_buffers(&mut first_channel, &mut second_channel)
+                                }
+                            }
+                            if stereo_mode != StereoMode::Mono && second_processor.is_none() {
+                                // Restart both so their sliding windows hop in lockstep
+                                *processor = FFTProcessor::new(processor.get_config());
+                                second_processor = Some(FFTProcessor::new(processor.get_config()));
+                            }
+
                             // A. Start the timer!
                             let process_start = Instant::now();
 
                             // B. Heavy Math (FFT)
-                            let (bars, peaks) = processor.process(&mono_buffer);
+                            //    Samples accumulate in the processor's sliding window,
+                            //    so a packet may not complete a new frame yet
+                            let (frame, second_frame) = match second_processor.as_mut() {
+                                Some(second) => (processor.process(&first_channel), second.process(&second_channel)),
+                                None => (processor.process(&mono_buffer), None),
+                            };
 
                             // C. Stop Timer
                             let process_time = process_start.elapsed();
+                            ms_since_frame += mono_buffer.len() as f32 / packet.sample_rate.max(1) as f32 * 1000.0;
 
                             // D. Track Performance Stats
                             total_process_time += process_time;
@@ -328,91 +434,144 @@ fn start_fft_processing(
                             let pending_config_update = {
                                 let mut state = shared_state.lock().unwrap();
                                 // Update  visualization  data
-                                state.visualization.bars = bars;
-                                state.visualization.peaks = peaks;
-                                state.visualization.timestamp = Instant::now();
+                                let frame_ready = frame.is_some();
+                                match (frame, second_frame) {
+                                    (Some((bars, peaks)), Some((second_bars, second_peaks))) => {
+                                        // Combined bars keep the inspector and silence checks working
+                                        state.visualization.bars = louder_of(&bars, &second_bars);
+                                        state.visualization.peaks = louder_of(&peaks, &second_peaks);
+                                        state.visualization.channel_bars = vec![bars, second_bars];
+                                        state.visualization.channel_peaks = vec![peaks, second_peaks];
+                                        state.visualization.timestamp = Instant::now();
+                                    }
+                                    (Some((bars, peaks)), None) => {
+                                        state.visualization.bars = bars;
+                                        state.visualization.peaks = peaks;
+                                        state.visualization.channel_bars.clear();
+                                        state.visualization.channel_peaks.clear();
+                                        state.visualization.timestamp = Instant::now();
+                                    }
+                                    _ => {}
+                                }
+
+                                // Raw spectrum for peak detection (louder channel per bin in stereo)
+                                if frame_ready {
+                                    let viz = &mut state.visualization;
+                                    match second_processor.as_ref() {
+                                        Some(second) => viz.spectrum = louder_of(processor.spectrum(), second.spectrum()),
+                                        None => {
+                                            viz.spectrum.clear();
+                                            viz.spectrum.extend_from_slice(processor.spectrum());
+                                        },
+                                    }
+                                    viz.spectrum_resolution_hz = processor.frequency_resolution();
+                                }
+
+                                // Pitch classes and key follow the raw spectrum
+                                if frame_ready {
+                                    if state.key_reset_requested {
+                                        state.key_reset_requested = false;
+                                        key_estimator.reset();
+                                    }
+                                    let chroma = chroma_from_spectrum(
+                                        &state.visualization.spectrum,
+                                        state.visualization.spectrum_resolution_hz,
+                                        state.config.a4_reference_hz,
+                                    );
+                                    key_estimator.update(&chroma, ms_since_frame);
+
+                                    let smoothing = Smoothing {
+                                        attack_ms: state.config.profile.attack_time_ms,
+                                        release_ms: state.config.profile.release_time_ms,
+                                    };
+                                    let viz = &mut state.visualization;
+                                    smoothing.apply(&mut viz.chroma, &chroma, ms_since_frame);
+                                    viz.key = key_estimator.key();
+                                }
+
+                                // Auto gain drifts the display range towards the published bars
+                                if frame_ready {
+                                    let config = &state.config;
+                                    let range = if config.auto_gain {
+                                        let tracker = auto_range.get_or_insert_with(|| {
+                                            AutoRange::new(DbRange::fixed(config.noise_floor_db))
+                                        });
+                                        Some(tracker.update(&state.visualization.bars, ms_since_frame, &config.auto_range_settings()))
+                                    } else {
+                                        auto_range = None;
+                                        None
+                                    };
+                                    state.visualization.auto_range = range;
+                                }
+
+                                // Spectrogram history follows the published bars
+                                if frame_ready {
+                                    let history_secs = state.config.profile.spectrogram_history_secs;
+                                    let viz = &mut state.visualization;
+                                    viz.spectrogram.set_history_secs(history_secs);
+                                    viz.spectrogram.push(&viz.bars, ms_since_frame);
+                                    ms_since_frame = 0.0;
+                                }
+
+                                // Max/min hold and LTAS accumulate until the next reset
+                                if frame_ready {
+                                    if state.traces_reset_requested {
+                                        state.traces_reset_requested = false;
+                                        state.visualization.traces.reset();
+                                    }
+                                    let viz = &mut state.visualization;
+                                    viz.traces.push(&viz.bars);
+                                }
 
                                 // Update performance stats
                                 state.performance.frame_count = frame_count;
                                 state.performance.fft_ave_time = total_process_time / frame_count as u32;
                                 state.performance.fft_min_time = min_process_time;
                                 state.performance.fft_max_time = max_process_time;
-                                state.performance.fft_info = fft_config.info();
 
                                 // Check if any config parameters changed
-                                // 1. Check for changes that require a rebuild
-                                let needs_update = state.config.profile.num_bars != state.visualization.bars.len();
-
-                                let config_differs = |current: &FFTConfig| -> bool {
-                                    state.config.profile.sensitivity != current.sensitivity ||
-                                    state.config.profile.attack_time_ms != current.attack_time_ms ||
-                                    state.config.profile.release_time_ms != current.release_time_ms ||
-                                    state.config.profile.peak_hold_time_ms != current.peak_hold_time_ms ||
-                                    state.config.profile.peak_release_time_ms != current.peak_release_time_ms ||
-                                    state.config.profile.use_peak_aggregation != current.use_peak_aggregation
-                                };
-                                                    
-                                
-                                if needs_update {
-                                    //Major change - needs FFT rebuild
-                                    tracing::debug!(
-                                        "[FFT] Config change requires rebuild (bar count: {} → {})",
-                                        state.visualization.bars.len(),
-                                        state.config.profile.num_bars
+                                // 1. FFT size is tracked by the config manager (latency/resolution stats)
+                                if fft_config.update_fft_size(state.config.profile.fft_size) {
+                                    let info = fft_config.info();
+                                    tracing::info!(
+                                        "[FFT] ⚙️  FFT size changed: {} samples, latency: {:.2}ms, resolution: {:.2} Hz",
+                                        info.fft_size, info.latency_ms, info.frequency_resolution
                                     );
-                                
-                                    Some(FFTConfig {
-                                        fft_size: FIXED_FFT_SIZE,
-                                        sample_rate: fft_config.get_sample_rate(),
-                                        num_bars: state.config.profile.num_bars,
-                                        sensitivity: state.config.profile.sensitivity,
-                                        attack_time_ms: state.config.profile.attack_time_ms,
-                                        release_time_ms: state.config.profile.release_time_ms,
-                                        peak_hold_time_ms: state.config.profile.peak_hold_time_ms,
-                                        peak_release_time_ms: state.config.profile.peak_release_time_ms,
-                                        use_peak_aggregation: state.config.profile.use_peak_aggregation,
-                                    })
-                                } else {
-                                    // Check for minor config changes that don't require a rebuild
-
-                                    let current = processor.get_config();
-
-                                    if config_differs(&current) {
-                                        // Log specific changes for debugging
-                                        if state.config.profile.use_peak_aggregation != current.use_peak_aggregation {
-                                            tracing::info!{
-                                                "[FFT] Aggregation mode changed: {} → {}",
-                                                if current.use_peak_aggregation { "Peak" } else { "Average" },
-                                                if state.config.profile.use_peak_aggregation { "Peak" } else { "Average" }
-                                            };
-                                        }
-                                    
-
-                                        Some(FFTConfig {
-                                            fft_size: FIXED_FFT_SIZE,
-                                            sample_rate: fft_config.get_sample_rate(),
-                                            num_bars: state.config.profile.num_bars,
-                                            sensitivity: state.config.profile.sensitivity,
-                                            attack_time_ms: state.config.profile.attack_time_ms,
-                                            release_time_ms: state.config.profile.release_time_ms,
-                                            peak_hold_time_ms: state.config.profile.peak_hold_time_ms,
-                                            peak_release_time_ms: state.config.profile.peak_release_time_ms,
-                                            use_peak_aggregation: state.config.profile.use_peak_aggregation,
-                                        })
-                                    } else {
-                                        None
+                                }
+                                state.performance.fft_info = fft_config.info();
+
+                                // 2. Compare the processor's config against the profile
+                                //    (the processor decides internally whether this needs a rebuild)
+                                let desired = FFTConfig::from_profile(&state.config.profile, fft_config.get_sample_rate());
+                                let current = processor.get_config();
+
+                                if desired != current {
+                                    // Stored spectrogram rows belong to the old bars
+                                    // (a new aggregation changes what a row means as well)
+                                    if !desired.same_bar_layout(&current) || desired.aggregation != current.aggregation {
+                                        state.visualization.spectrogram.clear();
                                     }
+
+                                    // Log specific changes for debugging
+                                    if desired.aggregation != current.aggregation {
+                                        tracing::info!{
+                                            "[FFT] Aggregation mode changed: {:?} → {:?}",
+                                            current.aggregation,
+                                            desired.aggregation
+                                        };
+                                    }
+                                    Some(desired)
+                                } else {
+                                    None
                                 }
                             };
                             // Apply confiig update if needed
                             if let Some(new_config) = pending_config_update {
-                                if new_config.num_bars != processor.get_config().num_bars {
-                                    tracing::debug!("[FFT]♻️ Recreating processor for new bar count: {}", new_config.num_bars);
-                                    *processor = FFTProcessor::new(new_config);
-                                } else {
-                                    tracing::debug!("[FFT]🔧 Updating processor config");
-                                    processor.update_config(new_config);
+                                tracing::debug!("[FFT]🔧 Updating processor config");
+                                if let Some(second) = second_processor.as_mut() {
+                                    second.update_config(new_config.clone());
                                 }
+                                processor.update_config(new_config);
                             }
                         }
                     }
@@ -430,8 +589,20 @@ fn start_fft_processing(
                             // fill with silence
                             state.visualization.bars.fill(current_silence);
                             state.visualization.peaks.fill(current_silence);
+                            state.visualization.spectrum.fill(current_silence);
+                            state.visualization.chroma = [0.0; PITCH_CLASSES];
+                            let viz = &mut state.visualization;
+                            for channel in viz.channel_bars.iter_mut().chain(viz.channel_peaks.iter_mut()) {
+                                channel.fill(current_silence);
+                            }
                             state.visualization.timestamp = Instant::now();
                         }
+
+                        // Let the goniometer go dark as well
+                        if !state.visualization.stereo_waveform.is_empty() {
+                            state.visualization.stereo_waveform.clear();
+                            state.visualization.phosphor.clear();
+                        }
                     }
                     continue;
                 }
@@ -462,6 +633,121 @@ fn start_fft_processing(
     });
 }
 
+// ========================================================================
+// METERING THREAD
+// ========================================================================
+//    EBU R128 / BS.1770 loudness, per-channel level meters and L/R phase
+//    correlation, measured on the full multichannel stream; onset / tempo
+//    tracking on a mono downmix
+fn start_metering(
+    rx: crossbeam_channel::Receiver<AudioPacket>,
+    shared_state: Arc<Mutex<SharedState>>,
+    shutdown: Arc<AtomicBool>
+) {
+    thread::spawn(move || {
+        tracing::info!("[Meter] Starting metering thread...");
+
+        let mut meters: Option<Meters> = None;
+        let mut mono = Vec::new();
+        let mut silence = Vec::new();
+        // Wall-clock time the meters have been fed up to (packets or synthetic silence)
+        let mut fed_until = Instant::now();
+
+        while !shutdown.load(Ordering::Relaxed) {
+            match rx.recv_timeout(Duration::from_millis(100)) {
+                Ok(packet) => {
+                    // (Re)create the meters when the stream format changes
+                    let channels = packet.channels as usize;
+                    let format_changed = meters.as_ref()
+                        .is_none_or(|m| m.loudness.sample_rate() != packet.sample_rate || m.loudness.channels() != channels);
+                    if format_changed {
+                        tracing::info!("[Meter] Measuring {} Hz, {} channel(s)", packet.sample_rate, channels);
+                        meters = Some(Meters {
+                            loudness: LoudnessMeter::new(packet.sample_rate, channels),
+                            levels: LevelMeter::new(packet.sample_rate, channels),
+                            correlation: CorrelationMeter::new(packet.sample_rate),
+                            tempo: TempoTracker::new(packet.sample_rate),
+                        });
+                    }
+
+                    let Some(meters) = meters.as_mut() else { continue };
+                    packet.to_mono_with_buffer(&mut mono);
+                    update_meters(meters, &packet.samples, &mono, &shared_state);
+                    fed_until = Instant::now();
+                }
+                Err(crossbeam_channel::RecvTimeoutError::Timeout) => {
+                    // A silent loopback stream stops sending packets: once the gap is
+                    // longer than any driver buffer, feed the meters the missing time as
+                    // silence so levels fall, loudness drops out and tempo lapses
+                    let Some(meters) = meters.as_mut() else { continue };
+                    let missing = fed_until.elapsed();
+                    if missing < METER_STALL_TIMEOUT {
+                        continue;
+                    }
+                    fed_until = Instant::now();
+
+                    let frames = (missing.as_secs_f64() * meters.loudness.sample_rate() as f64) as usize;
+                    silence.resize(frames * meters.loudness.channels(), 0.0);
+                    mono.clear();
+                    mono.resize(frames, 0.0);
+                    update_meters(meters, &silence, &mono, &shared_state);
+                }
+                Err(crossbeam_channel::RecvTimeoutError::Disconnected) => break,
+            }
+        }
+
+        tracing::info!("[Meter] Shutdown");
+    });
+}
+
+/// Packets later than this mean the stream has stalled rather than a slow driver buffer
+const METER_STALL_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Everything the metering thread measures, for one stream format
+struct Meters {
+    loudness: LoudnessMeter,
+    levels: LevelMeter,
+    correlation: CorrelationMeter,
+    tempo: TempoTracker,
+}
+
+/// Run one block of interleaved samples (and its mono downmix) through the meters
+/// and publish their readings
+fn update_meters(meters: &mut Meters, samples: &[f32], mono: &[f32], shared_state: &Mutex<SharedState>) {
+    let channels = meters.loudness.channels();
+    meters.loudness.process(samples);
+    meters.correlation.process(samples, channels);
+    let beats = meters.tempo.process(mono);
+
+    let mut state = shared_state.lock().unwrap();
+    meters.levels.process(samples, state.config.meter_ballistics);
+    if state.loudness_reset_requested {
+        state.loudness_reset_requested = false;
+        meters.loudness.reset();
+        tracing::info!("[Meter] Loudness measurement reset");
+    }
+    if state.levels_reset_requested {
+        state.levels_reset_requested = false;
+        meters.levels.reset();
+    }
+    state.loudness = meters.loudness.readings();
+    state.visualization.levels.clear();
+    state.visualization.levels.extend_from_slice(meters.levels.levels());
+    state.visualization.correlation = meters.correlation.value();
+
+    let readings = &mut state.visualization.tempo;
+    readings.bpm = meters.tempo.bpm();
+    if beats > 0 {
+        readings.beat_count += beats as u64;
+        readings.last_beat = Some(Instant::now());
+    }
+}
+
+/// Per-bar (or per-bin) maximum of two channels
+fn louder_of(a: &[f32], b: &[f32]) -> Vec<f32> {
+    a.iter().zip(b).map(|(&x, &y)| x.max(y)).collect()
+}
+
 // ========================================================================
 // Load Icon to Memory
 // ========================================================================
@@ -569,11 +855,14 @@ fn main (){
     let shutdown = Arc::new(AtomicBool::new(false));
 
     // Start audio capture thread
-    let audio_rx = start_audio_capture(shutdown.clone(), shared_state.clone());
+    let (audio_rx, meter_rx) = start_audio_capture(shutdown.clone(), shared_state.clone());
 
     // Start FFT processing thread
     start_fft_processing(audio_rx, shared_state.clone(), shutdown.clone());
 
+    // Start metering thread (loudness and level meters)
+    start_metering(meter_rx, shared_state.clone(), shutdown.clone());
+
     // Start Media Monitoring thread
     tracing::info!("[Main] Starting Media Monitor...");
     let media_manager = Arc::new(PlatformMedia::new());
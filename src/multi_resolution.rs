@@ -0,0 +1,148 @@
+//! Multi-resolution analysis: several FFT sizes run side by side on the same audio
+//! A long window resolves the bass, a short one keeps the treble's transients sharp,
+//! and each bar is read from the stage that covers its centre frequency
+
+use std::sync::Arc;
+
+use realfft::{RealFftPlanner, RealToComplex};
+
+use crate::fft_config::sanitize_fft_size;
+use crate::windowing::{WindowCorrection, WindowFunction};
+
+/// Sample rate the stage sizes below are quoted at
+/// Other rates scale the sizes so every stage keeps its window length in time
+const REFERENCE_RATE: f64 = 48000.0;
+
+/// One FFT size and the highest frequency it is used for
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct StageSpec {
+    pub fft_size: usize,    // At 48 kHz
+    pub upper_hz: f64,
+}
+
+/// Bass, mids and treble (ascending; the last stage covers everything above)
+pub const STAGES: [StageSpec; 3] = [
+    StageSpec { fft_size: 8192, upper_hz: 250.0 },   // 171 ms, 5.9 Hz bins
+    StageSpec { fft_size: 2048, upper_hz: 4000.0 },  // 43 ms, 23.4 Hz bins
+    StageSpec { fft_size: 512, upper_hz: f64::INFINITY }, // 11 ms, 93.8 Hz bins
+];
+
+impl StageSpec {
+    /// FFT size at `sample_rate` (same window duration as at 48 kHz, power of two)
+    pub fn fft_size_at(&self, sample_rate: u32) -> usize {
+        let scaled = self.fft_size as f64 * sample_rate as f64 / REFERENCE_RATE;
+        sanitize_fft_size(scaled.round() as usize)
+    }
+}
+
+/// Index of the stage that analyses `freq_hz`
+pub fn stage_index(freq_hz: f64) -> usize {
+    STAGES
+        .iter()
+        .position(|stage| freq_hz < stage.upper_hz)
+        .unwrap_or(STAGES.len() - 1)
+}
+
+/// One FFT size with its own sample history and window
+pub struct ResolutionStage {
+    // Newest `fft_size` samples (ring buffer, oldest at `write_pos`)
+    ring: Vec<f32>,
+    write_pos: usize,
+
+    fft: Arc<dyn RealToComplex<f32>>,
+    window: Vec<f32>,
+    window_correction: WindowCorrection,
+    input: Vec<f32>,
+    spectrum: Vec<num_complex::Complex<f32>>,
+    scratch: Vec<num_complex::Complex<f32>>,
+}
+
+impl ResolutionStage {
+    pub fn new(fft_size: usize, window_function: WindowFunction, kaiser_beta: f32) -> Self {
+        let fft = RealFftPlanner::<f32>::new().plan_fft_forward(fft_size);
+        let window = window_function.generate(fft_size, kaiser_beta);
+        let window_correction = WindowCorrection::from_window(&window);
+        let spectrum = fft.make_output_vec();
+        let scratch = fft.make_scratch_vec();
+
+        Self {
+            ring: vec![0.0; fft_size],
+            write_pos: 0,
+            fft,
+            window,
+            window_correction,
+            input: vec![0.0; fft_size],
+            spectrum,
+            scratch,
+        }
+    }
+
+    pub fn fft_size(&self) -> usize {
+        self.ring.len()
+    }
+
+    /// Append samples (any amount), overwriting the oldest
+    pub fn push(&mut self, samples: &[f32]) {
+        let size = self.ring.len();
+        for &sample in samples {
+            self.ring[self.write_pos] = sample;
+            self.write_pos = (self.write_pos + 1) % size;
+        }
+    }
+
+    /// Windowed FFT of the newest `fft_size` samples
+    /// Returns the amplitude of every bin: a sine of amplitude A reads A at its bin
+    pub fn analyse(&mut self) -> Vec<f32> {
+        let (newest, oldest) = self.ring.split_at(self.write_pos);
+        let ordered = oldest.iter().chain(newest);
+        for ((out, &sample), &w) in self.input.iter_mut().zip(ordered).zip(&self.window) {
+            *out = sample * w;
+        }
+
+        self.fft
+            .process_with_scratch(&mut self.input, &mut self.spectrum, &mut self.scratch)
+            .expect("FFT processing failed");
+
+        let normalization = self.window_correction.amplitude * 2.0 / self.ring.len() as f32;
+        self.spectrum.iter().map(|bin| bin.norm() * normalization).collect()
+    }
+}
+
+// =============== Tests ==================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::windowing::DEFAULT_KAISER_BETA;
+
+    #[test]
+    fn test_stage_selection() {
+        assert_eq!(stage_index(40.0), 0);
+        assert_eq!(stage_index(250.0), 1);
+        assert_eq!(stage_index(3999.0), 1);
+        assert_eq!(stage_index(12000.0), 2);
+    }
+
+    #[test]
+    fn test_sizes_follow_sample_rate() {
+        assert_eq!(STAGES[0].fft_size_at(48000), 8192);
+        assert_eq!(STAGES[0].fft_size_at(44100), 8192);
+        assert_eq!(STAGES[0].fft_size_at(96000), 16384);
+        assert_eq!(STAGES[2].fft_size_at(192000), 2048);
+    }
+
+    #[test]
+    fn test_every_size_reads_tone_amplitude() {
+        // 1.5 kHz is a whole bin at every size, so no scalloping
+        for spec in STAGES {
+            let mut stage = ResolutionStage::new(spec.fft_size, WindowFunction::Hann, DEFAULT_KAISER_BETA);
+            let tone: Vec<f32> = (0..stage.fft_size())
+                .map(|i| 0.5 * (2.0 * std::f32::consts::PI * 1500.0 * i as f32 / 48000.0).sin())
+                .collect();
+            stage.push(&tone);
+
+            let amplitudes = stage.analyse();
+            let bin = (1500.0 * stage.fft_size() as f32 / 48000.0).round() as usize;
+            assert!((amplitudes[bin] - 0.5).abs() < 0.01, "{} points read {}", spec.fft_size, amplitudes[bin]);
+        }
+    }
+}
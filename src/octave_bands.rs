@@ -0,0 +1,193 @@
+//! Fractional-octave band layout (IEC 61260-1 style, base-10)
+//! Used by the octave-band analysis mode so bars line up with hardware RTAs
+
+use serde::{Serialize, Deserialize};
+
+/// Reference frequency every band series is anchored to
+const REFERENCE_FREQ: f64 = 1000.0;
+/// Base-10 octave ratio (10^0.3 ≈ 1.995) as specified by IEC 61260-1
+const OCTAVE_RATIO: f64 = 1.9952623149688795;
+/// Bands are kept when their nominal centre lies inside the audible range
+const MIN_NOMINAL_FREQ: f64 = 20.0;
+const MAX_NOMINAL_FREQ: f64 = 20000.0;
+
+/// R10 preferred-number mantissas: the nominal octave and 1/3-octave centres
+const R10_MANTISSAS: [f64; 10] = [1.0, 1.25, 1.6, 2.0, 2.5, 3.15, 4.0, 5.0, 6.3, 8.0];
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub enum OctaveFraction {
+    Full,       // 1/1 octave (10 bands)
+    Third,      // 1/3 octave (31 bands, the classic RTA)
+    Sixth,      // 1/6 octave
+    Twelfth,    // 1/12 octave (~ one band per semitone)
+}
+
+impl OctaveFraction {
+    /// All fractions, in the order shown in the settings UI
+    pub const ALL: [OctaveFraction; 4] = [
+        OctaveFraction::Full,
+        OctaveFraction::Third,
+        OctaveFraction::Sixth,
+        OctaveFraction::Twelfth,
+    ];
+
+    /// Human readable name for the settings UI
+    pub fn label(&self) -> &'static str {
+        match self {
+            OctaveFraction::Full => "1/1 Octave",
+            OctaveFraction::Third => "1/3 Octave",
+            OctaveFraction::Sixth => "1/6 Octave",
+            OctaveFraction::Twelfth => "1/12 Octave",
+        }
+    }
+
+    pub fn bands_per_octave(&self) -> u32 {
+        match self {
+            OctaveFraction::Full => 1,
+            OctaveFraction::Third => 3,
+            OctaveFraction::Sixth => 6,
+            OctaveFraction::Twelfth => 12,
+        }
+    }
+}
+
+/// One analysis band
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct OctaveBand {
+    pub center_hz: f64,     // Exact mid-band frequency
+    pub lower_hz: f64,      // Lower band edge
+    pub upper_hz: f64,      // Upper band edge
+    pub nominal_hz: f64,    // Rounded frequency used for labels (31.5, 63, 125 ...)
+}
+
+impl OctaveBand {
+    /// Short axis label: "31.5", "125", "1k", "12.5k"
+    pub fn label(&self) -> String {
+        if self.nominal_hz >= 1000.0 {
+            format!("{}k", trim_decimals(self.nominal_hz / 1000.0))
+        } else {
+            trim_decimals(self.nominal_hz)
+        }
+    }
+}
+
+/// All bands of a series whose nominal centre is within 20 Hz - 20 kHz and whose
+/// upper edge stays below `max_freq` (normally Nyquist)
+pub fn bands(fraction: OctaveFraction, max_freq: f64) -> Vec<OctaveBand> {
+    let b = fraction.bands_per_octave() as i32;
+    let half_band = OCTAVE_RATIO.powf(1.0 / (2.0 * b as f64));
+
+    // Band indices around the reference, generous enough to cover 20 Hz - 20 kHz
+    let lowest = -(b * 6);
+    let highest = b * 5;
+
+    (lowest..=highest)
+        .map(|x| {
+            // Odd fractions centre a band on 1 kHz, even fractions straddle it
+            let exponent = if b % 2 == 1 {
+                x as f64 / b as f64
+            } else {
+                (2 * x + 1) as f64 / (2 * b) as f64
+            };
+            let center_hz = REFERENCE_FREQ * OCTAVE_RATIO.powf(exponent);
+
+            OctaveBand {
+                center_hz,
+                lower_hz: center_hz / half_band,
+                upper_hz: center_hz * half_band,
+                nominal_hz: nominal_frequency(center_hz, fraction),
+            }
+        })
+        .filter(|band| {
+            band.nominal_hz >= MIN_NOMINAL_FREQ
+                && band.nominal_hz <= MAX_NOMINAL_FREQ
+                && band.upper_hz <= max_freq
+        })
+        .collect()
+}
+
+/// Round an exact centre frequency to its nominal value
+/// Octave and 1/3-octave bands use the R10 preferred numbers,
+/// finer fractions have no standard names and are rounded to 3 significant figures
+fn nominal_frequency(center_hz: f64, fraction: OctaveFraction) -> f64 {
+    let decade = 10f64.powf(center_hz.log10().floor());
+    let mantissa = center_hz / decade;
+
+    match fraction {
+        OctaveFraction::Full | OctaveFraction::Third => {
+            let closest = R10_MANTISSAS
+                .iter()
+                .chain(std::iter::once(&10.0))
+                .min_by(|a, b| {
+                    let da = (mantissa / **a).ln().abs();
+                    let db = (mantissa / **b).ln().abs();
+                    da.total_cmp(&db)
+                })
+                .copied()
+                .unwrap_or(mantissa);
+            closest * decade
+        }
+        OctaveFraction::Sixth | OctaveFraction::Twelfth => (mantissa * 100.0).round() / 100.0 * decade,
+    }
+}
+
+/// Format without trailing zeros ("31.50" -> "31.5", "63.00" -> "63")
+fn trim_decimals(value: f64) -> String {
+    let text = format!("{:.2}", value);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+// =============== Tests ==================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(fraction: OctaveFraction) -> Vec<String> {
+        bands(fraction, 24000.0).iter().map(|b| b.label()).collect()
+    }
+
+    #[test]
+    fn test_octave_nominal_labels() {
+        assert_eq!(
+            labels(OctaveFraction::Full),
+            vec!["31.5", "63", "125", "250", "500", "1k", "2k", "4k", "8k", "16k"]
+        );
+    }
+
+    #[test]
+    fn test_third_octave_series() {
+        let thirds = labels(OctaveFraction::Third);
+
+        assert_eq!(thirds.len(), 31, "20 Hz - 20 kHz is 31 third-octave bands");
+        assert_eq!(thirds.first().map(String::as_str), Some("20"));
+        assert_eq!(thirds.last().map(String::as_str), Some("20k"));
+        assert!(thirds.contains(&"31.5".to_string()));
+        assert!(thirds.contains(&"12.5k".to_string()));
+    }
+
+    #[test]
+    fn test_bands_are_contiguous() {
+        for fraction in OctaveFraction::ALL {
+            let series = bands(fraction, 24000.0);
+            for pair in series.windows(2) {
+                let gap = (pair[0].upper_hz / pair[1].lower_hz - 1.0).abs();
+                assert!(gap < 1e-9, "{:?} bands must touch", fraction);
+            }
+        }
+    }
+
+    #[test]
+    fn test_even_fractions_straddle_reference() {
+        // 1/6 octave: no band is centred on 1 kHz, one band edge sits on it
+        let series = bands(OctaveFraction::Sixth, 24000.0);
+        assert!(series.iter().any(|b| (b.lower_hz - 1000.0).abs() < 1e-6));
+        assert!(series.iter().all(|b| (b.center_hz - 1000.0).abs() > 1.0));
+    }
+
+    #[test]
+    fn test_bands_stop_below_nyquist() {
+        // At 32 kHz sample rate the 16 kHz octave (upper edge ~22.4 kHz) is dropped
+        let series = bands(OctaveFraction::Full, 16000.0);
+        assert_eq!(series.last().map(|b| b.label()), Some("8k".to_string()));
+    }
+}
@@ -0,0 +1,391 @@
+//! Triggered oscilloscope capture
+//! Samples stream into a history buffer regardless of packet size; a sweep starts
+//! when the signal crosses the trigger level on the selected edge, so a periodic
+//! waveform is drawn at the same phase every frame instead of drifting
+
+use std::collections::VecDeque;
+
+use serde::{Serialize, Deserialize};
+
+use crate::shared_state::VisualProfile;
+
+/// Horizontal graticule divisions across the screen
+pub const SCOPE_DIVISIONS: usize = 10;
+/// Default timebase: 20 ms on screen, one cycle of 50 Hz
+pub const DEFAULT_MS_PER_DIV: f32 = 2.0;
+/// The trigger point sits this many divisions in from the left edge
+const PRE_TRIGGER_DIVISIONS: usize = 1;
+/// Auto mode free-runs when no trigger arrived for this long after a sweep
+const AUTO_TIMEOUT_MS: f32 = 100.0;
+/// Longer sweeps are sent to the GUI as this many min/max columns
+pub const SCOPE_COLUMNS: usize = 1024;
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub enum TriggerEdge {
+    Rising,
+    Falling,
+}
+
+impl TriggerEdge {
+    /// All trigger edges, in the order shown in the settings UI
+    pub const ALL: [TriggerEdge; 2] = [
+        TriggerEdge::Rising,
+        TriggerEdge::Falling,
+    ];
+
+    /// Human readable name for the settings UI
+    pub fn label(&self) -> &'static str {
+        match self {
+            TriggerEdge::Rising => "↗ Rising",
+            TriggerEdge::Falling => "↘ Falling",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub enum TriggerMode {
+    Auto,   // Sweep on trigger, free-run when none arrives (always shows something)
+    Normal, // Sweep on trigger only; the last sweep stays on screen
+    Single, // One sweep, then hold until re-armed
+}
+
+impl TriggerMode {
+    /// All trigger modes, in the order shown in the settings UI
+    pub const ALL: [TriggerMode; 3] = [
+        TriggerMode::Auto,
+        TriggerMode::Normal,
+        TriggerMode::Single,
+    ];
+
+    /// Human readable name for the settings UI
+    pub fn label(&self) -> &'static str {
+        match self {
+            TriggerMode::Auto => "Auto",
+            TriggerMode::Normal => "Normal",
+            TriggerMode::Single => "Single",
+        }
+    }
+}
+
+/// What the scope is doing, shown in the corner of the display
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ScopeStatus {
+    #[default]
+    Waiting,    // Armed, no trigger yet
+    Triggered,  // Latest sweep started on a trigger
+    FreeRun,    // Auto mode without a trigger
+    Stopped,    // Single sweep captured
+}
+
+impl ScopeStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ScopeStatus::Waiting => "WAIT",
+            ScopeStatus::Triggered => "TRIG'D",
+            ScopeStatus::FreeRun => "AUTO",
+            ScopeStatus::Stopped => "STOP",
+        }
+    }
+}
+
+/// A sweep ready to draw
+#[derive(Clone, PartialEq, Debug)]
+pub enum ScopeTrace {
+    Samples(Vec<f32>),          // Short sweeps: every sample
+    Envelope(Vec<[f32; 2]>),    // Long sweeps: [min, max] per column
+}
+
+impl Default for ScopeTrace {
+    fn default() -> Self {
+        ScopeTrace::Samples(Vec::new())
+    }
+}
+
+impl ScopeTrace {
+    /// Keep short sweeps as they are, decimate longer ones to `SCOPE_COLUMNS`
+    pub fn from_sweep(sweep: Vec<f32>) -> Self {
+        if sweep.len() <= SCOPE_COLUMNS {
+            ScopeTrace::Samples(sweep)
+        } else {
+            ScopeTrace::Envelope(min_max_envelope(&sweep, SCOPE_COLUMNS))
+        }
+    }
+}
+
+/// Lowest and highest sample of each of `columns` equal slices of `samples`
+/// Every slice also takes the first sample of the next one, so steep edges
+/// between columns stay connected; single-sample peaks are never dropped
+pub fn min_max_envelope(samples: &[f32], columns: usize) -> Vec<[f32; 2]> {
+    let len = samples.len();
+    if len == 0 || columns == 0 {
+        return Vec::new();
+    }
+
+    (0..columns)
+        .map(|column| {
+            let start = (column * len / columns).min(len - 1);
+            let end = ((column + 1) * len / columns + 1).clamp(start + 1, len);
+            samples[start..end]
+                .iter()
+                .fold([f32::MAX, f32::MIN], |[min, max], &s| [min.min(s), max.max(s)])
+        })
+        .collect()
+}
+
+/// Trigger and timebase settings, taken from the visual profile
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct TriggerSettings {
+    pub edge: TriggerEdge,
+    pub mode: TriggerMode,
+    pub level: f32,         // Sample value (-1 .. 1)
+    pub holdoff_ms: f32,    // Dead time after a sweep before the next trigger
+    pub ms_per_div: f32,
+}
+
+impl TriggerSettings {
+    pub fn from_profile(profile: &VisualProfile) -> Self {
+        Self {
+            edge: profile.scope_trigger_edge,
+            mode: profile.scope_trigger_mode,
+            level: profile.scope_trigger_level,
+            holdoff_ms: profile.scope_holdoff_ms,
+            ms_per_div: profile.scope_ms_per_div,
+        }
+    }
+
+    /// Samples across the whole screen
+    pub fn sweep_samples(&self, sample_rate: u32) -> usize {
+        let ms = self.ms_per_div * SCOPE_DIVISIONS as f32;
+        ((ms / 1000.0 * sample_rate as f32).round() as usize).max(SCOPE_DIVISIONS)
+    }
+}
+
+/// Streaming trigger: feed every packet, get a complete sweep whenever one is ready
+#[derive(Clone, Debug)]
+pub struct Oscilloscope {
+    settings: TriggerSettings,
+    sample_rate: u32,
+
+    history: VecDeque<f32>,     // Newest samples, at least one sweep long
+    position: u64,              // Absolute index of the next sample
+    previous: f32,              // Last sample of the previous packet (edge detection)
+
+    pending: Option<u64>,       // Trigger waiting for the rest of its sweep
+    rearm_at: u64,              // No trigger before this sample (sweep + holdoff)
+    last_sweep_at: u64,         // End of the latest sweep (auto timeout)
+    status: ScopeStatus,
+}
+
+impl Oscilloscope {
+    pub fn new(settings: TriggerSettings, sample_rate: u32) -> Self {
+        Self {
+            settings,
+            sample_rate,
+            history: VecDeque::new(),
+            position: 0,
+            previous: 0.0,
+            pending: None,
+            rearm_at: 0,
+            last_sweep_at: 0,
+            status: ScopeStatus::Waiting,
+        }
+    }
+
+    pub fn status(&self) -> ScopeStatus {
+        self.status
+    }
+
+    /// Apply new settings; a different timebase or sample rate starts over
+    pub fn configure(&mut self, settings: TriggerSettings, sample_rate: u32) {
+        if settings.ms_per_div != self.settings.ms_per_div || sample_rate != self.sample_rate {
+            *self = Self::new(settings, sample_rate);
+            return;
+        }
+        if settings.mode != self.settings.mode {
+            self.arm();
+        }
+        self.settings = settings;
+    }
+
+    /// Wait for the next trigger (also restarts a stopped single sweep)
+    pub fn arm(&mut self) {
+        self.pending = None;
+        self.status = ScopeStatus::Waiting;
+    }
+
+    /// Add samples; returns the newest complete sweep, if any finished in this packet
+    pub fn push(&mut self, samples: &[f32]) -> Option<Vec<f32>> {
+        let sweep = self.settings.sweep_samples(self.sample_rate);
+        let pre_trigger = sweep * PRE_TRIGGER_DIVISIONS / SCOPE_DIVISIONS;
+        let post_trigger = sweep - pre_trigger;
+        let holdoff = (self.settings.holdoff_ms / 1000.0 * self.sample_rate as f32) as u64;
+        let auto_timeout = (AUTO_TIMEOUT_MS / 1000.0 * self.sample_rate as f32) as u64;
+
+        let mut latest = None;
+        for &sample in samples {
+            self.history.push_back(sample);
+            if self.history.len() > sweep {
+                self.history.pop_front();
+            }
+            let index = self.position;
+            self.position += 1;
+
+            let armed = self.status != ScopeStatus::Stopped;
+            if armed && self.pending.is_none() && index >= self.rearm_at && self.crosses(sample) {
+                self.pending = Some(index);
+            }
+            self.previous = sample;
+
+            // Sweep complete: the trigger sits `pre_trigger` samples in from the left
+            if let Some(trigger) = self.pending {
+                if index + 1 >= trigger + post_trigger as u64 {
+                    latest = Some(self.history.iter().copied().collect());
+                    self.finish_sweep(holdoff);
+                    self.status = match self.settings.mode {
+                        TriggerMode::Single => ScopeStatus::Stopped,
+                        _ => ScopeStatus::Triggered,
+                    };
+                }
+                continue;
+            }
+
+            // Auto: nothing to trigger on, show the latest sweep anyway
+            let waited = index + 1 - self.last_sweep_at;
+            if self.settings.mode == TriggerMode::Auto && waited >= sweep as u64 + auto_timeout {
+                latest = Some(self.history.iter().copied().collect());
+                self.finish_sweep(holdoff);
+                self.status = ScopeStatus::FreeRun;
+            }
+        }
+
+        // Before the first full sweep the history is still short: pad the left side
+        latest.map(|mut frame: Vec<f32>| {
+            if frame.len() < sweep {
+                frame.splice(0..0, std::iter::repeat_n(0.0, sweep - frame.len()));
+            }
+            frame
+        })
+    }
+
+    fn crosses(&self, sample: f32) -> bool {
+        let level = self.settings.level;
+        match self.settings.edge {
+            TriggerEdge::Rising => self.previous < level && sample >= level,
+            TriggerEdge::Falling => self.previous > level && sample <= level,
+        }
+    }
+
+    fn finish_sweep(&mut self, holdoff: u64) {
+        self.pending = None;
+        self.last_sweep_at = self.position;
+        self.rearm_at = self.position + holdoff;
+    }
+}
+
+// =============== Tests ==================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(mode: TriggerMode, edge: TriggerEdge) -> TriggerSettings {
+        TriggerSettings { edge, mode, level: 0.0, holdoff_ms: 0.0, ms_per_div: 1.0 }
+    }
+
+    fn sine(freq: f32, start: usize, len: usize) -> Vec<f32> {
+        (start..start + len)
+            .map(|i| (2.0 * std::f32::consts::PI * freq * i as f32 / 48000.0).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_sweeps_start_at_same_phase() {
+        // 100 Hz is not a whole number of packets: free-running sweeps would drift,
+        // triggered ones always cross zero upwards one division in
+        let mut scope = Oscilloscope::new(settings(TriggerMode::Normal, TriggerEdge::Rising), 48000);
+        let sweep = scope.settings.sweep_samples(48000);
+        let trigger_at = sweep / SCOPE_DIVISIONS;
+
+        let mut frames = Vec::new();
+        for packet in 0..40 {
+            let samples = sine(100.0, packet * 333 + 17, 333);
+            if let Some(frame) = scope.push(&samples) {
+                frames.push(frame);
+            }
+        }
+
+        assert!(frames.len() > 5);
+        assert_eq!(scope.status(), ScopeStatus::Triggered);
+        for frame in &frames[1..] {
+            assert_eq!(frame.len(), sweep);
+            assert!(frame[trigger_at - 1] < 0.0 && frame[trigger_at] >= 0.0);
+            assert!(frame[trigger_at + 10] > 0.0, "rising edge");
+        }
+    }
+
+    #[test]
+    fn test_falling_edge_and_level() {
+        let mut scope = Oscilloscope::new(
+            TriggerSettings { level: 0.5, ..settings(TriggerMode::Normal, TriggerEdge::Falling) },
+            48000,
+        );
+        let trigger_at = scope.settings.sweep_samples(48000) / SCOPE_DIVISIONS;
+        let frame = (0..20).find_map(|p| scope.push(&sine(50.0, p * 480, 480))).unwrap();
+
+        assert!(frame[trigger_at - 1] > 0.5 && frame[trigger_at] <= 0.5);
+        assert!(frame[trigger_at + 10] < frame[trigger_at]);
+    }
+
+    #[test]
+    fn test_normal_waits_and_auto_free_runs() {
+        // DC never crosses the level
+        let dc = vec![0.25; 48000];
+
+        let mut normal = Oscilloscope::new(settings(TriggerMode::Normal, TriggerEdge::Rising), 48000);
+        assert!(normal.push(&dc).is_none());
+        assert_eq!(normal.status(), ScopeStatus::Waiting);
+
+        let mut auto = Oscilloscope::new(settings(TriggerMode::Auto, TriggerEdge::Rising), 48000);
+        let frame = auto.push(&dc).unwrap();
+        assert_eq!(auto.status(), ScopeStatus::FreeRun);
+        assert!(frame.iter().all(|&s| s == 0.25));
+    }
+
+    #[test]
+    fn test_single_holds_until_rearmed() {
+        let mut scope = Oscilloscope::new(settings(TriggerMode::Single, TriggerEdge::Rising), 48000);
+        assert!(scope.push(&sine(100.0, 1, 4800)).is_some());
+        assert_eq!(scope.status(), ScopeStatus::Stopped);
+        assert!(scope.push(&sine(100.0, 4801, 4800)).is_none());
+
+        scope.arm();
+        assert!(scope.push(&sine(100.0, 9601, 4800)).is_some());
+    }
+
+    #[test]
+    fn test_envelope_keeps_single_sample_peaks() {
+        // One second of silence with a one-sample click and a short negative spike
+        let mut samples = vec![0.0; 48000];
+        samples[12345] = 0.9;
+        samples[40000] = -0.7;
+        let envelope = min_max_envelope(&samples, SCOPE_COLUMNS);
+
+        assert_eq!(envelope.len(), SCOPE_COLUMNS);
+        let (highest, lowest) = envelope.iter().fold((0.0f32, 0.0f32), |(hi, lo), &[min, max]| (hi.max(max), lo.min(min)));
+        assert_eq!((highest, lowest), (0.9, -0.7));
+        assert_eq!(envelope[0], [0.0, 0.0]);
+
+        // Short sweeps are sent sample by sample
+        assert_eq!(ScopeTrace::from_sweep(vec![0.5; 480]), ScopeTrace::Samples(vec![0.5; 480]));
+        assert!(matches!(ScopeTrace::from_sweep(samples), ScopeTrace::Envelope(columns) if columns.len() == SCOPE_COLUMNS));
+    }
+
+    #[test]
+    fn test_holdoff_spaces_triggers() {
+        // 1 kHz crosses every 1 ms; a 25 ms holdoff after each 10 ms sweep leaves
+        // one sweep every 35 ms
+        let holdoff = TriggerSettings { holdoff_ms: 25.0, ..settings(TriggerMode::Normal, TriggerEdge::Rising) };
+        let mut scope = Oscilloscope::new(holdoff, 48000);
+        let sweeps = (0..100).filter(|&p| scope.push(&sine(1000.0, p * 48 + 1, 48)).is_some()).count();
+        assert_eq!(sweeps, 3, "{} sweeps in 100 ms", sweeps);
+    }
+}
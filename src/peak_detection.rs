@@ -0,0 +1,197 @@
+//! Spectral peak picking with sub-bin interpolation
+//! Works on the raw FFT magnitude spectrum (dB per bin), so the precision is not
+//! limited by bar spacing or by the bin width
+
+use serde::{Serialize, Deserialize};
+
+/// A bin only counts as a peak if it is the loudest within this many bins either
+/// side, which keeps window side lobes next to a strong tone out of the list
+const PEAK_NEIGHBOURHOOD_BINS: usize = 3;
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub enum PeakInterpolation {
+    Parabolic,  // Parabola through the linear magnitudes of the peak and its neighbours
+    Gaussian,   // Parabola through the dB values: exact for Gaussian windows, close for Hann
+}
+
+impl PeakInterpolation {
+    /// All interpolation methods, in the order shown in the settings UI
+    pub const ALL: [PeakInterpolation; 2] = [
+        PeakInterpolation::Parabolic,
+        PeakInterpolation::Gaussian,
+    ];
+
+    /// Human readable name for the settings UI
+    pub fn label(&self) -> &'static str {
+        match self {
+            PeakInterpolation::Parabolic => "Parabolic",
+            PeakInterpolation::Gaussian => "Gaussian",
+        }
+    }
+
+    /// Fractional bin offset (-0.5 ..= 0.5) and level of the true peak around the
+    /// local maximum `centre`, given its left and right neighbours (all dB)
+    pub fn refine(&self, left: f32, centre: f32, right: f32) -> (f64, f32) {
+        let (a, b, c) = match self {
+            PeakInterpolation::Parabolic => (db_to_amplitude(left), db_to_amplitude(centre), db_to_amplitude(right)),
+            PeakInterpolation::Gaussian => (left as f64, centre as f64, right as f64),
+        };
+
+        let denominator = a - 2.0 * b + c;
+        if denominator.abs() < 1e-12 {
+            return (0.0, centre);
+        }
+        let offset = (0.5 * (a - c) / denominator).clamp(-0.5, 0.5);
+        let vertex = b - 0.25 * (a - c) * offset;
+
+        let level_db = match self {
+            PeakInterpolation::Parabolic => amplitude_to_db(vertex),
+            PeakInterpolation::Gaussian => vertex as f32,
+        };
+        (offset, level_db)
+    }
+}
+
+/// One interpolated spectral peak
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SpectralPeak {
+    pub frequency_hz: f64,
+    pub level_db: f32,
+}
+
+/// Up to `max_count` peaks above `min_db`, loudest first
+pub fn find_peaks(
+    spectrum_db: &[f32],
+    freq_resolution: f64,
+    method: PeakInterpolation,
+    min_db: f32,
+    max_count: usize,
+) -> Vec<SpectralPeak> {
+    let mut peaks: Vec<SpectralPeak> = (1..spectrum_db.len().saturating_sub(1))
+        .filter(|&bin| spectrum_db[bin] >= min_db && is_local_maximum(spectrum_db, bin))
+        .map(|bin| refine_peak(spectrum_db, bin, freq_resolution, method))
+        .collect();
+
+    peaks.sort_by(|a, b| b.level_db.total_cmp(&a.level_db));
+    peaks.truncate(max_count);
+    peaks
+}
+
+/// Loudest peak whose bin lies within `low_hz ..= high_hz`
+pub fn strongest_peak_in(
+    spectrum_db: &[f32],
+    freq_resolution: f64,
+    method: PeakInterpolation,
+    low_hz: f64,
+    high_hz: f64,
+) -> Option<SpectralPeak> {
+    if spectrum_db.len() < 3 || freq_resolution <= 0.0 {
+        return None;
+    }
+    let last = spectrum_db.len() - 2;
+    let first_bin = ((low_hz / freq_resolution).floor().max(1.0) as usize).min(last);
+    let last_bin = ((high_hz / freq_resolution).ceil().max(1.0) as usize).min(last);
+
+    let bin = (first_bin..=last_bin).max_by(|&a, &b| spectrum_db[a].total_cmp(&spectrum_db[b]))?;
+    Some(refine_peak(spectrum_db, bin, freq_resolution, method))
+}
+
+fn is_local_maximum(spectrum_db: &[f32], bin: usize) -> bool {
+    let start = bin.saturating_sub(PEAK_NEIGHBOURHOOD_BINS);
+    let end = (bin + PEAK_NEIGHBOURHOOD_BINS).min(spectrum_db.len() - 1);
+    let level = spectrum_db[bin];
+
+    // Strictly louder than the bins before it, at least as loud as those after
+    // (a flat top is reported once, at its first bin)
+    spectrum_db[start..bin].iter().all(|&v| v < level)
+        && spectrum_db[bin + 1..=end].iter().all(|&v| v <= level)
+}
+
+fn refine_peak(spectrum_db: &[f32], bin: usize, freq_resolution: f64, method: PeakInterpolation) -> SpectralPeak {
+    let (offset, level_db) = method.refine(spectrum_db[bin - 1], spectrum_db[bin], spectrum_db[bin + 1]);
+    SpectralPeak {
+        frequency_hz: (bin as f64 + offset) * freq_resolution,
+        level_db,
+    }
+}
+
+fn db_to_amplitude(db: f32) -> f64 {
+    10f64.powf(db as f64 / 20.0)
+}
+
+fn amplitude_to_db(amplitude: f64) -> f32 {
+    (20.0 * (amplitude + 1e-12).log10()) as f32
+}
+
+// =============== Tests ==================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fft_processor::{FFTConfig, FFTProcessor};
+
+    /// Raw spectrum of a steady sine through a real processor (Hann, 2048 @ 48 kHz)
+    fn tone_spectrum(freq: f32, amplitude: f32) -> (Vec<f32>, f64) {
+        let config = FFTConfig { sample_rate: 48000, fft_size: 2048, ..FFTConfig::default() };
+        let mut processor = FFTProcessor::new(config);
+        let samples: Vec<f32> = (0..8192)
+            .map(|i| amplitude * (2.0 * std::f32::consts::PI * freq * i as f32 / 48000.0).sin())
+            .collect();
+        processor.process(&samples);
+
+        (processor.spectrum().to_vec(), processor.frequency_resolution())
+    }
+
+    #[test]
+    fn test_gaussian_is_exact_for_gaussian_peak() {
+        // dB values of a Gaussian are a parabola: peak at bin 10.3, 0 dB
+        let spectrum: Vec<f32> = (0..20).map(|bin| -3.0 * (bin as f32 - 10.3).powi(2)).collect();
+        let peak = strongest_peak_in(&spectrum, 1.0, PeakInterpolation::Gaussian, 0.0, 20.0).unwrap();
+
+        assert!((peak.frequency_hz - 10.3).abs() < 1e-4, "f = {}", peak.frequency_hz);
+        assert!(peak.level_db.abs() < 1e-3, "L = {}", peak.level_db);
+    }
+
+    #[test]
+    fn test_sub_bin_accuracy_on_real_spectrum() {
+        // 15.734 kHz sits ~0.3 bins off the 23.4 Hz grid
+        let (spectrum, resolution) = tone_spectrum(15734.0, 0.5);
+        let gaussian = strongest_peak_in(&spectrum, resolution, PeakInterpolation::Gaussian, 15500.0, 16000.0).unwrap();
+        let parabolic = strongest_peak_in(&spectrum, resolution, PeakInterpolation::Parabolic, 15500.0, 16000.0).unwrap();
+
+        assert!((gaussian.frequency_hz - 15734.0).abs() < 0.5, "gaussian f = {}", gaussian.frequency_hz);
+        assert!((parabolic.frequency_hz - 15734.0).abs() < 3.0, "parabolic f = {}", parabolic.frequency_hz);
+    }
+
+    #[test]
+    fn test_interpolated_level_removes_scalloping() {
+        // Hann loses 1.4 dB between bins; the interpolated level recovers most of it
+        let resolution = 48000.0 / 2048.0;
+        let (on_bin, _) = tone_spectrum((100.0 * resolution) as f32, 0.5);
+        let (half_bin, _) = tone_spectrum((100.5 * resolution) as f32, 0.5);
+
+        let peak = |spectrum: &[f32]| {
+            strongest_peak_in(spectrum, resolution, PeakInterpolation::Gaussian, 0.0, 24000.0).unwrap().level_db
+        };
+        let loudest_bin = |spectrum: &[f32]| spectrum.iter().copied().fold(f32::MIN, f32::max);
+        assert!(loudest_bin(&on_bin) - loudest_bin(&half_bin) > 1.0);
+        assert!((peak(&on_bin) - peak(&half_bin)).abs() < 0.5, "{} vs {}", peak(&on_bin), peak(&half_bin));
+    }
+
+    #[test]
+    fn test_find_peaks_orders_and_limits() {
+        let mut spectrum = vec![-100.0f32; 64];
+        for (bin, level) in [(10, -20.0), (30, -5.0), (50, -40.0)] {
+            spectrum[bin - 1] = level - 6.0;
+            spectrum[bin] = level;
+            spectrum[bin + 1] = level - 6.0;
+        }
+
+        let peaks = find_peaks(&spectrum, 1.0, PeakInterpolation::Gaussian, -60.0, 2);
+        assert_eq!(peaks.len(), 2);
+        assert!((peaks[0].frequency_hz - 30.0).abs() < 1e-6);
+        assert!((peaks[1].frequency_hz - 10.0).abs() < 1e-6);
+
+        // The -40 dB peak is below the floor
+        assert_eq!(find_peaks(&spectrum, 1.0, PeakInterpolation::Gaussian, -30.0, 10).len(), 2);
+    }
+}
@@ -0,0 +1,105 @@
+//! Musical pitch helpers: nearest equal-tempered note and cents deviation
+//! Notes are numbered as MIDI keys (A4 = 69, middle C = C4 = 60)
+
+/// Concert pitch reference
+pub const DEFAULT_A4_HZ: f32 = 440.0;
+/// MIDI number of A4
+const A4_MIDI: i32 = 69;
+/// Piano range drawn on the keyboard axis (A0 - C8)
+pub const PIANO_LOWEST_MIDI: i32 = 21;
+pub const PIANO_HIGHEST_MIDI: i32 = 108;
+
+/// Pitch-class names, C first (sharps only)
+pub const NOTE_NAMES: [&str; 12] = ["C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B"];
+
+/// Nearest note to a frequency
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Note {
+    pub midi: i32,
+    pub cents: f32,     // Deviation from the note, -50 .. +50
+}
+
+impl Note {
+    /// Nearest note for `freq_hz`, None for non-positive frequencies
+    pub fn from_frequency(freq_hz: f64, a4_hz: f32) -> Option<Self> {
+        if freq_hz <= 0.0 || a4_hz <= 0.0 {
+            return None;
+        }
+        let semitones = 12.0 * (freq_hz / a4_hz as f64).log2();
+        let nearest = semitones.round();
+
+        Some(Self {
+            midi: A4_MIDI + nearest as i32,
+            cents: ((semitones - nearest) * 100.0) as f32,
+        })
+    }
+
+    /// Name with octave: "A4", "C#5", "F#-1"
+    pub fn name(&self) -> String {
+        format!("{}{}", NOTE_NAMES[self.midi.rem_euclid(12) as usize], self.octave())
+    }
+
+    /// Scientific pitch octave (changes at C)
+    pub fn octave(&self) -> i32 {
+        self.midi.div_euclid(12) - 1
+    }
+}
+
+/// Frequency of a (possibly fractional) MIDI note number
+pub fn midi_frequency(midi: f64, a4_hz: f32) -> f64 {
+    a4_hz as f64 * 2f64.powf((midi - A4_MIDI as f64) / 12.0)
+}
+
+/// Sharps and flats (the black keys on a piano)
+pub fn is_black_key(midi: i32) -> bool {
+    matches!(midi.rem_euclid(12), 1 | 3 | 6 | 8 | 10)
+}
+
+// =============== Tests ==================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reference_notes() {
+        let a4 = Note::from_frequency(440.0, DEFAULT_A4_HZ).unwrap();
+        assert_eq!(a4.name(), "A4");
+        assert!(a4.cents.abs() < 1e-3);
+
+        let middle_c = Note::from_frequency(261.6256, DEFAULT_A4_HZ).unwrap();
+        assert_eq!(middle_c.name(), "C4");
+        assert_eq!(middle_c.midi, 60);
+
+        let low_e = Note::from_frequency(41.2034, DEFAULT_A4_HZ).unwrap();
+        assert_eq!(low_e.name(), "E1");
+    }
+
+    #[test]
+    fn test_cents_deviation() {
+        // A quarter tone sharp of A4 rounds to A4 at +50 cents, slightly less lands at +49
+        let sharp = Note::from_frequency(midi_frequency(69.49, DEFAULT_A4_HZ), DEFAULT_A4_HZ).unwrap();
+        assert_eq!(sharp.name(), "A4");
+        assert!((sharp.cents - 49.0).abs() < 1e-2);
+
+        let flat = Note::from_frequency(midi_frequency(71.8, DEFAULT_A4_HZ), DEFAULT_A4_HZ).unwrap();
+        assert_eq!(flat.name(), "C5");
+        assert!((flat.cents + 20.0).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_custom_reference() {
+        // Baroque pitch: A4 = 415 Hz, so 440 Hz is a semitone up
+        let note = Note::from_frequency(440.0, 415.0).unwrap();
+        assert_eq!(note.name(), "A#4");
+        assert!(note.cents.abs() < 2.0);
+
+        assert!(Note::from_frequency(0.0, DEFAULT_A4_HZ).is_none());
+    }
+
+    #[test]
+    fn test_black_keys() {
+        let black: Vec<i32> = (60..72).filter(|&m| is_black_key(m)).collect();
+        assert_eq!(black, vec![61, 63, 66, 68, 70]);
+        assert_eq!(Note { midi: 0, cents: 0.0 }.name(), "C-1");
+    }
+}
@@ -0,0 +1,177 @@
+//! Reference spectra for A/B comparison
+//! A reference is a frozen set of (frequency, level) points, so it can be drawn on
+//! any bar count, frequency scale or FFT size by interpolating in log frequency
+
+use serde::{Serialize, Deserialize};
+
+use crate::shared_state::SILENCE_DB;
+
+/// Vertical range of the difference curve (± dB around the centre line)
+pub const DIFFERENCE_RANGE_DB: f32 = 24.0;
+
+/// Which spectrum a new reference is frozen from
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub enum ReferenceSource {
+    Live,       // The bars as currently displayed
+    Average,    // The long-term average spectrum (LTAS) since the last trace reset
+}
+
+impl ReferenceSource {
+    /// All capture sources, in the order shown in the settings UI
+    pub const ALL: [ReferenceSource; 2] = [
+        ReferenceSource::Live,
+        ReferenceSource::Average,
+    ];
+
+    /// Human readable name for the settings UI
+    pub fn label(&self) -> &'static str {
+        match self {
+            ReferenceSource::Live => "Live Spectrum",
+            ReferenceSource::Average => "Long-Term Average",
+        }
+    }
+}
+
+/// A named, frozen spectrum saved next to the user presets
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct ReferenceTrace {
+    pub name: String,
+    /// Bar centre frequencies (Hz, ascending) and their levels (dB)
+    pub frequencies_hz: Vec<f32>,
+    pub levels_db: Vec<f32>,
+}
+
+impl ReferenceTrace {
+    /// Freeze `levels_db`, one value per bar centred at `frequencies_hz`
+    /// Non-positive frequencies (DC) are dropped
+    pub fn capture(name: &str, frequencies_hz: &[f64], levels_db: &[f32]) -> Self {
+        let (frequencies_hz, levels_db) = frequencies_hz
+            .iter()
+            .zip(levels_db)
+            .filter(|(&freq, _)| freq > 0.0)
+            .map(|(&freq, &db)| (freq as f32, db))
+            .unzip();
+
+        Self { name: name.trim().to_string(), frequencies_hz, levels_db }
+    }
+
+    /// Check a trace loaded from disk: one level per frequency, and positive finite
+    /// frequencies in ascending order (`level_at` relies on both)
+    pub fn validate(&self) -> Result<(), String> {
+        if self.frequencies_hz.len() != self.levels_db.len() {
+            return Err(format!(
+                "{} frequencies but {} levels",
+                self.frequencies_hz.len(),
+                self.levels_db.len()
+            ));
+        }
+        if let Some(freq) = self.frequencies_hz.iter().find(|f| !f.is_finite() || **f <= 0.0) {
+            return Err(format!("invalid frequency {}", freq));
+        }
+        if self.frequencies_hz.windows(2).any(|pair| pair[1] < pair[0]) {
+            return Err("frequencies are not in ascending order".to_string());
+        }
+        Ok(())
+    }
+
+    /// Level at `freq_hz`, interpolated linearly in log frequency
+    /// None outside the captured range
+    pub fn level_at(&self, freq_hz: f64) -> Option<f32> {
+        let freqs = &self.frequencies_hz;
+        let (&first, &last) = (freqs.first()?, freqs.last()?);
+        if freq_hz < first as f64 || freq_hz > last as f64 {
+            return None;
+        }
+        if freqs.len() == 1 {
+            return Some(self.levels_db[0]);
+        }
+
+        let upper = freqs.partition_point(|&f| (f as f64) < freq_hz).clamp(1, freqs.len() - 1);
+        let (lo, hi) = (freqs[upper - 1] as f64, freqs[upper] as f64);
+        let frac = if hi > lo { ((freq_hz / lo).ln() / (hi / lo).ln()) as f32 } else { 0.0 };
+
+        let (a, b) = (self.levels_db[upper - 1], self.levels_db[upper]);
+        Some(a + (b - a) * frac)
+    }
+
+    /// Reference level at each of `centers` (None where it was not captured)
+    pub fn resample(&self, centers: &[f64]) -> Vec<Option<f32>> {
+        centers.iter().map(|&freq| self.level_at(freq)).collect()
+    }
+}
+
+/// Live minus reference (dB) per bar; None where either side is missing or silent
+pub fn difference(live_db: &[f32], reference_db: &[Option<f32>]) -> Vec<Option<f32>> {
+    live_db
+        .iter()
+        .zip(reference_db)
+        .map(|(&live, &reference)| match reference {
+            Some(reference) if live > SILENCE_DB && reference > SILENCE_DB => Some(live - reference),
+            _ => None,
+        })
+        .collect()
+}
+
+// =============== Tests ==================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reference() -> ReferenceTrace {
+        ReferenceTrace::capture("Mix A", &[0.0, 100.0, 1000.0, 10000.0], &[-10.0, -20.0, -30.0, -60.0])
+    }
+
+    #[test]
+    fn test_capture_drops_dc() {
+        let trace = reference();
+        assert_eq!(trace.frequencies_hz, vec![100.0, 1000.0, 10000.0]);
+        assert_eq!(trace.levels_db, vec![-20.0, -30.0, -60.0]);
+    }
+
+    #[test]
+    fn test_log_frequency_interpolation() {
+        let trace = reference();
+        assert_eq!(trace.level_at(1000.0), Some(-30.0));
+
+        // 316 Hz is half way between 100 Hz and 1 kHz on a log axis
+        let mid = trace.level_at(316.227_77).unwrap();
+        assert!((mid + 25.0).abs() < 1e-3, "mid = {}", mid);
+
+        assert_eq!(trace.level_at(50.0), None);
+        assert_eq!(trace.level_at(20000.0), None);
+    }
+
+    #[test]
+    fn test_resample_to_new_layout_and_difference() {
+        let trace = reference();
+        let resampled = trace.resample(&[50.0, 100.0, 10000.0]);
+        assert_eq!(resampled, vec![None, Some(-20.0), Some(-60.0)]);
+
+        let diff = difference(&[-30.0, -14.0, SILENCE_DB], &resampled);
+        assert_eq!(diff, vec![None, Some(6.0), None]);
+    }
+
+    #[test]
+    fn test_validate_rejects_malformed_files() {
+        assert!(reference().validate().is_ok());
+
+        let mut short = reference();
+        short.levels_db.pop();
+        assert!(short.validate().is_err());
+
+        let mut unsorted = reference();
+        unsorted.frequencies_hz.swap(0, 2);
+        assert!(unsorted.validate().is_err());
+
+        let mut infinite = reference();
+        infinite.frequencies_hz[2] = f32::INFINITY;
+        assert!(infinite.validate().is_err());
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let trace = reference();
+        let json = serde_json::to_string(&trace).unwrap();
+        assert_eq!(serde_json::from_str::<ReferenceTrace>(&json).unwrap(), trace);
+    }
+}
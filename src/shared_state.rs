@@ -1,5 +1,25 @@
 use std::time::{Duration, Instant};
-use crate::fft_config::FFTInfo;
+use crate::fft_config::{AnalysisMode, AnalysisOverlap, FFTInfo, DEFAULT_FFT_SIZE};
+use crate::auto_range::{AutoRangeSettings, DbRange};
+use crate::ballistics::MeterBallistics;
+use crate::fft_processor::BinAggregation;
+use crate::frequency_scale::{FrequencyRange, FrequencyScale, DEFAULT_KNEE_FREQ, SCALE_MAX_FREQ, SCALE_MIN_FREQ};
+use crate::chroma::{MusicalKey, PITCH_CLASSES};
+use crate::colormap::Colormap;
+use crate::constant_q::CqtResolution;
+use crate::level_meter::ChannelLevel;
+use crate::loudness::LoudnessReadings;
+use crate::octave_bands::OctaveFraction;
+use crate::oscilloscope::{ScopeStatus, ScopeTrace, TriggerEdge, TriggerMode, DEFAULT_MS_PER_DIV};
+use crate::peak_detection::PeakInterpolation;
+use crate::pitch::DEFAULT_A4_HZ;
+use crate::tempo::TempoReadings;
+use crate::reference::ReferenceTrace;
+use crate::spectrum_traces::SpectrumTraces;
+use crate::spectrogram::{SpectrogramHistory, DEFAULT_HISTORY_SECS};
+use crate::vectorscope::{Phosphor, PhosphorDecay, DEFAULT_PERSISTENCE_SECS};
+use crate::weighting::FrequencyWeighting;
+use crate::windowing::{WindowFunction, DEFAULT_KAISER_BETA};
 use serde::{Serialize, Deserialize};
 use std::fs;
 use std::path::PathBuf;
@@ -19,8 +39,59 @@ pub enum VisualMode {
     SegmentedBars,
     LineSpectrum,
     Oscilloscope,
+    Spectrogram,    // Scrolling time-frequency history (waterfall)
+    LevelMeter,     // Per-channel peak/RMS meters
+    Vectorscope,    // L/R goniometer with phase correlation meter
+    Chroma,         // 12 pitch classes with the running key estimate
+    XyScope,        // One channel against another (Lissajous) with phosphor persistence
 }
 
+impl VisualMode {
+    /// Modes that draw the FFT bars (and use the analysis settings)
+    pub fn is_spectral(&self) -> bool {
+        !matches!(
+            self,
+            VisualMode::Oscilloscope | VisualMode::LevelMeter | VisualMode::Vectorscope | VisualMode::Chroma | VisualMode::XyScope
+        )
+    }
+}
+
+/// How the two input channels are analysed and drawn
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub enum StereoMode {
+    Mono,       // Channels averaged before a single FFT
+    Overlay,    // L and R spectra drawn on top of each other in two colours
+    Mirrored,   // L points up, R points down from the centre line
+    MidSide,    // Mid (L+R) and Side (L-R) overlaid: phase-cancelled content lives in Side
+}
+
+impl StereoMode {
+    /// All stereo modes, in the order shown in the settings UI
+    pub const ALL: [StereoMode; 4] = [
+        StereoMode::Mono,
+        StereoMode::Overlay,
+        StereoMode::Mirrored,
+        StereoMode::MidSide,
+    ];
+
+    /// Human readable name for the settings UI
+    pub fn label(&self) -> &'static str {
+        match self {
+            StereoMode::Mono => "Mono (Downmix)",
+            StereoMode::Overlay => "L/R Overlay",
+            StereoMode::Mirrored => "L/R Mirrored",
+            StereoMode::MidSide => "Mid/Side",
+        }
+    }
+
+    /// Short names of the two analysed channels
+    pub fn channel_names(&self) -> [&'static str; 2] {
+        match self {
+            StereoMode::MidSide => ["M", "S"],
+            _ => ["L", "R"],
+        }
+    }
+}
 
 #[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
 pub enum MediaDisplayMode {
@@ -102,7 +173,26 @@ pub enum ColorRef {
 // Visual Profile (Windowing, Bars, and Visualization Colors)
 // =====================================================================================
 
+/// Reads either a `BinAggregation` or the old `use_peak_aggregation` bool
+/// (true was Peak, false the per-bin average now called Mean Power)
+fn deserialize_aggregation<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<BinAggregation, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Stored {
+        Current(BinAggregation),
+        Legacy(bool),
+    }
+
+    Ok(match Stored::deserialize(deserializer)? {
+        Stored::Current(aggregation) => aggregation,
+        Stored::Legacy(true) => BinAggregation::Peak,
+        Stored::Legacy(false) => BinAggregation::MeanPower,
+    })
+}
+
+// Missing fields fall back to Default so presets saved by older versions still load
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(default)]
 pub struct VisualProfile {
     pub name: String,
 
@@ -114,8 +204,32 @@ pub struct VisualProfile {
     pub segment_height_px: f32,
     pub segment_gap_px: f32,
     pub inverted_spectrum: bool,
+    pub stereo_mode: StereoMode,
     pub fill_peaks: bool,
     pub show_peaks: bool,
+    /// Flash the background on detected beats
+    pub beat_flash: bool,
+
+    // Spectrogram
+    pub spectrogram_history_secs: f32,
+    pub spectrogram_colormap: Colormap,
+
+    // Vectorscope
+    pub vectorscope_persistence_secs: f32,
+
+    // XY scope: channel indices (0-based) for the horizontal and vertical axes
+    pub xy_x_channel: usize,
+    pub xy_y_channel: usize,
+    pub xy_persistence_secs: f32,
+    pub xy_decay: PhosphorDecay,
+
+    // Oscilloscope
+    pub scope_ms_per_div: f32,
+    pub scope_trigger_mode: TriggerMode,
+    pub scope_trigger_edge: TriggerEdge,
+    /// Trigger level as a sample value (-1 .. 1)
+    pub scope_trigger_level: f32,
+    pub scope_holdoff_ms: f32,
 
     // Font Selection
     pub overlay_font: ThemeFont,
@@ -126,7 +240,34 @@ pub struct VisualProfile {
     pub release_time_ms: f32,
     pub peak_hold_time_ms: f32,
     pub peak_release_time_ms: f32,
-    pub use_peak_aggregation: bool,
+    /// Older profiles stored `use_peak_aggregation: bool` instead
+    #[serde(alias = "use_peak_aggregation", deserialize_with = "deserialize_aggregation")]
+    pub aggregation: BinAggregation,
+
+    // === Analysis ===
+    /// FFT window length in samples (512 - 32768, power of two)
+    pub fft_size: usize,
+    /// Overlap between consecutive FFT frames (sets the update rate)
+    pub overlap: AnalysisOverlap,
+    pub window_function: WindowFunction,
+    pub kaiser_beta: f32,
+    pub frequency_scale: FrequencyScale,
+    /// Linear/log crossover of the hybrid scale (Hz)
+    pub scale_knee_hz: f32,
+    /// Frequencies at the left and right edge of the display (Hz, limited to Nyquist)
+    pub min_freq_hz: f32,
+    pub max_freq_hz: f32,
+    /// Zoomed view of the frequency axis (mouse wheel / Shift-drag); not saved
+    #[serde(skip)]
+    pub frequency_zoom: Option<FrequencyRange>,
+    /// Octave-band and constant-Q modes replace `num_bars` and the scale with
+    /// their own fixed bands
+    pub analysis_mode: AnalysisMode,
+    pub octave_fraction: OctaveFraction,
+    pub cqt_resolution: CqtResolution,
+    pub weighting: FrequencyWeighting,
+    /// Spectral tilt around 1 kHz (+3 dB/oct flattens pink noise)
+    pub tilt_db_per_octave: f32,
 
     // === Color Link ===
     pub color_link: ColorRef,
@@ -146,8 +287,22 @@ impl Default for VisualProfile {
             segment_height_px: 4.0,
             segment_gap_px: 2.0,
             inverted_spectrum: false,
+            stereo_mode: StereoMode::Mono,
             fill_peaks: false,
             show_peaks: true,
+            beat_flash: false,
+            spectrogram_history_secs: DEFAULT_HISTORY_SECS,
+            spectrogram_colormap: Colormap::Viridis,
+            vectorscope_persistence_secs: DEFAULT_PERSISTENCE_SECS,
+            xy_x_channel: 0,
+            xy_y_channel: 1,
+            xy_persistence_secs: DEFAULT_PERSISTENCE_SECS,
+            xy_decay: PhosphorDecay::Exponential,
+            scope_ms_per_div: DEFAULT_MS_PER_DIV,
+            scope_trigger_mode: TriggerMode::Auto,
+            scope_trigger_edge: TriggerEdge::Rising,
+            scope_trigger_level: 0.0,
+            scope_holdoff_ms: 0.0,
             overlay_font: ThemeFont::Medium,
 
             sensitivity: 1.0,
@@ -155,7 +310,22 @@ impl Default for VisualProfile {
             release_time_ms: 200.0,
             peak_hold_time_ms: 1000.0,
             peak_release_time_ms: 1500.0,
-            use_peak_aggregation: true,
+            aggregation: BinAggregation::Peak,
+
+            fft_size: DEFAULT_FFT_SIZE,
+            overlap: AnalysisOverlap::Half,
+            window_function: WindowFunction::Hann,
+            kaiser_beta: DEFAULT_KAISER_BETA,
+            frequency_scale: FrequencyScale::Hybrid,
+            scale_knee_hz: DEFAULT_KNEE_FREQ,
+            min_freq_hz: SCALE_MIN_FREQ as f32,
+            max_freq_hz: SCALE_MAX_FREQ as f32,
+            frequency_zoom: None,
+            analysis_mode: AnalysisMode::Spectrum,
+            octave_fraction: OctaveFraction::Third,
+            cqt_resolution: CqtResolution::HalfSemitone,
+            weighting: FrequencyWeighting::Z,
+            tilt_db_per_octave: 0.0,
 
             color_link: ColorRef::Preset("Default".to_string()),
 
@@ -169,6 +339,18 @@ impl VisualProfile {
     pub fn built_in() -> Vec<Self> {
         crate::presets::built_in_visuals()
     }
+
+    /// The profile's frequency bounds at `sample_rate` (never past Nyquist)
+    pub fn frequency_bounds(&self, sample_rate: u32) -> FrequencyRange {
+        FrequencyRange { min_hz: self.min_freq_hz as f64, max_hz: self.max_freq_hz as f64 }
+            .clamped(FrequencyRange::full(sample_rate))
+    }
+
+    /// Displayed frequency range: the zoomed view inside the bounds, or the bounds themselves
+    pub fn frequency_range(&self, sample_rate: u32) -> FrequencyRange {
+        let bounds = self.frequency_bounds(sample_rate);
+        self.frequency_zoom.map_or(bounds, |zoom| zoom.clamped(bounds))
+    }
 }
 
 // ====================================================================================
@@ -195,6 +377,20 @@ pub struct SharedState{
     /// Flag: GUI requests a hardware scan (handled by main thread
     pub refresh_devices_requested: bool,
 
+    // === Loudness (EBU R128) ===
+    /// Latest readings from the metering thread
+    pub loudness: LoudnessReadings,
+    /// Flag: GUI requests a fresh measurement (handled by metering thread)
+    pub loudness_reset_requested: bool,
+    /// Flag: GUI requests clearing the level meter peak holds and clip latches
+    pub levels_reset_requested: bool,
+    /// Flag: GUI requests a fresh key estimate (new track, or the reset button)
+    pub key_reset_requested: bool,
+    /// Flag: GUI requests clearing the max/min hold and LTAS traces
+    pub traces_reset_requested: bool,
+    /// Flag: GUI re-arms the oscilloscope trigger (single sweep mode)
+    pub scope_rearm_requested: bool,
+
     // === Media Player State ===
     /// Curreently playing track info
     pub media_info: Option<crate::media::MediaTrackInfo>,
@@ -205,6 +401,8 @@ pub struct SharedState{
     /// Loaded from JSON file at startup
     pub user_color_presets: Vec<ColorProfile>,
     pub user_visual_presets: Vec<VisualProfile>,
+    /// Saved reference spectra for A/B comparison
+    pub user_references: Vec<ReferenceTrace>,
 
 }
 
@@ -214,8 +412,10 @@ impl SharedState {
 
         let user_color_presets = AppConfig::load_user_color_presets();
         let user_visual_presets = AppConfig::load_user_visual_presets();
+        let user_references = AppConfig::load_user_references();
         tracing::info!("[State] Loaded {} user color presets", user_color_presets.len());
         tracing::info!("[State] Loaded {} user visual presets", user_visual_presets.len());
+        tracing::info!("[State] Loaded {} reference traces", user_references.len());
 
         Self {
             visualization: VisualizationData::new(config.profile.num_bars),
@@ -224,10 +424,17 @@ impl SharedState {
             audio_devices: Vec::new(),
             device_changed: false,
             refresh_devices_requested: false,
+            loudness: LoudnessReadings::default(),
+            loudness_reset_requested: false,
+            levels_reset_requested: false,
+            key_reset_requested: false,
+            traces_reset_requested: false,
+            scope_rearm_requested: false,
             media_info: None,
             last_media_update: None,
             user_color_presets,
             user_visual_presets,
+            user_references,
         }
     }
 }
@@ -239,11 +446,48 @@ pub struct VisualizationData {
     pub bars: Vec<f32>,
 
     /// Peak indicator heights in dB
+    /// In stereo modes these hold the louder of the two channels per bar
     pub peaks: Vec<f32>,
 
+    /// Per-channel bars and peaks ([L, R] or [M, S]); empty in mono mode
+    pub channel_bars: Vec<Vec<f32>>,
+    pub channel_peaks: Vec<Vec<f32>>,
+
+    /// Raw FFT magnitude spectrum (dB per bin, unweighted) and its bin width
+    pub spectrum: Vec<f32>,
+    pub spectrum_resolution_hz: f64,
+
+    /// Time-frequency history of the bars for spectrogram mode
+    pub spectrogram: SpectrogramHistory,
+
+    /// Max-hold, min-hold and long-term average of the bars since the last reset
+    pub traces: SpectrumTraces,
+
+    /// Per-channel level meter readings (written by the metering thread)
+    pub levels: Vec<ChannelLevel>,
+
+    /// Latest raw L/R (or X/Y) sample pairs and their fading trace (vectorscope and XY modes)
+    pub stereo_waveform: Vec<[f32; 2]>,
+    pub phosphor: Phosphor,
+
+    /// Phase correlation of L/R, -1 to +1 (written by the metering thread)
+    pub correlation: f32,
+
+    /// Tempo estimate and latest beat (written by the metering thread)
+    pub tempo: TempoReadings,
+
+    /// Smoothed pitch-class energy (C first, 0 - 1) and the running key estimate
+    pub chroma: [f32; PITCH_CLASSES],
+    pub key: Option<MusicalKey>,
+
     /// Raw Audio wavefor for oscilloscope mode 
-    // We keep a small buffer for drawing
-    pub waveform: Vec<f32>,
+    // Latest complete sweep (one screen width, trigger one division in), as raw
+    // samples or a min/max envelope for long timebases
+    pub waveform: ScopeTrace,
+    pub scope_status: ScopeStatus,
+
+    /// Auto-ranged dB span for the spectrum (None while auto gain is off)
+    pub auto_range: Option<DbRange>,
 
     /// When this data was last updated
     pub timestamp: Instant,
@@ -254,7 +498,22 @@ impl VisualizationData {
         Self {
             bars: vec![SILENCE_DB; num_bars],
             peaks: vec![SILENCE_DB; num_bars],
-            waveform: vec![0.0; 2048],
+            channel_bars: Vec::new(),
+            channel_peaks: Vec::new(),
+            spectrum: Vec::new(),
+            spectrum_resolution_hz: 0.0,
+            spectrogram: SpectrogramHistory::default(),
+            traces: SpectrumTraces::default(),
+            levels: Vec::new(),
+            stereo_waveform: Vec::new(),
+            phosphor: Phosphor::default(),
+            correlation: 0.0,
+            tempo: TempoReadings::default(),
+            chroma: [0.0; PITCH_CLASSES],
+            key: None,
+            waveform: ScopeTrace::default(),
+            scope_status: ScopeStatus::Waiting,
+            auto_range: None,
             timestamp: Instant::now(),
         }
     }
@@ -274,7 +533,9 @@ pub struct PerformanceStats {
 
 // ==== Configuration ====
 
+// Missing fields fall back to Default so configs saved by older versions still load
 #[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AppConfig {
     pub profile: VisualProfile,
 
@@ -296,14 +557,53 @@ pub struct AppConfig {
 
     pub show_stats: bool,
 
+    /// Show the EBU R128 loudness overlay
+    pub show_loudness: bool,
+
+    /// Ballistics of the level meter mode
+    pub meter_ballistics: MeterBallistics,
+
     pub inspector_enabled: bool,    
 
+    /// Tuning reference for note names in the inspector and piano axis
+    pub a4_reference_hz: f32,
+
+    /// Draw a piano keyboard along the bottom of the spectrum
+    pub show_piano_axis: bool,
+
+    /// Sub-bin peak estimation used by the inspector and the peak list
+    pub peak_interpolation: PeakInterpolation,
+
+    /// Show the strongest spectral peaks as an overlay list
+    pub show_peak_list: bool,
+    pub peak_list_count: usize,
+
+    /// Show the detected musical key next to the now-playing overlay
+    pub show_key: bool,
+
+    /// Spectrum traces drawn over the live bars
+    pub show_max_hold: bool,
+    pub show_min_hold: bool,
+    pub show_ltas: bool,
+    /// Clear the traces when the media monitor reports a new track
+    pub traces_reset_on_track_change: bool,
+
+    /// Reference spectrum overlaid on the live view (by name), and the live - reference curve
+    pub active_reference: Option<String>,
+    pub show_reference_difference: bool,
+
     /// Name of selected input device (default: "Default")
     pub selected_device: String,
 
     /// The lowest dB value to display (the "floor")
     pub noise_floor_db: f32,
 
+    /// Auto gain: the displayed range follows the programme instead of the noise floor and 0 dB
+    pub auto_gain: bool,
+    pub auto_gain_speed_secs: f32,
+    pub auto_gain_lowest_floor_db: f32,
+    pub auto_gain_highest_ceiling_db: f32,
+
     // === Media Settings ===
     pub media_display_mode: MediaDisplayMode,
     pub media_fade_duration_sec: f32,
@@ -329,9 +629,27 @@ impl Default for AppConfig {
             window_locked: false,
             window_decorations: false,
             inspector_enabled: true,
+            a4_reference_hz: DEFAULT_A4_HZ,
+            show_piano_axis: false,
+            peak_interpolation: PeakInterpolation::Gaussian,
+            show_peak_list: false,
+            peak_list_count: 5,
+            show_key: true,
+            show_max_hold: false,
+            show_min_hold: false,
+            show_ltas: false,
+            traces_reset_on_track_change: true,
+            active_reference: None,
+            show_reference_difference: false,
             show_stats: false,
+            show_loudness: false,
+            meter_ballistics: MeterBallistics::Ppm,
             selected_device: "Default".to_string(),
             noise_floor_db: -60.0,
+            auto_gain: false,
+            auto_gain_speed_secs: 3.0,
+            auto_gain_lowest_floor_db: -120.0,
+            auto_gain_highest_ceiling_db: 24.0,
             media_display_mode: MediaDisplayMode::FadeOnUpdate,
             media_fade_duration_sec: 5.0,
             beos_mode: false,
@@ -381,6 +699,15 @@ impl AppConfig {
         Self::default()
     }
 
+    /// Adaptation speed and limits for the auto gain tracker
+    pub fn auto_range_settings(&self) -> AutoRangeSettings {
+        AutoRangeSettings {
+            speed_secs: self.auto_gain_speed_secs,
+            lowest_floor_db: self.auto_gain_lowest_floor_db,
+            highest_ceiling_db: self.auto_gain_highest_ceiling_db,
+        }
+    }
+
     pub fn save(&self) {
         let path = Self::get_config_path();
         match serde_json::to_string_pretty(self) {
@@ -537,6 +864,63 @@ impl AppConfig {
         Ok(())
     }
 
+    pub fn load_user_references() -> Vec<ReferenceTrace> {
+        let mut references = Vec::new();
+        if let Some(proj_dirs) = ProjectDirs::from("","","BeSpec") {
+            let reference_dir = proj_dirs.data_dir().join("presets").join("references");
+            if reference_dir.exists() {
+                if let Ok(entries) = fs::read_dir(reference_dir) {
+                    for entry in entries.flatten() {
+                        let path = entry.path();
+                        if path.extension().is_some_and(|ext| ext == "json") {
+                            if let Ok(content) = fs::read_to_string(&path) {
+                                match serde_json::from_str::<ReferenceTrace>(&content) {
+                                    Ok(reference) => {
+                                        if let Err(e) = reference.validate() {
+                                            tracing::warn!("[Presets] Invalid reference in file {:?}: {}. Skipping.", path, e);
+                                        } else if references.iter().any(|r: &ReferenceTrace| r.name == reference.name) {
+                                            tracing::warn!("[Presets] Duplicate reference name '{}' in file {:?}. Skipping.", reference.name, path);
+                                        } else {
+                                            references.push(reference);
+                                        }
+                                    },
+                                    Err(e) => tracing::warn!("[Presets] Failed to parse {:?}: {}", path, e),
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        references
+    }
+
+    pub fn save_user_reference(reference: &ReferenceTrace) -> std::io::Result<()> {
+        if let Some(proj_dirs) = ProjectDirs::from("","","BeSpec") {
+            let reference_dir = proj_dirs.data_dir().join("presets").join("references");
+            fs::create_dir_all(&reference_dir)?;
+
+            let filename = format!("{}.json", Self::sanitize_filename(&reference.name));
+
+            let json = serde_json::to_string_pretty(reference)?;
+            fs::write(reference_dir.join(filename), json)?;
+        }
+        Ok(())
+    }
+
+    pub fn delete_user_reference(name: &str) -> std::io::Result<()> {
+        if let Some(proj_dirs) = ProjectDirs::from("","","BeSpec") {
+            let reference_dir = proj_dirs.data_dir().join("presets").join("references");
+            let filename = format!("{}.json", Self::sanitize_filename(name));
+            let path = reference_dir.join(filename);
+            if path.exists() {
+                fs::remove_file(path)?;
+                tracing::info!("[Presets] Deleted reference: {}", name);
+            }
+        }
+        Ok(())
+    }
+
     // Helper: Sanitize Filename to avoid duplicates / illegal chars
     fn sanitize_filename(name: &str) -> String {
         name.trim()
@@ -612,6 +996,21 @@ mod tests {
         assert_eq!(deserialized.sensitivity, 2.5);
     }
 
+    #[test]
+    fn test_legacy_peak_aggregation_flag() {
+        // Profiles saved before the aggregation modes stored a bool
+        let average: VisualProfile = serde_json::from_str(r#"{"name":"Old","use_peak_aggregation":false}"#)
+            .expect("Failed to deserialize");
+        assert_eq!(average.aggregation, BinAggregation::MeanPower);
+
+        let peak: VisualProfile = serde_json::from_str(r#"{"name":"Old","use_peak_aggregation":true}"#)
+            .expect("Failed to deserialize");
+        assert_eq!(peak.aggregation, BinAggregation::Peak);
+
+        let current: VisualProfile = serde_json::from_str(r#"{"aggregation":"Rms"}"#).expect("Failed to deserialize");
+        assert_eq!(current.aggregation, BinAggregation::Rms);
+    }
+
     // --- 2. Logic Tests (Color Resolution) ---
     // Critical: Ensures the "cascading" logic of presets works
     #[test]
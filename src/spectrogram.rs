@@ -0,0 +1,139 @@
+//! Time-frequency history for the spectrogram view
+//! FFT frames arrive at the hop rate (tens to hundreds per second), so they are
+//! folded into a fixed number of rows covering the configured history length.
+//! Frames that land in the same row keep their per-bar maximum, so short
+//! transients survive the decimation
+
+use std::collections::VecDeque;
+
+use crate::shared_state::SILENCE_DB;
+
+/// Rows kept in the history (the texture height of the spectrogram)
+pub const SPECTROGRAM_ROWS: usize = 512;
+/// Default visible history
+pub const DEFAULT_HISTORY_SECS: f32 = 10.0;
+
+#[derive(Clone, Debug)]
+pub struct SpectrogramHistory {
+    /// Completed rows, newest at the front
+    rows: VecDeque<Vec<f32>>,
+
+    /// Row being accumulated (per-bar maximum of the frames so far)
+    pending: Vec<f32>,
+    pending_ms: f32,
+
+    /// Time covered by one row
+    row_ms: f32,
+}
+
+impl SpectrogramHistory {
+    pub fn new(history_secs: f32) -> Self {
+        Self {
+            rows: VecDeque::with_capacity(SPECTROGRAM_ROWS),
+            pending: Vec::new(),
+            pending_ms: 0.0,
+            row_ms: Self::row_duration(history_secs),
+        }
+    }
+
+    /// Change the history length; existing rows are kept (and re-timed)
+    pub fn set_history_secs(&mut self, history_secs: f32) {
+        self.row_ms = Self::row_duration(history_secs);
+    }
+
+    /// Add one frame of bars that covers `elapsed_ms` of audio
+    pub fn push(&mut self, bars: &[f32], elapsed_ms: f32) {
+        // Layout changes are cleared by the caller; a new bar count is caught here too
+        if self.pending.len() != bars.len() {
+            self.clear();
+            self.pending = vec![SILENCE_DB; bars.len()];
+        }
+
+        for (acc, &db) in self.pending.iter_mut().zip(bars) {
+            *acc = acc.max(db);
+        }
+        self.pending_ms += elapsed_ms;
+
+        if self.pending_ms >= self.row_ms {
+            let row = std::mem::replace(&mut self.pending, vec![SILENCE_DB; bars.len()]);
+            self.rows.push_front(row);
+            self.rows.truncate(SPECTROGRAM_ROWS);
+            self.pending_ms = (self.pending_ms - self.row_ms).min(self.row_ms);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.rows.clear();
+        self.pending.clear();
+        self.pending_ms = 0.0;
+    }
+
+    /// Completed rows, newest first
+    pub fn rows(&self) -> impl Iterator<Item = &Vec<f32>> {
+        self.rows.iter()
+    }
+
+    /// Number of bars per row (0 until the first frame arrives)
+    pub fn width(&self) -> usize {
+        self.pending.len()
+    }
+
+    fn row_duration(history_secs: f32) -> f32 {
+        (history_secs.max(0.1) * 1000.0) / SPECTROGRAM_ROWS as f32
+    }
+}
+
+impl Default for SpectrogramHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_HISTORY_SECS)
+    }
+}
+
+// =============== Tests ==================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frames_fold_into_rows() {
+        // 5.12 s history -> 10 ms per row; 4 ms frames -> a row every 2.5 frames
+        let mut history = SpectrogramHistory::new(5.12);
+        for _ in 0..10 {
+            history.push(&[-60.0, -30.0], 4.0);
+        }
+
+        assert_eq!(history.rows().count(), 4);
+        assert_eq!(history.width(), 2);
+    }
+
+    #[test]
+    fn test_rows_keep_transients() {
+        let mut history = SpectrogramHistory::new(5.12);
+        history.push(&[-80.0], 4.0);
+        history.push(&[-10.0], 4.0);   // Short click inside the row
+        history.push(&[-80.0], 4.0);
+
+        assert_eq!(history.rows().next().map(|row| row[0]), Some(-10.0));
+    }
+
+    #[test]
+    fn test_history_is_bounded_and_newest_first() {
+        let mut history = SpectrogramHistory::new(1.0);
+        for i in 0..(SPECTROGRAM_ROWS * 2) {
+            history.push(&[i as f32], 1000.0);
+        }
+
+        assert_eq!(history.rows().count(), SPECTROGRAM_ROWS);
+        assert_eq!(history.rows().next().map(|row| row[0]), Some((SPECTROGRAM_ROWS * 2 - 1) as f32));
+    }
+
+    #[test]
+    fn test_bar_count_change_resets() {
+        let mut history = SpectrogramHistory::new(1.0);
+        history.push(&[-20.0; 4], 1000.0);
+        history.push(&[-20.0; 8], 1.0);
+
+        assert_eq!(history.rows().count(), 0);
+        assert_eq!(history.width(), 8);
+    }
+}
@@ -0,0 +1,123 @@
+//! Long-running spectrum traces: infinite max-hold, min-hold and the long-term
+//! average spectrum (LTAS), accumulated per bar since the last reset
+//! Unlike the peak indicators these never decay, so they answer "how loud did this
+//! band get over the whole song"
+
+use crate::shared_state::SILENCE_DB;
+
+#[derive(Clone, Debug, Default)]
+pub struct SpectrumTraces {
+    max_hold: Vec<f32>,
+    min_hold: Vec<f32>,
+    /// Summed linear power per bar (the average is taken in the power domain)
+    power_sum: Vec<f64>,
+    average: Vec<f32>,
+    frames: u64,
+}
+
+impl SpectrumTraces {
+    /// Forget everything; the next frame starts new traces
+    pub fn reset(&mut self) {
+        self.max_hold.clear();
+        self.min_hold.clear();
+        self.power_sum.clear();
+        self.average.clear();
+        self.frames = 0;
+    }
+
+    /// Frames accumulated since the last reset
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    /// Loudest level of each bar (dB); empty before the first frame
+    pub fn max_hold(&self) -> &[f32] {
+        &self.max_hold
+    }
+
+    /// Quietest level of each bar (dB), ignoring silent frames
+    pub fn min_hold(&self) -> &[f32] {
+        &self.min_hold
+    }
+
+    /// Power average of each bar (dB)
+    pub fn average(&self) -> &[f32] {
+        &self.average
+    }
+
+    /// Add one frame of bar levels (dB)
+    /// A change in bar count (new layout) restarts the traces. Frames where every
+    /// bar is silent (gaps between tracks, stopped stream) are skipped so they do
+    /// not drag the min-hold and the average down to the floor
+    pub fn push(&mut self, bars: &[f32]) {
+        if bars.iter().all(|&db| db <= SILENCE_DB) {
+            return;
+        }
+        if bars.len() != self.max_hold.len() {
+            self.reset();
+            self.max_hold = bars.to_vec();
+            self.min_hold = bars.to_vec();
+            self.power_sum = vec![0.0; bars.len()];
+            self.average = vec![SILENCE_DB; bars.len()];
+        }
+
+        self.frames += 1;
+        for (i, &db) in bars.iter().enumerate() {
+            self.max_hold[i] = self.max_hold[i].max(db);
+            self.min_hold[i] = self.min_hold[i].min(db);
+            self.power_sum[i] += 10f64.powf(db as f64 / 10.0);
+
+            let mean_power = self.power_sum[i] / self.frames as f64;
+            self.average[i] = ((10.0 * mean_power.log10()) as f32).max(SILENCE_DB);
+        }
+    }
+}
+
+// =============== Tests ==================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_holds_never_decay() {
+        let mut traces = SpectrumTraces::default();
+        traces.push(&[-20.0, -40.0]);
+        traces.push(&[-10.0, -50.0]);
+        for _ in 0..1000 {
+            traces.push(&[-30.0, -45.0]);
+        }
+
+        assert_eq!(traces.max_hold(), &[-10.0, -40.0]);
+        assert_eq!(traces.min_hold(), &[-30.0, -50.0]);
+        assert_eq!(traces.frames(), 1002);
+    }
+
+    #[test]
+    fn test_average_is_power_mean() {
+        // Equal time at 0 dB and silence-ish (-100 dB) averages to about -3 dB
+        let mut traces = SpectrumTraces::default();
+        for _ in 0..50 {
+            traces.push(&[0.0, -20.0]);
+            traces.push(&[-100.0, -20.0]);
+        }
+        assert!((traces.average()[0] + 3.01).abs() < 0.01, "LTAS = {}", traces.average()[0]);
+        assert!((traces.average()[1] + 20.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_silence_skipped_and_reset() {
+        let mut traces = SpectrumTraces::default();
+        traces.push(&[-20.0, -30.0]);
+        traces.push(&[SILENCE_DB, SILENCE_DB]);
+        assert_eq!(traces.min_hold(), &[-20.0, -30.0]);
+        assert_eq!(traces.frames(), 1);
+
+        // A new bar layout restarts the traces
+        traces.push(&[-5.0, -5.0, -5.0]);
+        assert_eq!(traces.max_hold(), &[-5.0, -5.0, -5.0]);
+        assert_eq!(traces.frames(), 1);
+
+        traces.reset();
+        assert!(traces.max_hold().is_empty());
+    }
+}
@@ -0,0 +1,360 @@
+//! Onset detection and tempo (BPM) tracking
+//! Onsets come from the spectral flux of a small dedicated STFT, so their timing
+//! does not depend on the FFT size chosen for the display. The tempo is the strongest
+//! periodicity of the onset-strength envelope, and beats are phase-locked to onsets
+//! (with a flywheel that keeps the beat going through short gaps)
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Instant;
+
+use realfft::{RealFftPlanner, RealToComplex};
+
+use crate::windowing::{WindowFunction, DEFAULT_KAISER_BETA};
+
+/// Analysis frame and hop (~21 ms / ~11 ms at 48 kHz)
+const FRAME_SIZE: usize = 1024;
+const HOP_SIZE: usize = 512;
+
+/// Onset-strength history used for the tempo estimate
+const HISTORY_SECS: f32 = 6.0;
+/// How often the tempo is re-estimated
+const ESTIMATE_INTERVAL_SECS: f32 = 0.5;
+
+/// Tempo search range
+pub const MIN_BPM: f32 = 60.0;
+pub const MAX_BPM: f32 = 200.0;
+/// Tempo prior: octave errors are resolved towards the most common dance tempo
+const PRIOR_BPM: f32 = 120.0;
+const PRIOR_WIDTH_OCTAVES: f32 = 1.0;
+/// Normalised autocorrelation a tempo needs before it is reported
+const MIN_CONFIDENCE: f32 = 0.1;
+
+/// Flux must exceed its running mean by this factor to be an onset
+const ONSET_THRESHOLD: f32 = 1.5;
+/// Flux below this is treated as silence / noise
+const MIN_FLUX: f32 = 1.0;
+/// Time constant of the running flux mean
+const FLUX_MEAN_SECS: f32 = 0.5;
+/// Onsets closer than this are merged
+const MIN_ONSET_INTERVAL_SECS: f32 = 0.1;
+
+/// An onset within this fraction of a period from the predicted beat re-aligns the beat
+const BEAT_TOLERANCE: f32 = 0.2;
+/// The flywheel stops this long after the last onset
+const FLYWHEEL_SECS: f32 = 2.0;
+/// Decay of the beat pulse published to the visualizers
+const PULSE_DECAY_SECS: f32 = 0.15;
+
+/// Published tempo state
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TempoReadings {
+    pub bpm: Option<f32>,
+    pub beat_count: u64,
+    pub last_beat: Option<Instant>,
+}
+
+impl TempoReadings {
+    /// Beat pulse for visuals: 1.0 on a beat, decaying towards 0.0
+    pub fn pulse(&self) -> f32 {
+        self.last_beat
+            .map_or(0.0, |beat| (-beat.elapsed().as_secs_f32() / PULSE_DECAY_SECS).exp())
+    }
+}
+
+pub struct TempoTracker {
+    // Spectral flux STFT
+    fft: Arc<dyn RealToComplex<f32>>,
+    window: Vec<f32>,
+    pending: Vec<f32>,
+    input_buffer: Vec<f32>,
+    spectrum: Vec<num_complex::Complex<f32>>,
+    last_log_magnitudes: Vec<f32>,
+
+    // Timing (audio clock)
+    hop_secs: f32,
+    time_secs: f32,
+    frames_since_estimate: usize,
+
+    // Onset detection
+    flux_mean: f32,
+    recent_strength: [f32; 2],      // Onset strength of the previous two frames
+    recent_flux: f32,               // Flux of the previous frame
+    last_onset_secs: Option<f32>,
+    envelope: VecDeque<f32>,
+
+    // Tempo and beat phase
+    period_secs: Option<f32>,
+    last_beat_secs: Option<f32>,
+    next_beat_secs: Option<f32>,
+}
+
+impl TempoTracker {
+    pub fn new(sample_rate: u32) -> Self {
+        let mut planner = RealFftPlanner::<f32>::new();
+        let fft = planner.plan_fft_forward(FRAME_SIZE);
+        let spectrum = fft.make_output_vec();
+        let hop_secs = HOP_SIZE as f32 / sample_rate.max(1) as f32;
+
+        Self {
+            fft,
+            window: WindowFunction::Hann.generate(FRAME_SIZE, DEFAULT_KAISER_BETA),
+            pending: Vec::with_capacity(FRAME_SIZE * 2),
+            input_buffer: vec![0.0; FRAME_SIZE],
+            spectrum,
+            last_log_magnitudes: vec![0.0; FRAME_SIZE / 2 + 1],
+            hop_secs,
+            time_secs: 0.0,
+            frames_since_estimate: 0,
+            flux_mean: 0.0,
+            recent_strength: [0.0; 2],
+            recent_flux: 0.0,
+            last_onset_secs: None,
+            envelope: VecDeque::with_capacity((HISTORY_SECS / hop_secs) as usize + 1),
+            period_secs: None,
+            last_beat_secs: None,
+            next_beat_secs: None,
+        }
+    }
+
+    /// Current tempo estimate
+    pub fn bpm(&self) -> Option<f32> {
+        self.period_secs.map(|period| 60.0 / period)
+    }
+
+    /// Feed mono samples; returns the number of beats that fell in this block
+    pub fn process(&mut self, samples: &[f32]) -> usize {
+        self.pending.extend_from_slice(samples);
+
+        let mut beats = 0;
+        let mut consumed = 0;
+        while self.pending.len() - consumed >= FRAME_SIZE {
+            let flux = self.spectral_flux(consumed);
+            consumed += HOP_SIZE;
+            if self.analyse_frame(flux) {
+                beats += 1;
+            }
+        }
+        self.pending.drain(..consumed);
+
+        beats
+    }
+
+    /// Half-wave rectified increase of log magnitude, summed over all bins
+    fn spectral_flux(&mut self, offset: usize) -> f32 {
+        let frame = &self.pending[offset..offset + FRAME_SIZE];
+        for ((out, &sample), &w) in self.input_buffer.iter_mut().zip(frame).zip(&self.window) {
+            *out = sample * w;
+        }
+        self.fft
+            .process(&mut self.input_buffer, &mut self.spectrum)
+            .expect("FFT processing failed");
+
+        let scale = 2.0 / FRAME_SIZE as f32;
+        let mut flux = 0.0;
+        for (last, bin) in self.last_log_magnitudes.iter_mut().zip(&self.spectrum) {
+            // Log compression so quiet percussion counts, not just the loudest partials
+            let log_mag = (1.0 + 1000.0 * bin.norm() * scale).ln();
+            flux += (log_mag - *last).max(0.0);
+            *last = log_mag;
+        }
+        flux
+    }
+
+    /// Update onsets, tempo and beat phase with one frame of flux; true on a beat
+    fn analyse_frame(&mut self, flux: f32) -> bool {
+        self.time_secs += self.hop_secs;
+
+        // Onset strength: flux above its running mean
+        let mean_alpha = self.hop_secs / FLUX_MEAN_SECS;
+        let previous_mean = self.flux_mean;
+        self.flux_mean += (flux - self.flux_mean) * mean_alpha;
+        let strength = (flux - self.flux_mean).max(0.0);
+
+        self.envelope.push_back(strength);
+        if self.envelope.len() as f32 * self.hop_secs > HISTORY_SECS {
+            self.envelope.pop_front();
+        }
+
+        // Peak picking with one frame of delay: the previous frame is an onset if it
+        // is a local maximum that clearly exceeds the running mean
+        let [older, previous] = self.recent_strength;
+        let onset_secs = self.time_secs - self.hop_secs;
+        let is_onset = previous > older
+            && previous >= strength
+            && self.recent_flux > MIN_FLUX
+            && self.recent_flux > ONSET_THRESHOLD * previous_mean
+            && self.last_onset_secs.is_none_or(|last| onset_secs - last >= MIN_ONSET_INTERVAL_SECS);
+        self.recent_strength = [previous, strength];
+        self.recent_flux = flux;
+        if is_onset {
+            self.last_onset_secs = Some(onset_secs);
+        }
+
+        self.frames_since_estimate += 1;
+        if self.frames_since_estimate as f32 * self.hop_secs >= ESTIMATE_INTERVAL_SECS {
+            self.frames_since_estimate = 0;
+            self.period_secs = self.estimate_period();
+        }
+
+        self.track_beat(is_onset.then_some(onset_secs))
+    }
+
+    /// Strongest periodicity of the onset envelope within the BPM range, weighted
+    /// by a log-normal prior around 120 BPM
+    fn estimate_period(&self) -> Option<f32> {
+        let envelope: Vec<f32> = self.envelope.iter().copied().collect();
+        let frames_per_sec = 1.0 / self.hop_secs;
+        let min_lag = (60.0 / MAX_BPM * frames_per_sec).floor().max(1.0) as usize;
+        let max_lag = (60.0 / MIN_BPM * frames_per_sec).ceil() as usize;
+        if envelope.len() < max_lag * 2 {
+            return None;
+        }
+
+        let energy = autocorrelation(&envelope, 0);
+        if energy <= 0.0 {
+            return None;
+        }
+        let raw: Vec<f32> = (0..=max_lag + 2).map(|lag| autocorrelation(&envelope, lag) / energy).collect();
+
+        // A period that is not a whole number of frames splits its energy across two
+        // neighbouring lags; a 3-lag sum collects it again
+        let acf: Vec<f32> = (0..=max_lag + 1)
+            .map(|lag| raw[lag.saturating_sub(1)..=lag + 1].iter().sum())
+            .collect();
+
+        let weight = |lag: usize| {
+            let bpm = 60.0 * frames_per_sec / lag as f32;
+            let octaves = (bpm / PRIOR_BPM).log2() / PRIOR_WIDTH_OCTAVES;
+            (-0.5 * octaves * octaves).exp()
+        };
+        let best = (min_lag..=max_lag).max_by(|&a, &b| (acf[a] * weight(a)).total_cmp(&(acf[b] * weight(b))))?;
+        if acf[best] < MIN_CONFIDENCE {
+            return None;
+        }
+
+        // Parabolic refinement between the neighbouring lags
+        let (a, b, c) = (acf[best - 1], acf[best], acf[best + 1]);
+        let denominator = a - 2.0 * b + c;
+        let offset = if denominator.abs() > 1e-9 { (0.5 * (a - c) / denominator).clamp(-0.5, 0.5) } else { 0.0 };
+
+        Some((best as f32 + offset) * self.hop_secs)
+    }
+
+    /// Advance the beat phase; true when a beat happens in this frame
+    fn track_beat(&mut self, onset_secs: Option<f32>) -> bool {
+        let Some(period) = self.period_secs else {
+            // No tempo yet: every onset is a beat
+            self.next_beat_secs = None;
+            if let Some(onset) = onset_secs {
+                self.last_beat_secs = Some(onset);
+            }
+            return onset_secs.is_some();
+        };
+        let tolerance = BEAT_TOLERANCE * period;
+
+        if let Some(onset) = onset_secs {
+            // Onset just after a flywheel beat: same beat, only re-align the phase
+            if self.last_beat_secs.is_some_and(|last| (onset - last).abs() <= tolerance) {
+                self.last_beat_secs = Some(onset);
+                self.next_beat_secs = Some(onset + period);
+                return false;
+            }
+            // Onset on (or without) a prediction: a beat
+            if self.next_beat_secs.is_none_or(|next| onset >= next - tolerance) {
+                self.last_beat_secs = Some(onset);
+                self.next_beat_secs = Some(onset + period);
+                return true;
+            }
+        }
+
+        // Flywheel: keep the beat going through short gaps between onsets
+        let playing = self.last_onset_secs.is_some_and(|last| self.time_secs - last < FLYWHEEL_SECS);
+        match self.next_beat_secs {
+            Some(next) if playing && self.time_secs >= next => {
+                self.last_beat_secs = Some(next);
+                self.next_beat_secs = Some(next + period);
+                true
+            }
+            Some(_) if !playing => {
+                self.next_beat_secs = None;
+                false
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Mean product of the envelope with itself shifted by `lag`
+fn autocorrelation(envelope: &[f32], lag: usize) -> f32 {
+    let n = envelope.len().saturating_sub(lag);
+    if n == 0 {
+        return 0.0;
+    }
+    envelope.iter().zip(&envelope[lag..]).map(|(a, b)| a * b).sum::<f32>() / n as f32
+}
+
+// =============== Tests ==================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 48000;
+
+    /// Short decaying noise bursts (kick-like clicks) at `bpm` over `secs`
+    fn click_track(bpm: f32, secs: f32) -> Vec<f32> {
+        let period = (60.0 / bpm * RATE as f32) as usize;
+        let mut seed = 12345u32;
+        (0..(secs * RATE as f32) as usize)
+            .map(|i| {
+                let since_click = i % period;
+                seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+                let noise = (seed >> 8) as f32 / (1u32 << 24) as f32 * 2.0 - 1.0;
+                0.8 * noise * (-(since_click as f32) / (0.01 * RATE as f32)).exp()
+            })
+            .collect()
+    }
+
+    fn run(tracker: &mut TempoTracker, samples: &[f32]) -> usize {
+        // Packet-sized blocks, like the capture thread delivers
+        samples.chunks(480).map(|block| tracker.process(block)).sum()
+    }
+
+    #[test]
+    fn test_detects_120_bpm() {
+        let mut tracker = TempoTracker::new(RATE);
+        run(&mut tracker, &click_track(120.0, 10.0));
+
+        let bpm = tracker.bpm().expect("tempo should lock");
+        assert!((bpm - 120.0).abs() < 1.5, "bpm = {}", bpm);
+    }
+
+    #[test]
+    fn test_detects_slow_and_fast_tempos() {
+        for expected in [90.0, 150.0] {
+            let mut tracker = TempoTracker::new(RATE);
+            run(&mut tracker, &click_track(expected, 12.0));
+
+            let bpm = tracker.bpm().expect("tempo should lock");
+            assert!((bpm - expected).abs() < 2.0, "expected {} got {}", expected, bpm);
+        }
+    }
+
+    #[test]
+    fn test_one_beat_per_click() {
+        let mut tracker = TempoTracker::new(RATE);
+        let beats = run(&mut tracker, &click_track(120.0, 10.0));
+
+        // 20 clicks; allow one either way for the first / last click
+        assert!((19..=21).contains(&beats), "beats = {}", beats);
+    }
+
+    #[test]
+    fn test_silence_has_no_tempo_or_beats() {
+        let mut tracker = TempoTracker::new(RATE);
+        let beats = run(&mut tracker, &vec![0.0; RATE as usize * 8]);
+
+        assert_eq!(beats, 0);
+        assert!(tracker.bpm().is_none());
+    }
+}
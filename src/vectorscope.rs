@@ -0,0 +1,296 @@
+//! Stereo image analysis: goniometer (vectorscope) persistence and phase correlation
+//! The goniometer plots each L/R sample pair rotated by 45°, so mono content is a
+//! vertical line (Mid) and out-of-phase content spreads horizontally (Side)
+//! The same phosphor buffer draws the unrotated XY (Lissajous) scope
+
+use serde::{Serialize, Deserialize};
+
+/// Resolution of the persistence buffer (square)
+pub const PHOSPHOR_SIZE: usize = 256;
+/// Default time for a trace to fade to ~37%
+pub const DEFAULT_PERSISTENCE_SECS: f32 = 0.5;
+/// Brightness added by one sample landing on a cell
+const HIT_INTENSITY: f32 = 0.2;
+/// The beam is held just outside the display, so clipped or corrupt samples
+/// (inf, huge values) can't turn one step into an endless sweep
+const BEAM_LIMIT: f32 = 1.1;
+/// Integration time of the correlation meter
+const CORRELATION_TIME_MS: f32 = 300.0;
+/// Below this energy the correlation is undefined and reads 0
+const CORRELATION_MIN_ENERGY: f32 = 1e-10;
+
+/// Rotate an L/R sample pair into goniometer coordinates (x = Side, y = Mid)
+/// Scaled so full-scale mono reaches the top edge; left-only signals lean to the
+/// left diagonal, right-only to the right one
+pub fn mid_side_point(left: f32, right: f32) -> (f32, f32) {
+    ((right - left) * 0.5, (left + right) * 0.5)
+}
+
+/// How the phosphor fades between frames
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub enum PhosphorDecay {
+    Exponential,    // Like a CRT: bright traces linger as a long faint tail
+    Linear,         // Every trace is gone after exactly the persistence time
+}
+
+impl PhosphorDecay {
+    /// All decay curves, in the order shown in the settings UI
+    pub const ALL: [PhosphorDecay; 2] = [
+        PhosphorDecay::Exponential,
+        PhosphorDecay::Linear,
+    ];
+
+    /// Human readable name for the settings UI
+    pub fn label(&self) -> &'static str {
+        match self {
+            PhosphorDecay::Exponential => "Exponential",
+            PhosphorDecay::Linear => "Linear",
+        }
+    }
+}
+
+/// Fading intensity buffer: every plotted point brightens a cell, and the whole
+/// buffer decays with audio time
+#[derive(Clone, Debug)]
+pub struct Phosphor {
+    size: usize,
+    intensity: Vec<f32>,
+    beam: Option<(f32, f32)>,   // Last traced point (XY scope beam)
+}
+
+impl Phosphor {
+    pub fn new(size: usize) -> Self {
+        Self { size, intensity: vec![0.0; size * size], beam: None }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Row-major intensities (0.0 - 1.0), top row first
+    pub fn intensity(&self) -> &[f32] {
+        &self.intensity
+    }
+
+    pub fn clear(&mut self) {
+        self.intensity.fill(0.0);
+        self.beam = None;
+    }
+
+    /// Fade the buffer by `elapsed_ms` of audio
+    pub fn decay(&mut self, elapsed_ms: f32, persistence_ms: f32) {
+        let factor = (-elapsed_ms / persistence_ms.max(1.0)).exp();
+        for cell in &mut self.intensity {
+            *cell *= factor;
+        }
+    }
+
+    /// Fade the buffer by `elapsed_ms` of audio along the chosen curve
+    pub fn fade(&mut self, curve: PhosphorDecay, elapsed_ms: f32, persistence_ms: f32) {
+        match curve {
+            PhosphorDecay::Exponential => self.decay(elapsed_ms, persistence_ms),
+            PhosphorDecay::Linear => {
+                let step = elapsed_ms / persistence_ms.max(1.0);
+                for cell in &mut self.intensity {
+                    *cell = (*cell - step).max(0.0);
+                }
+            }
+        }
+    }
+
+    /// Brighten the cell at (x, y), both in -1.0 ..= 1.0 with y pointing up
+    /// Points outside the range are dropped
+    pub fn plot(&mut self, x: f32, y: f32) {
+        self.hit(x, y, HIT_INTENSITY);
+    }
+
+    /// Move the beam to (x, y), lighting the cells along the way
+    /// Like a CRT beam, a fixed energy per sample is spread over the path: slow
+    /// movement draws bright lines, fast jumps faint ones
+    pub fn trace(&mut self, x: f32, y: f32) {
+        if x.is_nan() || y.is_nan() {
+            return;
+        }
+        let (x, y) = (x.clamp(-BEAM_LIMIT, BEAM_LIMIT), y.clamp(-BEAM_LIMIT, BEAM_LIMIT));
+        let Some((from_x, from_y)) = self.beam.replace((x, y)) else {
+            self.plot(x, y);
+            return;
+        };
+
+        let cells = ((x - from_x).abs().max((y - from_y).abs()) * 0.5 * (self.size - 1) as f32).ceil();
+        let steps = (cells as usize).max(1);
+        let energy = HIT_INTENSITY / steps as f32;
+        for step in 1..=steps {
+            let t = step as f32 / steps as f32;
+            self.hit(from_x + (x - from_x) * t, from_y + (y - from_y) * t, energy);
+        }
+    }
+
+    fn hit(&mut self, x: f32, y: f32, energy: f32) {
+        if !(-1.0..=1.0).contains(&x) || !(-1.0..=1.0).contains(&y) {
+            return;
+        }
+        let last = (self.size - 1) as f32;
+        let col = ((x + 1.0) * 0.5 * last).round() as usize;
+        let row = ((1.0 - y) * 0.5 * last).round() as usize;
+
+        let cell = &mut self.intensity[row * self.size + col];
+        *cell = (*cell + energy).min(1.0);
+    }
+}
+
+impl Default for Phosphor {
+    fn default() -> Self {
+        Self::new(PHOSPHOR_SIZE)
+    }
+}
+
+/// Phase correlation meter: +1 mono, 0 unrelated (wide), -1 out of phase
+/// Running, exponentially weighted products of the two channels
+#[derive(Clone, Debug)]
+pub struct CorrelationMeter {
+    sample_rate: u32,
+    lr: f32,
+    ll: f32,
+    rr: f32,
+}
+
+impl CorrelationMeter {
+    pub fn new(sample_rate: u32) -> Self {
+        Self { sample_rate, lr: 0.0, ll: 0.0, rr: 0.0 }
+    }
+
+    /// Feed interleaved samples; mono input is perfectly correlated with itself
+    pub fn process(&mut self, interleaved: &[f32], channels: usize) {
+        if channels == 0 {
+            return;
+        }
+        let alpha = 1.0 - (-1000.0 / (CORRELATION_TIME_MS * self.sample_rate.max(1) as f32)).exp();
+
+        for frame in interleaved.chunks_exact(channels) {
+            let left = frame[0];
+            let right = frame[channels.min(2) - 1];
+            self.lr += (left * right - self.lr) * alpha;
+            self.ll += (left * left - self.ll) * alpha;
+            self.rr += (right * right - self.rr) * alpha;
+        }
+    }
+
+    /// Current correlation (-1.0 ..= 1.0), 0 while silent
+    pub fn value(&self) -> f32 {
+        let energy = (self.ll * self.rr).sqrt();
+        if energy < CORRELATION_MIN_ENERGY {
+            return 0.0;
+        }
+        (self.lr / energy).clamp(-1.0, 1.0)
+    }
+}
+
+// =============== Tests ==================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stereo(frames: usize, left: impl Fn(f32) -> f32, right: impl Fn(f32) -> f32) -> Vec<f32> {
+        (0..frames)
+            .flat_map(|i| {
+                let t = i as f32 / 48000.0;
+                [left(t), right(t)]
+            })
+            .collect()
+    }
+
+    fn sine(freq: f32) -> impl Fn(f32) -> f32 {
+        move |t| 0.5 * (2.0 * std::f32::consts::PI * freq * t).sin()
+    }
+
+    #[test]
+    fn test_mid_side_rotation() {
+        // Mono: straight up; left only: up-left diagonal; out of phase: horizontal
+        let (x, y) = mid_side_point(0.5, 0.5);
+        assert!(x.abs() < 1e-6 && y > 0.0);
+
+        let (x, y) = mid_side_point(0.5, 0.0);
+        assert!(x < 0.0 && (x + y).abs() < 1e-6);
+
+        let (_, y) = mid_side_point(0.5, -0.5);
+        assert!(y.abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_correlation_extremes() {
+        let mut mono = CorrelationMeter::new(48000);
+        mono.process(&stereo(48000, sine(440.0), sine(440.0)), 2);
+        assert!((mono.value() - 1.0).abs() < 1e-3, "mono = {}", mono.value());
+
+        let mut inverted = CorrelationMeter::new(48000);
+        inverted.process(&stereo(48000, sine(440.0), |t| -sine(440.0)(t)), 2);
+        assert!((inverted.value() + 1.0).abs() < 1e-3, "inverted = {}", inverted.value());
+
+        // Different frequencies are uncorrelated
+        let mut wide = CorrelationMeter::new(48000);
+        wide.process(&stereo(48000, sine(440.0), sine(1234.0)), 2);
+        assert!(wide.value().abs() < 0.1, "wide = {}", wide.value());
+    }
+
+    #[test]
+    fn test_correlation_silent_and_mono_input() {
+        let mut meter = CorrelationMeter::new(48000);
+        meter.process(&[0.0; 4800], 2);
+        assert_eq!(meter.value(), 0.0);
+
+        meter.process(&vec![0.3; 4800], 1);
+        assert!((meter.value() - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_phosphor_plots_and_fades() {
+        let mut phosphor = Phosphor::new(9);
+        phosphor.plot(0.0, 1.0);    // Top centre
+        phosphor.plot(2.0, 0.0);    // Out of range: ignored
+
+        let lit: Vec<usize> = (0..81).filter(|&i| phosphor.intensity()[i] > 0.0).collect();
+        assert_eq!(lit, vec![4]);
+
+        phosphor.decay(500.0, 500.0);
+        assert!((phosphor.intensity()[4] - HIT_INTENSITY * (-1.0f32).exp()).abs() < 1e-6);
+
+        // Linear decay is gone after exactly the persistence time
+        phosphor.fade(PhosphorDecay::Linear, 500.0, 500.0);
+        assert_eq!(phosphor.intensity()[4], 0.0);
+    }
+
+    #[test]
+    fn test_beam_draws_connected_line() {
+        // A jump from the left edge to the right edge of the middle row lights the
+        // whole row, with the energy of one sample spread across it
+        let mut phosphor = Phosphor::new(9);
+        phosphor.trace(-1.0, 0.0);
+        phosphor.trace(1.0, 0.0);
+
+        let middle_row = &phosphor.intensity()[36..45];
+        assert!(middle_row.iter().all(|&i| i > 0.0), "{:?}", middle_row);
+        assert!((middle_row[8] - HIT_INTENSITY / 8.0).abs() < 1e-6);
+        assert_eq!(phosphor.intensity().iter().filter(|&&i| i > 0.0).count(), 9);
+
+        // A resting beam keeps hitting the same cell at full energy
+        phosphor.clear();
+        phosphor.trace(0.0, 0.0);
+        phosphor.trace(0.0, 0.0);
+        assert!((phosphor.intensity()[40] - 2.0 * HIT_INTENSITY).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_beam_survives_corrupt_samples() {
+        // Infinite or huge samples are pinned just off screen instead of sweeping forever
+        let mut phosphor = Phosphor::new(9);
+        phosphor.trace(0.0, 0.0);
+        phosphor.trace(f32::INFINITY, 0.0);
+        phosphor.trace(f32::MIN, 1e30);
+        phosphor.trace(f32::NAN, 0.0);
+        phosphor.trace(0.0, 0.0);
+
+        assert!(phosphor.intensity().iter().all(|i| i.is_finite()));
+        assert!(phosphor.intensity()[40] > 0.0);
+    }
+}
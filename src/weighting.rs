@@ -0,0 +1,149 @@
+//! Frequency weighting curves and spectral tilt
+//! Both are expressed as a dB offset per frequency, normalised to 0 dB at 1 kHz,
+//! and added to the FFT magnitudes before bins are grouped into bars
+
+use serde::{Serialize, Deserialize};
+
+/// Reference frequency: weighting and tilt are both 0 dB here
+const REFERENCE_FREQ: f64 = 1000.0;
+/// Lowest gain a curve can apply (keeps DC out of -inf territory)
+const MIN_GAIN_DB: f32 = -120.0;
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub enum FrequencyWeighting {
+    Z,  // Zero (flat)
+    A,  // IEC 61672 A-weighting: loudness of quiet sounds, strong bass cut
+    C,  // IEC 61672 C-weighting: loud sounds, nearly flat with gentle roll-offs
+}
+
+impl FrequencyWeighting {
+    /// All weightings, in the order shown in the settings UI
+    pub const ALL: [FrequencyWeighting; 3] = [
+        FrequencyWeighting::Z,
+        FrequencyWeighting::A,
+        FrequencyWeighting::C,
+    ];
+
+    /// Human readable name for the settings UI
+    pub fn label(&self) -> &'static str {
+        match self {
+            FrequencyWeighting::Z => "Z (Flat)",
+            FrequencyWeighting::A => "A-Weighting",
+            FrequencyWeighting::C => "C-Weighting",
+        }
+    }
+
+    /// Gain in dB at `freq_hz`, 0 dB at 1 kHz
+    pub fn gain_db(&self, freq_hz: f64) -> f32 {
+        match self {
+            FrequencyWeighting::Z => 0.0,
+            FrequencyWeighting::A | FrequencyWeighting::C => {
+                let response = self.response(freq_hz);
+                if response <= 0.0 {
+                    return MIN_GAIN_DB;
+                }
+                let gain = 20.0 * (response / self.response(REFERENCE_FREQ)).log10();
+                (gain as f32).max(MIN_GAIN_DB)
+            }
+        }
+    }
+
+    /// Unnormalised analogue magnitude response (IEC 61672-1 pole frequencies)
+    fn response(&self, f: f64) -> f64 {
+        const F1: f64 = 20.598997;
+        const F2: f64 = 107.65265;
+        const F3: f64 = 737.86223;
+        const F4: f64 = 12194.217;
+
+        let f_sq = f * f;
+        let common = (f_sq + F1 * F1) * (f_sq + F4 * F4);
+
+        match self {
+            FrequencyWeighting::A => {
+                F4 * F4 * f_sq * f_sq / (common * ((f_sq + F2 * F2) * (f_sq + F3 * F3)).sqrt())
+            }
+            FrequencyWeighting::C => F4 * F4 * f_sq / common,
+            FrequencyWeighting::Z => 1.0,
+        }
+    }
+}
+
+/// Spectral tilt in dB at `freq_hz`: +3 dB/oct lifts 2 kHz by 3 dB and cuts 500 Hz by 3 dB
+pub fn tilt_db(freq_hz: f64, db_per_octave: f32) -> f32 {
+    if db_per_octave == 0.0 {
+        return 0.0;
+    }
+    if freq_hz <= 0.0 {
+        return MIN_GAIN_DB;
+    }
+    (db_per_octave as f64 * (freq_hz / REFERENCE_FREQ).log2()) as f32
+}
+
+/// Combined per-bin offset (weighting + tilt) for an FFT of `fft_size` at `sample_rate`
+/// One entry per output bin (fft_size / 2 + 1)
+pub fn bin_curve(
+    weighting: FrequencyWeighting,
+    tilt_db_per_octave: f32,
+    sample_rate: u32,
+    fft_size: usize,
+) -> Vec<f32> {
+    let freq_res = sample_rate as f64 / fft_size as f64;
+    let frequencies: Vec<f64> = (0..=fft_size / 2).map(|bin| bin as f64 * freq_res).collect();
+
+    frequency_curve(weighting, tilt_db_per_octave, &frequencies)
+}
+
+/// Combined offset (weighting + tilt) at each of `frequencies_hz`
+/// (used by analyses whose bins are not evenly spaced, e.g. constant-Q)
+pub fn frequency_curve(weighting: FrequencyWeighting, tilt_db_per_octave: f32, frequencies_hz: &[f64]) -> Vec<f32> {
+    frequencies_hz
+        .iter()
+        .map(|&freq| (weighting.gain_db(freq) + tilt_db(freq, tilt_db_per_octave)).max(MIN_GAIN_DB))
+        .collect()
+}
+
+// =============== Tests ==================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weightings_are_zero_at_1khz() {
+        for weighting in FrequencyWeighting::ALL {
+            assert!(weighting.gain_db(1000.0).abs() < 1e-4, "{:?}", weighting);
+        }
+    }
+
+    #[test]
+    fn test_a_weighting_table_values() {
+        // IEC 61672-1 tables list values at the exact base-10 band centres (31.5 = 10^1.5)
+        let a = FrequencyWeighting::A;
+        assert!((a.gain_db(10f64.powf(1.5)) + 39.4).abs() < 0.1);
+        assert!((a.gain_db(100.0) + 19.1).abs() < 0.1);
+        assert!((a.gain_db(4000.0) - 1.0).abs() < 0.1);
+        assert!((a.gain_db(10000.0) + 2.5).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_c_weighting_table_values() {
+        let c = FrequencyWeighting::C;
+        assert!((c.gain_db(10f64.powf(1.5)) + 3.0).abs() < 0.1);
+        assert!((c.gain_db(100.0) + 0.3).abs() < 0.1);
+        assert!((c.gain_db(10000.0) + 4.4).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_tilt_per_octave() {
+        assert!((tilt_db(2000.0, 3.0) - 3.0).abs() < 1e-5);
+        assert!((tilt_db(250.0, 3.0) + 6.0).abs() < 1e-5);
+        assert_eq!(tilt_db(8000.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_bin_curve_handles_dc() {
+        let curve = bin_curve(FrequencyWeighting::A, 3.0, 48000, 1024);
+        assert_eq!(curve.len(), 513);
+        assert_eq!(curve[0], MIN_GAIN_DB);
+        assert!(curve.iter().all(|g| g.is_finite()));
+    }
+}
@@ -0,0 +1,233 @@
+//! Window functions for FFT analysis
+//! Each window trades main-lobe width (frequency resolution) against sidelobe
+//! leakage (how far a loud tone smears into its neighbours)
+
+use serde::{Serialize, Deserialize};
+
+/// Default Kaiser shape parameter (~ -70 dB sidelobes, similar to Blackman-Harris)
+pub const DEFAULT_KAISER_BETA: f32 = 8.6;
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub enum WindowFunction {
+    Hann,               // General purpose (-31 dB sidelobes)
+    BlackmanHarris,     // 4-term, -92 dB sidelobes: narrow whines next to loud content
+    FlatTop,            // < 0.02 dB scalloping: accurate tone amplitudes
+    Kaiser,             // Adjustable via beta (0 = rectangular, higher = lower sidelobes)
+    Rectangular,        // No windowing: best resolution, worst leakage
+}
+
+impl WindowFunction {
+    /// All windows, in the order shown in the settings UI
+    pub const ALL: [WindowFunction; 5] = [
+        WindowFunction::Hann,
+        WindowFunction::BlackmanHarris,
+        WindowFunction::FlatTop,
+        WindowFunction::Kaiser,
+        WindowFunction::Rectangular,
+    ];
+
+    /// Human readable name for the settings UI
+    pub fn label(&self) -> &'static str {
+        match self {
+            WindowFunction::Hann => "Hann",
+            WindowFunction::BlackmanHarris => "Blackman-Harris",
+            WindowFunction::FlatTop => "Flat-Top",
+            WindowFunction::Kaiser => "Kaiser",
+            WindowFunction::Rectangular => "Rectangular",
+        }
+    }
+
+    /// Precompute the (symmetric) window coefficients
+    /// `kaiser_beta` is ignored by every window except Kaiser
+    pub fn generate(&self, size: usize, kaiser_beta: f32) -> Vec<f32> {
+        if size <= 1 {
+            return vec![1.0; size];
+        }
+
+        let denom = (size - 1) as f64;
+        let i0_beta = bessel_i0(kaiser_beta as f64);
+
+        (0..size)
+            .map(|i| {
+                let angle = 2.0 * std::f64::consts::PI * i as f64 / denom;
+                let w = match self {
+                    WindowFunction::Hann => 0.5 * (1.0 - angle.cos()),
+                    WindowFunction::BlackmanHarris => {
+                        0.35875 - 0.48829 * angle.cos() + 0.14128 * (2.0 * angle).cos()
+                            - 0.01168 * (3.0 * angle).cos()
+                    }
+                    WindowFunction::FlatTop => {
+                        0.21557895 - 0.41663158 * angle.cos() + 0.277263158 * (2.0 * angle).cos()
+                            - 0.083578947 * (3.0 * angle).cos() + 0.006947368 * (4.0 * angle).cos()
+                    }
+                    WindowFunction::Kaiser => {
+                        let ratio = 2.0 * i as f64 / denom - 1.0;
+                        let arg = kaiser_beta as f64 * (1.0 - ratio * ratio).max(0.0).sqrt();
+                        bessel_i0(arg) / i0_beta
+                    }
+                    WindowFunction::Rectangular => 1.0,
+                };
+                w as f32
+            })
+            .collect()
+    }
+
+    /// Equivalent noise bandwidth in bins (1.0 = rectangular, 1.5 = Hann)
+    /// Used by the settings UI to show how much each window widens a bin
+    pub fn enbw_bins(&self, kaiser_beta: f32) -> f32 {
+        let correction = WindowCorrection::from_window(&self.generate(1024, kaiser_beta));
+        (correction.amplitude / correction.energy).powi(2)
+    }
+}
+
+/// Gain corrections derived from a window's coefficients
+///
+/// Windowing attenuates the signal, so FFT magnitudes have to be scaled back up:
+/// - `amplitude`: 1 / coherent gain. Restores the true level of a sine (tones, peaks)
+/// - `energy`: 1 / RMS of the window. Restores total power of broadband content
+///   (noise, summed band power)
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct WindowCorrection {
+    pub amplitude: f32,
+    pub energy: f32,
+}
+
+impl WindowCorrection {
+    pub fn from_window(window: &[f32]) -> Self {
+        let n = window.len().max(1) as f64;
+        let sum: f64 = window.iter().map(|&w| w as f64).sum();
+        let sum_sq: f64 = window.iter().map(|&w| (w as f64) * (w as f64)).sum();
+
+        Self {
+            amplitude: (n / sum.max(1e-12)) as f32,
+            energy: (n / sum_sq.max(1e-12)).sqrt() as f32,
+        }
+    }
+}
+
+/// Zeroth-order modified Bessel function of the first kind (power series)
+/// Converges quickly for the beta range we expose (0 - 20)
+fn bessel_i0(x: f64) -> f64 {
+    let half_x = x / 2.0;
+    let mut sum = 1.0;
+    let mut term = 1.0;
+
+    for k in 1..50 {
+        term *= half_x / k as f64;
+        let contribution = term * term;
+        sum += contribution;
+        if contribution < sum * 1e-12 {
+            break;
+        }
+    }
+    sum
+}
+
+// =============== Tests ==================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Magnitude of a single DFT bin (slow, but fine for tests)
+    fn dft_bin_magnitude(samples: &[f32], bin: f64) -> f32 {
+        let n = samples.len() as f64;
+        let (mut re, mut im) = (0.0f64, 0.0f64);
+        for (i, &s) in samples.iter().enumerate() {
+            let angle = 2.0 * std::f64::consts::PI * bin * i as f64 / n;
+            re += s as f64 * angle.cos();
+            im -= s as f64 * angle.sin();
+        }
+        (re * re + im * im).sqrt() as f32
+    }
+
+    /// Window a sine sitting `bin_offset` bins away from bin 64 and measure
+    /// the corrected amplitude at bin 64 (1.0 = perfect)
+    fn measured_amplitude(window_fn: WindowFunction, bin_offset: f64) -> f32 {
+        let size = 1024;
+        let window = window_fn.generate(size, DEFAULT_KAISER_BETA);
+        let correction = WindowCorrection::from_window(&window);
+
+        let freq_bins = 64.0 + bin_offset;
+        let windowed: Vec<f32> = (0..size)
+            .map(|i| {
+                let phase = 2.0 * std::f64::consts::PI * freq_bins * i as f64 / size as f64;
+                phase.sin() as f32 * window[i]
+            })
+            .collect();
+
+        // One-sided spectrum: a sine of amplitude A shows up as A * N / 2
+        dft_bin_magnitude(&windowed, 64.0) * correction.amplitude * 2.0 / size as f32
+    }
+
+    #[test]
+    fn test_rectangular_needs_no_correction() {
+        let window = WindowFunction::Rectangular.generate(512, DEFAULT_KAISER_BETA);
+        let correction = WindowCorrection::from_window(&window);
+
+        assert!((correction.amplitude - 1.0).abs() < 1e-6);
+        assert!((correction.energy - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_hann_corrections() {
+        let window = WindowFunction::Hann.generate(2048, DEFAULT_KAISER_BETA);
+        let correction = WindowCorrection::from_window(&window);
+
+        // Coherent gain 0.5 -> x2, RMS sqrt(3/8) -> x1.633
+        assert!((correction.amplitude - 2.0).abs() < 0.01, "Amplitude was {}", correction.amplitude);
+        assert!((correction.energy - 1.633).abs() < 0.01, "Energy was {}", correction.energy);
+        assert!((WindowFunction::Hann.enbw_bins(DEFAULT_KAISER_BETA) - 1.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_bin_centred_tone_amplitude() {
+        // Every window should report a bin-centred full scale sine as ~1.0
+        for window_fn in WindowFunction::ALL {
+            let amp = measured_amplitude(window_fn, 0.0);
+            assert!((amp - 1.0).abs() < 0.01, "{:?} measured {}", window_fn, amp);
+        }
+    }
+
+    #[test]
+    fn test_flat_top_scalloping() {
+        // Worst case: tone exactly half way between two bins
+        let flat_top_db = 20.0 * measured_amplitude(WindowFunction::FlatTop, 0.5).log10();
+        let hann_db = 20.0 * measured_amplitude(WindowFunction::Hann, 0.5).log10();
+
+        assert!(flat_top_db.abs() < 0.1, "Flat-top scalloping was {:.2} dB", flat_top_db);
+        assert!(hann_db < -1.0, "Hann scalloping should be ~-1.4 dB, was {:.2} dB", hann_db);
+    }
+
+    #[test]
+    fn test_kaiser_beta_zero_is_rectangular() {
+        let window = WindowFunction::Kaiser.generate(256, 0.0);
+        assert!(window.iter().all(|&w| (w - 1.0).abs() < 1e-6));
+
+        // Higher beta tapers the edges harder
+        let soft = WindowFunction::Kaiser.generate(256, 4.0);
+        let hard = WindowFunction::Kaiser.generate(256, 12.0);
+        assert!(hard[0] < soft[0]);
+        assert!((hard[128] - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_blackman_harris_sidelobes() {
+        // 5 bins away from a loud tone, Blackman-Harris leaks far less than Hann
+        let size = 1024;
+        let leakage_db = |window_fn: WindowFunction| {
+            let window = window_fn.generate(size, DEFAULT_KAISER_BETA);
+            let windowed: Vec<f32> = (0..size)
+                .map(|i| {
+                    let phase = 2.0 * std::f64::consts::PI * 64.5 * i as f64 / size as f64;
+                    phase.sin() as f32 * window[i]
+                })
+                .collect();
+            let main = dft_bin_magnitude(&windowed, 64.0);
+            let far = dft_bin_magnitude(&windowed, 70.0);
+            20.0 * (far / main).log10()
+        };
+
+        assert!(leakage_db(WindowFunction::BlackmanHarris) < -90.0);
+        assert!(leakage_db(WindowFunction::Hann) > -60.0);
+    }
+}